@@ -0,0 +1,124 @@
+/// Answers that remember what kind of value they are
+use std::fmt::{Display, Formatter};
+
+use num::BigInt;
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Answer {
+    /// Most answers, like counts and sums
+    Integer(i64),
+    /// Integers that do not fit in an `i64`
+    BigInteger(BigInt),
+    /// Codes like crate labels, step orders and SNAFU numbers
+    Text(String),
+    /// Letters read off a grid drawn by the puzzle
+    Letters(String),
+}
+
+impl Answer {
+    /// Infer the kind of an answer that is only known as text
+    pub fn parse(s: &str) -> Self {
+        if let Ok(v) = s.parse() {
+            Self::Integer(v)
+        } else if let Ok(v) = s.parse() {
+            Self::BigInteger(v)
+        } else {
+            Self::Text(s.to_string())
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Integer(_) => "integer",
+            Self::BigInteger(_) => "big_integer",
+            Self::Text(_) => "text",
+            Self::Letters(_) => "letters",
+        }
+    }
+
+    /// Return `true` if `expected`, e.g. read from a file, is the same answer.
+    ///
+    /// Numbers are compared by value and surrounding whitespace is ignored for every kind.
+    pub fn matches(&self, expected: &str) -> bool {
+        let expected = expected.trim();
+        match self {
+            Self::Integer(v) => expected.parse::<BigInt>() == Ok(BigInt::from(*v)),
+            Self::BigInteger(v) => expected.parse::<BigInt>().as_ref() == Ok(v),
+            Self::Text(v) | Self::Letters(v) => v == expected,
+        }
+    }
+
+    /// Return `true` if the answer is a number that is at most zero
+    pub fn is_non_positive(&self) -> bool {
+        match self {
+            Self::Integer(v) => *v <= 0,
+            Self::BigInteger(v) => *v <= BigInt::from(0),
+            Self::Text(_) | Self::Letters(_) => false,
+        }
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Integer(v) => Display::fmt(v, f),
+            Self::BigInteger(v) => Display::fmt(v, f),
+            Self::Text(v) | Self::Letters(v) => Display::fmt(v, f),
+        }
+    }
+}
+
+/// Serialized as the text of the answer and its kind, so that integers of any size survive
+/// being read by tools that use floats for numbers.
+impl Serialize for Answer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("Answer", 2)?;
+        s.serialize_field("answer", &self.to_string())?;
+        s.serialize_field("answer_kind", self.kind())?;
+        s.end()
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Self::Text(value)
+    }
+}
+
+macro_rules! impl_from_integer {
+    ($($t:ty),+) => {$(
+        impl From<$t> for Answer {
+            fn from(value: $t) -> Self {
+                match i64::try_from(value) {
+                    Ok(v) => Self::Integer(v),
+                    Err(_) => Self::BigInteger(value.into()),
+                }
+            }
+        }
+    )+};
+}
+
+impl_from_integer!(u8, i32, u16, u32, i64, u64, i128, u128, usize);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integers_are_compared_by_value() {
+        assert!(Answer::from(42u32).matches("42\n"));
+        assert!(Answer::from(-7i64).matches("-7"));
+        assert!(!Answer::from(42u32).matches("4 2"));
+        assert!(Answer::from(u64::MAX).matches(&u64::MAX.to_string()));
+        assert!(!Answer::Text("042".into()).matches("42"));
+    }
+
+    #[test]
+    fn kind_is_inferred_from_text() {
+        assert_eq!(Answer::parse("12"), Answer::Integer(12));
+        assert_eq!(Answer::parse(&u64::MAX.to_string()).kind(), "big_integer");
+        assert_eq!(Answer::parse("2=-1=0"), Answer::Text("2=-1=0".into()));
+    }
+}
@@ -1,17 +1,78 @@
 use std::io;
 use std::io::Read;
+use std::path::PathBuf;
+
+use anyhow::bail;
 use structopt::StructOpt;
 
+#[derive(StructOpt)]
+struct Args {
+    #[structopt(flatten)]
+    cli: aoclib::Cli,
+    #[structopt(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(StructOpt)]
+enum Command {
+    /// Solve every file matching the given paths, directories or globs instead of stdin
+    Batch { patterns: Vec<String> },
+    /// Check the answers of every solver against the inputs and answers stored in a dataset
+    Verify {
+        /// Directory laid out like `YYYY/DD/inputs/<stem>.txt` and `YYYY/DD/answers/P/<stem>.txt`
+        #[structopt(default_value = "data")]
+        root: PathBuf,
+    },
+}
+
 fn main() -> anyhow::Result<()> {
     env_logger::init();
 
+    let Args { cli: args, command } = Args::from_args();
+    if args.list {
+        aoclib::list_text(&args)?
+            .iter()
+            .for_each(|line| println!("{}", line));
+        return Ok(());
+    }
+
+    if let Some(Command::Verify { root }) = command {
+        let checks = aoclib::verify(&args, &aoclib::dataset::Dataset::new(root))?;
+        aoclib::verify_text(&checks)
+            .iter()
+            .for_each(|line| println!("{}", line));
+        if checks
+            .iter()
+            .any(|check| check.verdict != aoclib::Verdict::Correct)
+        {
+            bail!("Not every answer is correct");
+        }
+        return Ok(());
+    }
+
+    if let Some(Command::Batch { patterns }) = command {
+        let rows = aoclib::batch(&args, &patterns)?;
+        aoclib::batch_text(&rows)
+            .iter()
+            .for_each(|line| println!("{}", line));
+        let num_unidentified = rows.iter().filter(|row| row.guesses.is_err()).count();
+        if num_unidentified > 0 {
+            bail!(
+                "Could not identify {} of {} files",
+                num_unidentified,
+                rows.len()
+            );
+        }
+        return Ok(());
+    }
+
     let mut text = String::new();
     io::stdin().read_to_string(&mut text)?;
-    let args = aoclib::Cli::from_args();
 
     match aoclib::helper_text(&args, &text) {
-        Ok(lines) => {
+        Ok((lines, notes)) => {
             lines.iter().for_each(|line| println!("{}", line));
+            notes.iter().for_each(|note| eprintln!("{}", note));
             Ok(())
         }
         Err(error) => Err(error),
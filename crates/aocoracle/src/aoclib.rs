@@ -1,22 +1,57 @@
+use std::any::Any;
+use std::cell::Cell;
 use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter};
+use std::panic::{self, AssertUnwindSafe};
 use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
-use anyhow::{anyhow, bail};
-use serde::Deserialize;
+use anyhow::bail;
+use serde::{Deserialize, Serialize};
 use structopt::StructOpt;
 
+mod answer;
+mod batch;
+mod cancel;
+pub mod checked;
+pub mod cycle;
+pub mod dataset;
+pub mod expr;
+mod fingerprint;
+pub mod geom;
+pub mod grid;
+pub mod interval;
 mod itersum;
-mod rect;
+pub mod ocr;
+#[cfg(feature = "parallel")]
+mod parallel;
+pub mod params;
+mod parse;
+mod rank;
+mod registry;
+mod report;
+pub mod schedule;
+pub mod search;
 #[cfg(test)]
 mod testing;
+pub mod trace;
+mod verify;
 mod y2018;
 mod y2020;
 mod y2021;
 mod y2022;
 mod y2023;
 
-#[derive(Eq, Ord, PartialEq, PartialOrd, Clone, Copy, Deserialize)]
+pub use answer::Answer;
+pub use batch::{batch, batch_text, Row};
+pub use fingerprint::Fingerprint;
+pub use rank::Guess;
+pub use report::{Format, Rejection, Report};
+pub use verify::{verify, verify_text, Check, Verdict};
+
+#[derive(Debug, Eq, Ord, PartialEq, PartialOrd, Clone, Copy, Deserialize, Serialize)]
+#[serde(into = "u8")]
 pub enum Part {
     #[serde(alias = "1")]
     One,
@@ -36,6 +71,15 @@ impl TryFrom<u8> for Part {
     }
 }
 
+impl From<Part> for u8 {
+    fn from(value: Part) -> Self {
+        match value {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
 impl FromStr for Part {
     type Err = String;
 
@@ -50,17 +94,11 @@ impl FromStr for Part {
 
 impl Display for Part {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        Display::fmt(
-            &match self {
-                Self::One => 1,
-                Self::Two => 2,
-            },
-            f,
-        )
+        Display::fmt(&u8::from(*self), f)
     }
 }
 
-#[derive(StructOpt)]
+#[derive(Clone, StructOpt)]
 pub struct Cli {
     #[structopt(long)]
     year: Option<u16>,
@@ -70,6 +108,30 @@ pub struct Cli {
     part: Option<Part>,
     #[structopt(long)]
     pub exhaustive: bool,
+    /// List the matching solvers instead of solving anything
+    #[structopt(long)]
+    pub list: bool,
+    /// Number of candidates to try at the same time, 0 means one per CPU
+    #[structopt(long, default_value = "1")]
+    pub jobs: usize,
+    /// Number of seconds after which a candidate is abandoned
+    #[structopt(long)]
+    pub timeout: Option<f64>,
+    /// Try every candidate, not only those whose fingerprint matches the input first
+    #[structopt(long)]
+    pub no_fingerprint: bool,
+    /// Reject inputs that are not exactly in the format of the puzzle, e.g. with leading zeros
+    #[structopt(long)]
+    pub strict: bool,
+    /// One of text, json or jsonl
+    #[structopt(long, default_value = "text")]
+    pub format: Format,
+    /// Include explanations from the solvers that support it
+    #[structopt(long)]
+    pub trace: bool,
+    /// Override a parameter of the solvers, like row=10 when solving an example
+    #[structopt(long = "param", number_of_values = 1)]
+    pub params: Vec<params::Override>,
 }
 
 impl Cli {
@@ -79,163 +141,76 @@ impl Cli {
             day,
             part,
             exhaustive,
+            list: false,
+            jobs: 1,
+            timeout: None,
+            no_fingerprint: false,
+            strict: false,
+            format: Format::Text,
+            trace: false,
+            params: Vec::new(),
         }
     }
-}
 
-pub type AnyError = Box<dyn std::error::Error>;
-type Solver = dyn Fn(&str) -> anyhow::Result<String>;
-
-fn boxed<F>(func: &'static F) -> Box<Solver>
-where
-    F: Fn(&str) -> Result<String, AnyError>,
-{
-    Box::new(move |s| func(s).map_err(|err| anyhow!("{err:}")))
+    fn settings(&self) -> anyhow::Result<Settings> {
+        Ok(Settings {
+            budget: match self.timeout {
+                None => None,
+                Some(seconds) => Some(Duration::try_from_secs_f64(seconds)?),
+            },
+            strict: self.strict,
+            trace: self.trace,
+            params: self.params.clone(),
+        })
+    }
 }
 
-fn boxed2<F, T>(func: &'static F) -> Box<Solver>
-where
-    F: Fn(&str) -> anyhow::Result<T>,
-    T: ToString,
-{
-    Box::new(move |s| func(s).map(|ok| ok.to_string()))
+/// The conditions under which every candidate is attempted
+#[derive(Clone)]
+struct Settings {
+    budget: Option<Duration>,
+    strict: bool,
+    trace: bool,
+    params: Vec<params::Override>,
 }
 
-// TODO: It would be neat if solvers could be registered where they are implemented
-fn _available_solvers() -> BTreeMap<(u16, u8, Part), Box<Solver>> {
-    let mut functions: BTreeMap<_, Box<Solver>> = BTreeMap::new();
-
-    macro_rules! register_solver_old {
-        ($y:literal, $d:literal, $p:expr => $f:expr) => {
-            assert!(functions.insert(($y, $d, $p), boxed($f)).is_none());
-        };
+pub(crate) type AnyError = Box<dyn std::error::Error>;
+
+/// Something that can answer one part of one puzzle
+pub trait Solver: Send + Sync {
+    fn year(&self) -> u16;
+    fn day(&self) -> u8;
+    fn part(&self) -> Part;
+    /// Title of the puzzle, like "Calorie Counting"
+    fn title(&self) -> &'static str;
+    /// Name of the type that the answer has before it is converted to text
+    fn answer_type(&self) -> &'static str;
+    /// Description of what inputs typically look like, if known
+    fn fingerprint(&self) -> Option<&Fingerprint> {
+        None
     }
-
-    macro_rules! register_solver_new {
-        ($y:literal, $d:literal, $p:expr => $f:expr) => {
-            assert!(functions.insert(($y, $d, $p), boxed2($f)).is_none());
-        };
+    /// Return `false` if the input is certainly not meant for this solver.
+    ///
+    /// This should be much cheaper than `solve` and must not reject any valid input.
+    fn accepts(&self, _text: &str) -> bool {
+        true
     }
-
-    // 2018
-    register_solver_old!(2018, 1, Part::One => &y2018::d01::part_1);
-    register_solver_old!(2018, 1, Part::Two => &y2018::d01::part_2);
-    register_solver_old!(2018, 2, Part::One => &y2018::d02::part_1);
-    register_solver_old!(2018, 2, Part::Two => &y2018::d02::part_2);
-    register_solver_old!(2018, 3, Part::One => &y2018::d03::part_1);
-    register_solver_old!(2018, 3, Part::Two => &y2018::d03::part_2);
-    register_solver_new!(2018, 4, Part::One => &y2018::d04::part_1);
-    register_solver_new!(2018, 4, Part::Two => &y2018::d04::part_2);
-    register_solver_new!(2018, 5, Part::One => &y2018::d05::part_1);
-    register_solver_new!(2018, 5, Part::Two => &y2018::d05::part_2);
-    register_solver_new!(2018, 6, Part::One => &y2018::d06::part_1);
-    register_solver_new!(2018, 6, Part::Two => &y2018::d06::part_2b);
-    register_solver_new!(2018, 7, Part::One => &y2018::d07::part_1);
-    register_solver_new!(2018, 7, Part::Two => &y2018::d07::part_2b);
-    register_solver_new!(2018, 8, Part::One => &y2018::d08::part_1);
-    register_solver_new!(2018, 8, Part::Two => &y2018::d08::part_2);
-    // 2020
-    register_solver_old!(2020, 1, Part::One => &y2020::d01::part_1);
-    register_solver_old!(2020, 1, Part::Two => &y2020::d01::part_2);
-    register_solver_old!(2020, 2, Part::One => &y2020::d02::part_1);
-    register_solver_old!(2020, 2, Part::Two => &y2020::d02::part_2);
-    // 2021
-    register_solver_old!(2021, 1, Part::One => &y2021::d01::part_1);
-    register_solver_old!(2021, 1, Part::Two => &y2021::d01::part_2);
-    register_solver_old!(2021, 2, Part::One => &y2021::d02::part_1);
-    register_solver_old!(2021, 2, Part::Two => &y2021::d02::part_2);
-    register_solver_old!(2021, 3, Part::One => &y2021::d03::part_1);
-    register_solver_old!(2021, 3, Part::Two => &y2021::d03::part_2);
-    register_solver_old!(2021, 4, Part::One => &y2021::d04::part_1);
-    register_solver_old!(2021, 4, Part::Two => &y2021::d04::part_2);
-    register_solver_old!(2021, 5, Part::One => &y2021::d05::part_1);
-    register_solver_old!(2021, 5, Part::Two => &y2021::d05::part_2);
-    register_solver_old!(2021, 6, Part::One => &y2021::d06::part_1);
-    register_solver_old!(2021, 6, Part::Two => &y2021::d06::part_2);
-    register_solver_old!(2021, 7, Part::One => &y2021::d07::part_1);
-    register_solver_old!(2021, 7, Part::Two => &y2021::d07::part_2);
-    register_solver_old!(2021, 8, Part::One => &y2021::d08::part_1);
-    register_solver_old!(2021, 8, Part::Two => &y2021::d08::part_2);
-    register_solver_old!(2021, 20, Part::One => &y2021::d20::part_1);
-    register_solver_old!(2021, 20, Part::Two => &y2021::d20::part_2);
-    register_solver_old!(2021, 21, Part::One => &y2021::d21::part_1);
-    register_solver_old!(2021, 21, Part::Two => &y2021::d21::part_2);
-    register_solver_old!(2021, 22, Part::One => &y2021::d22::part_1);
-    register_solver_old!(2021, 22, Part::Two => &y2021::d22::part_2);
-    register_solver_old!(2021, 23, Part::One => &y2021::d23::part_1);
-    register_solver_old!(2021, 23, Part::Two => &y2021::d23::part_2);
-    register_solver_old!(2021, 24, Part::One => &y2021::d24::part_1);
-    register_solver_old!(2021, 24, Part::Two => &y2021::d24::part_2);
-    register_solver_old!(2021, 25, Part::One => &y2021::d25::part_1);
-    // 2022
-    register_solver_new!(2022, 1, Part::One => &y2022::d01::part_1);
-    register_solver_new!(2022, 1, Part::Two => &y2022::d01::part_2);
-    register_solver_new!(2022, 2, Part::One => &y2022::d02::part_1);
-    register_solver_new!(2022, 2, Part::Two => &y2022::d02::part_2);
-    register_solver_new!(2022, 3, Part::One => &y2022::d03::part_1);
-    register_solver_new!(2022, 3, Part::Two => &y2022::d03::part_2);
-    register_solver_new!(2022, 4, Part::One => &y2022::d04::part_1);
-    register_solver_new!(2022, 4, Part::Two => &y2022::d04::part_2);
-    register_solver_new!(2022, 5, Part::One => &y2022::d05::part_1);
-    register_solver_new!(2022, 5, Part::Two => &y2022::d05::part_2);
-    register_solver_new!(2022, 6, Part::One => &y2022::d06::part_1);
-    register_solver_new!(2022, 6, Part::Two => &y2022::d06::part_2);
-    register_solver_new!(2022, 7, Part::One => &y2022::d07::part_1);
-    register_solver_new!(2022, 7, Part::Two => &y2022::d07::part_2);
-    register_solver_new!(2022, 8, Part::One => &y2022::d08::part_1);
-    register_solver_new!(2022, 8, Part::Two => &y2022::d08::part_2);
-    register_solver_new!(2022, 9, Part::One => &y2022::d09::part_1);
-    register_solver_new!(2022, 9, Part::Two => &y2022::d09::part_2);
-    register_solver_new!(2022, 10, Part::One => &y2022::d10::part_1);
-    register_solver_new!(2022, 10, Part::Two => &y2022::d10::part_2);
-    register_solver_new!(2022, 11, Part::One => &y2022::d11::part_1);
-    register_solver_new!(2022, 11, Part::Two => &y2022::d11::part_2);
-    register_solver_new!(2022, 12, Part::One => &y2022::d12::part_1);
-    register_solver_new!(2022, 12, Part::Two => &y2022::d12::part_2);
-    register_solver_new!(2022, 13, Part::One => &y2022::d13::part_1);
-    register_solver_new!(2022, 13, Part::Two => &y2022::d13::part_2);
-    register_solver_new!(2022, 14, Part::One => &y2022::d14::part_1);
-    register_solver_new!(2022, 14, Part::Two => &y2022::d14::part_2);
-    register_solver_new!(2022, 15, Part::One => &y2022::d15::part_1b);
-    register_solver_new!(2022, 15, Part::Two => &y2022::d15::part_2b);
-    register_solver_new!(2022, 17, Part::One => &y2022::d17::part_1);
-    register_solver_new!(2022, 17, Part::Two => &y2022::d17::part_2);
-    register_solver_new!(2022, 18, Part::One => &y2022::d18::part_1);
-    register_solver_new!(2022, 18, Part::Two => &y2022::d18::part_2);
-    register_solver_new!(2022, 20, Part::One => &y2022::d20::part_1);
-    register_solver_new!(2022, 20, Part::Two => &y2022::d20::part_2);
-    register_solver_new!(2022, 21, Part::One => &y2022::d21::part_1);
-    register_solver_new!(2022, 21, Part::Two => &y2022::d21::part_2);
-    register_solver_new!(2022, 23, Part::One => &y2022::d23::part_1);
-    register_solver_new!(2022, 23, Part::Two => &y2022::d23::part_2);
-    register_solver_new!(2022, 24, Part::One => &y2022::d24::part_1);
-    register_solver_new!(2022, 24, Part::Two => &y2022::d24::part_2);
-    register_solver_new!(2022, 25, Part::One => &y2022::d25::part_1);
-    // 2023
-    register_solver_new!(2023, 1, Part::One => &y2023::d01::part_1);
-    register_solver_new!(2023, 1, Part::Two => &y2023::d01::part_2);
-    register_solver_new!(2023, 2, Part::One => &y2023::d02::part_1);
-    register_solver_new!(2023, 2, Part::Two => &y2023::d02::part_2);
-    register_solver_new!(2023, 3, Part::One => &y2023::d03::part_1);
-    register_solver_new!(2023, 3, Part::Two => &y2023::d03::part_2);
-    register_solver_new!(2023, 4, Part::One => &y2023::d04::part_1);
-    register_solver_new!(2023, 4, Part::Two => &y2023::d04::part_2);
-    register_solver_new!(2023, 5, Part::One => &y2023::d05::part_1);
-    register_solver_new!(2023, 5, Part::Two => &y2023::d05::part_2);
-    register_solver_new!(2023, 6, Part::One => &y2023::d06::part_1);
-    register_solver_new!(2023, 6, Part::Two => &y2023::d06::part_2);
-    register_solver_new!(2023, 7, Part::One => &y2023::d07::part_1);
-    register_solver_new!(2023, 7, Part::Two => &y2023::d07::part_2);
-    register_solver_new!(2023, 8, Part::One => &y2023::d08::part_1);
-    register_solver_new!(2023, 8, Part::Two => &y2023::d08::part_2);
-    register_solver_new!(2023, 9, Part::One => &y2023::d09::part_1);
-    register_solver_new!(2023, 9, Part::Two => &y2023::d09::part_2);
-
-    functions
+    /// Numbers that the solver reads from the puzzle text rather than from the input
+    fn params(&self) -> &[params::Param] {
+        &[]
+    }
+    /// Description of what the example in the puzzle text looks like, if it needs other params
+    fn example(&self) -> Option<&Fingerprint> {
+        None
+    }
+    fn solve(&self, text: &str) -> anyhow::Result<Answer>;
 }
 
-fn _candidates(args: &Cli) -> anyhow::Result<BTreeMap<(u16, u8, Part), Box<Solver>>> {
-    let mut functions = _available_solvers();
+pub type Solvers = BTreeMap<(u16, u8, Part), Box<dyn Solver>>;
+
+/// Return the solvers that match the year, day and part in `args`
+pub fn candidates(args: &Cli) -> anyhow::Result<Solvers> {
+    let mut functions = registry::available_solvers();
     let mut result = BTreeMap::new();
     let parts: Vec<Part> = match args.part {
         None => vec![Part::One, Part::Two],
@@ -273,43 +248,323 @@ fn _candidates(args: &Cli) -> anyhow::Result<BTreeMap<(u16, u8, Part), Box<Solve
     Ok(result)
 }
 
-pub fn helper(args: &Cli, text: &str) -> anyhow::Result<BTreeMap<(u16, u8, Part), String>> {
-    let candidates = _candidates(args)?;
+enum Attempt {
+    Rejected,
+    Solved {
+        answer: Answer,
+        mode: params::Mode,
+        elapsed: Duration,
+        trace: Vec<trace::Event>,
+    },
+    Failed(anyhow::Error),
+    TimedOut,
+    Panicked(String),
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&str>() {
+            Ok(message) => message.to_string(),
+            Err(_) => "Unknown panic".to_string(),
+        },
+    }
+}
+
+type PanicHook = Box<dyn Fn(&panic::PanicInfo<'_>) + Sync + Send + 'static>;
+
+thread_local! {
+    static QUIET: Cell<bool> = Cell::new(false);
+}
+
+/// Number of threads running `quietly` and the hook that was in place before the first began
+static HOOK: Mutex<(usize, Option<Arc<PanicHook>>)> = Mutex::new((0, None));
+
+/// Run `func` with panics on this thread logged instead of printed by the panic hook.
+///
+/// The hook is global, so it is replaced when the first thread begins and restored when the
+/// last thread ends, and panics on other threads still reach the previous hook.
+fn quietly<T>(func: impl FnOnce() -> T) -> T {
+    {
+        let mut hook = HOOK.lock().expect("Lock is never held while panicking");
+        if hook.0 == 0 {
+            let previous = Arc::new(panic::take_hook());
+            hook.1 = Some(previous.clone());
+            panic::set_hook(Box::new(move |info| match QUIET.with(Cell::get) {
+                true => log::debug!("Candidate {}", info),
+                false => previous(info),
+            }));
+        }
+        hook.0 += 1;
+    }
+    let was_quiet = QUIET.with(|q| q.replace(true));
+    let result = func();
+    QUIET.with(|q| q.set(was_quiet));
+    {
+        let mut hook = HOOK.lock().expect("Lock is never held while panicking");
+        hook.0 -= 1;
+        if hook.0 == 0 {
+            // Dropping the current hook releases its reference to the previous one
+            drop(panic::take_hook());
+            let previous = hook
+                .1
+                .take()
+                .expect("Previous hook is kept while threads run");
+            match Arc::try_unwrap(previous) {
+                Ok(previous) => panic::set_hook(previous),
+                Err(previous) => panic::set_hook(Box::new(move |info| previous(info))),
+            }
+        }
+    }
+    result
+}
+
+fn attempt(solver: &dyn Solver, text: &str, settings: &Settings) -> Attempt {
+    let (year, day, part) = (solver.year(), solver.day(), solver.part());
+    if !solver.accepts(text) {
+        log::debug!("Skipping year {} day {} part {}", year, day, part);
+        return Attempt::Rejected;
+    }
+    log::debug!("Trying year {} day {} part {}", year, day, part);
+    // Parameters given by the caller take precedence over those implied by the input
+    let mut example = match solver.example() {
+        Some(fingerprint) if fingerprint.matches(text) => {
+            params::example_overrides(solver.params())
+        }
+        _ => Vec::new(),
+    };
+    example.retain(|e| settings.params.iter().all(|o| o.name != e.name));
+    let mut overrides = example.clone();
+    overrides.extend(settings.params.iter().cloned());
+    let start = cancel::now();
+    // Unwinding is caught as close to the solver as possible so that the state of the cancel,
+    // parse and params modules is restored. On targets where panics abort, like wasm32, the
+    // caller must isolate solvers instead, as the webapp does by running them in a worker.
+    let solve = || quietly(|| panic::catch_unwind(AssertUnwindSafe(|| solver.solve(text))));
+    let (((result, used), trace), expired) = cancel::with_budget(settings.budget, || {
+        trace::with_trace(settings.trace, || {
+            parse::with_strict(settings.strict, || {
+                params::with_overrides(&overrides, || params::with_usage(solve))
+            })
+        })
+    });
+    // Only the parts that read a parameter with a different value for the example are affected
+    let mode = match example.iter().any(|e| used.contains(&e.name.as_str())) {
+        true => params::Mode::Example,
+        false => params::Mode::Real,
+    };
+    match (result, expired) {
+        (Ok(Ok(answer)), _) => Attempt::Solved {
+            answer,
+            mode,
+            elapsed: cancel::now() - start,
+            trace,
+        },
+        (Ok(Err(_)), true) => Attempt::TimedOut,
+        (Ok(Err(error)), false) => Attempt::Failed(error),
+        (Err(payload), _) => Attempt::Panicked(panic_message(payload)),
+    }
+}
+
+#[cfg(feature = "parallel")]
+fn attempt_all<'a>(
+    solvers: Vec<&'a dyn Solver>,
+    text: &'a str,
+    args: &Cli,
+) -> anyhow::Result<Box<dyn Iterator<Item = Attempt> + 'a>> {
+    let settings = args.settings()?;
+    let jobs = match args.jobs {
+        0 => std::thread::available_parallelism()?.get(),
+        jobs => jobs,
+    };
+    Ok(match jobs {
+        1 => Box::new(
+            solvers
+                .into_iter()
+                .map(move |s| attempt(s, text, &settings)),
+        ),
+        _ => Box::new(parallel::attempt_all(&solvers, text, jobs, &settings).into_iter()),
+    })
+}
+
+#[cfg(not(feature = "parallel"))]
+fn attempt_all<'a>(
+    solvers: Vec<&'a dyn Solver>,
+    text: &'a str,
+    args: &Cli,
+) -> anyhow::Result<Box<dyn Iterator<Item = Attempt> + 'a>> {
+    let settings = args.settings()?;
+    if args.jobs != 1 {
+        bail!("Trying more than one candidate at a time requires the parallel feature");
+    }
+    Ok(Box::new(
+        solvers
+            .into_iter()
+            .map(move |s| attempt(s, text, &settings)),
+    ))
+}
+
+/// Split candidates into those that should be tried first and those that should be tried only
+/// if none of the first produce an answer.
+pub fn stages<'a>(args: &Cli, candidates: &'a Solvers, text: &str) -> [Vec<&'a dyn Solver>; 2] {
+    let (plausible, implausible) = candidates.values().map(|s| s.as_ref()).partition(|s| {
+        args.day.is_some()
+            || args.no_fingerprint
+            || s.fingerprint().map_or(true, |f| f.matches(text))
+    });
+    [plausible, implausible]
+}
+
+/// Try the candidates and account for every one of them
+pub fn report(args: &Cli, text: &str) -> anyhow::Result<Report> {
+    let candidates = candidates(args)?;
     if candidates.is_empty() {
         bail!("Invalid combination of year, day and part");
     }
+    // A misspelled parameter would otherwise silently leave the default in place
+    for o in &args.params {
+        if !candidates
+            .values()
+            .any(|s| s.params().iter().any(|p| p.name == o.name))
+        {
+            bail!("No candidate has a parameter named {:?}", o.name);
+        }
+    }
 
-    let mut result = BTreeMap::new();
-    for ((year, day, part), func) in candidates.iter() {
-        log::debug!("Trying year {} day {} part {}", year, day, part);
-        match func(text) {
-            Ok(output) => {
-                if !args.exhaustive && !result.is_empty() {
-                    bail!("Found more than 1 possible answer");
+    let mut result = Report::default();
+    for solvers in stages(args, &candidates, text) {
+        if !result.answers.is_empty() {
+            break;
+        }
+        let attempts = attempt_all(solvers.clone(), text, args)?;
+        for (solver, attempt) in solvers.into_iter().zip(attempts) {
+            let (year, day, part) = (solver.year(), solver.day(), solver.part());
+            let (error, panicked) = match attempt {
+                Attempt::Rejected => {
+                    if args.day.is_some() {
+                        bail!("Input is not accepted by {}", solver.title());
+                    }
+                    ("Input is not accepted".to_string(), false)
                 }
-                let entry = result.entry((*year, *day)).or_insert_with(BTreeMap::new);
-                entry.insert(*part, output);
-            }
-            Err(error) => match args.day {
-                None => log::debug!("{}", error),
-                Some(_) => return Err(error),
-            },
+                Attempt::Solved {
+                    answer,
+                    mode,
+                    elapsed,
+                    trace,
+                } => {
+                    result.answers.push(Guess {
+                        year,
+                        day,
+                        part,
+                        confidence: rank::confidence(solver, text, &answer),
+                        answer,
+                        mode,
+                        elapsed,
+                        trace,
+                    });
+                    continue;
+                }
+                Attempt::Failed(error) => match args.day {
+                    None => {
+                        // The whole chain, since the outermost context alone rarely says why
+                        log::debug!("{:#}", error);
+                        (format!("{:#}", error), false)
+                    }
+                    Some(_) => return Err(error),
+                },
+                Attempt::TimedOut => match args.day {
+                    None => {
+                        log::warn!("Year {} day {} part {} timed out", year, day, part);
+                        ("Timed out".to_string(), false)
+                    }
+                    Some(_) => bail!("Timed out"),
+                },
+                Attempt::Panicked(message) => match args.day {
+                    None => {
+                        log::warn!("Year {} day {} part {} panicked", year, day, part);
+                        (message, true)
+                    }
+                    Some(_) => bail!("Solver panicked: {}", message),
+                },
+            };
+            result.rejected.push(Rejection {
+                year,
+                day,
+                part,
+                error,
+                panicked,
+            });
         }
     }
-    Ok(result
-        .into_iter()
-        .flat_map(|((y, d), vs)| vs.into_iter().map(move |(p, v)| ((y, d, p), v)))
+    result.answers = rank::ranked(result.answers);
+    Ok(result)
+}
+
+/// Return every answer that could be found, with days ranked from most to least likely to be right
+pub fn helper(args: &Cli, text: &str) -> anyhow::Result<Vec<Guess>> {
+    Ok(report(args, text)?.answers)
+}
+
+pub fn list_text(args: &Cli) -> anyhow::Result<Vec<String>> {
+    Ok(candidates(args)?
+        .values()
+        .map(|s| {
+            let mut line = format!(
+                "{:04}:{:02}::{:01} {} ({})",
+                s.year(),
+                s.day(),
+                s.part(),
+                s.title(),
+                s.answer_type()
+            );
+            for p in s.params() {
+                line.push_str(&format!(" [{p}: {}]", p.description));
+            }
+            line
+        })
         .collect())
 }
 
-pub fn helper_text(args: &Cli, text: &str) -> anyhow::Result<Vec<String>> {
-    let structured = helper(args, text)?;
-    Ok(match args.exhaustive {
-        false => structured.values().map(|v| v.to_string()).collect(),
-        true => structured
+/// Return the lines to print to stdout followed by the lines to print to stderr
+pub fn helper_text(args: &Cli, text: &str) -> anyhow::Result<(Vec<String>, Vec<String>)> {
+    match args.format {
+        Format::Text => {}
+        Format::Json => return Ok((vec![report(args, text)?.to_json()?], Vec::new())),
+        Format::Jsonl => return Ok((report(args, text)?.to_jsonl()?, Vec::new())),
+    }
+    let guesses = helper(args, text)?;
+    let line = |g: &Guess| format!("{:04}:{:02}::{:01} = {}", g.year, g.day, g.part, g.answer);
+    // Modes and traces go with the notes so that they never mix with the answers
+    let traces = |guesses: &[Guess]| -> Vec<String> {
+        guesses
             .iter()
-            .map(|((y, d, p), v)| format!("{y:04}:{d:02}::{p:01} = {v}"))
-            .collect(),
+            .flat_map(|g| {
+                let mode = (g.mode == params::Mode::Example)
+                    .then(|| format!("Input of {} looks like the example", line(g)));
+                let trace = (!g.trace.is_empty())
+                    .then(|| format!("Trace of {}", line(g)))
+                    .into_iter()
+                    .chain(g.trace.iter().map(|e| e.to_string()));
+                mode.into_iter().chain(trace)
+            })
+            .collect()
+    };
+    Ok(match args.exhaustive {
+        false => match rank::split_best(&guesses) {
+            ([], _) => bail!("Found no answer"),
+            (best, runners_up) => (
+                best.iter().map(|g| g.answer.to_string()).collect(),
+                traces(best)
+                    .into_iter()
+                    .chain(
+                        runners_up
+                            .iter()
+                            .map(|g| format!("Runner-up: {} ({:.2})", line(g), g.confidence)),
+                    )
+                    .collect(),
+            ),
+        },
+        true => (guesses.iter().map(line).collect(), traces(&guesses)),
     })
 }
 
@@ -318,6 +573,8 @@ mod tests {
     use super::*;
     use crate::testing::{available_inputs, expected_answer, read_input};
     use itertools::Itertools;
+    use std::cmp::Reverse;
+    use std::collections::BTreeSet;
     use std::fmt::Display;
     use std::panic;
 
@@ -390,6 +647,117 @@ mod tests {
         Ok(result)
     }
 
+    struct Panicking;
+
+    impl Solver for Panicking {
+        fn year(&self) -> u16 {
+            2022
+        }
+        fn day(&self) -> u8 {
+            1
+        }
+        fn part(&self) -> Part {
+            Part::One
+        }
+        fn title(&self) -> &'static str {
+            "Broken"
+        }
+        fn answer_type(&self) -> &'static str {
+            "String"
+        }
+        fn solve(&self, text: &str) -> anyhow::Result<Answer> {
+            Ok(text.lines().next().unwrap().to_string().into())
+        }
+    }
+
+    #[test]
+    fn panic_in_solver_becomes_attempt() {
+        let settings = Settings {
+            budget: Some(Duration::from_secs(1)),
+            strict: true,
+            trace: true,
+            params: vec![params::Override::new("row", 10)],
+        };
+        let message = match attempt(&Panicking, "", &settings) {
+            Attempt::Panicked(message) => message,
+            _ => panic!("Expected attempt to panic"),
+        };
+        assert!(message.contains("unwrap"));
+        assert!(!QUIET.with(Cell::get));
+        assert!(cancel::checkpoint().is_ok());
+        assert!(!parse::is_strict());
+        assert!(!trace::is_enabled());
+        assert_eq!(params::Param::new("row", 2000000, "").get(), 2000000);
+    }
+
+    #[test]
+    fn params_are_passed_to_solvers() {
+        let text = read_input(2022, 15, "EXAMPLE");
+        let mut args = Cli::new(Some(2022), Some(15), Some(Part::One), false);
+        args.params = vec!["row=10".parse().unwrap()];
+        let guesses = helper(&args, &text).unwrap();
+        let expected = expected_answer(2022, 15, Part::One, "EXAMPLE").unwrap();
+        assert!(guesses[0].answer.matches(&expected));
+
+        args.params = vec!["rows=10".parse().unwrap()];
+        assert!(helper(&args, &text).is_err());
+    }
+
+    #[test]
+    fn example_params_are_used_on_examples() {
+        let text = read_input(2022, 15, "EXAMPLE");
+        let args = Cli::new(Some(2022), Some(15), None, true);
+        let guesses = helper(&args, &text).unwrap();
+        for g in guesses {
+            let expected = expected_answer(2022, 15, g.part, "EXAMPLE").unwrap();
+            assert!(g.answer.matches(&expected));
+            assert_eq!(g.mode, params::Mode::Example);
+        }
+    }
+
+    #[test]
+    fn rejections_include_the_cause() {
+        let args = Cli::new(Some(2023), None, Some(Part::One), false);
+        let report = report(&args, "Card x: 1 | 2\n").unwrap();
+        let rejection = report
+            .rejected
+            .iter()
+            .find(|r| r.day == 4)
+            .expect("Day 4 rejects the input");
+        assert_eq!(rejection.error, "\"Card x\": invalid digit found in string");
+    }
+
+    #[test]
+    fn every_part_of_the_best_day_is_printed() {
+        let text = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000\n";
+        let args = Cli::new(Some(2022), None, None, false);
+        let (lines, notes) = helper_text(&args, text).unwrap();
+        assert_eq!(lines, vec!["24000", "45000"]);
+        assert!(notes.iter().all(|n| !n.contains("2022:01")));
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_answers_are_identical_to_sequential_answers() {
+        for (year, day, stem) in available_inputs() {
+            let text = read_input(year, day, &stem);
+            // Elapsed times differ between runs so only compare the rest
+            let answers = |args: &Cli| {
+                helper(args, &text).ok().map(|guesses| {
+                    guesses
+                        .into_iter()
+                        .map(|g| (g.year, g.day, g.part, g.answer, g.confidence))
+                        .collect::<Vec<_>>()
+                })
+            };
+            let mut args = Cli::new(None, None, None, true);
+            let expected = answers(&args);
+            args.jobs = 4;
+            let actual = answers(&args);
+            assert_eq!(actual, expected, "{year}:{day}:{stem}");
+        }
+    }
+
     #[test]
     fn every_input_is_solved_by_exactly_one_solver() {
         let mut cols = BTreeMap::new();
@@ -401,28 +769,82 @@ mod tests {
         let mut num_unknown = 0;
         let mut num_error = 0;
         let mut num_panic = 0;
+        // How well fingerprints alone identify the day of an input
+        let mut num_fingerprint_unique = 0;
+        let mut num_fingerprint_ambiguous = 0;
+        let mut num_fingerprint_miss = 0;
+        let mut num_fingerprint_insertion = 0;
+        // Which solvers give answers for inputs meant for other days, with and without strict
+        // parsing, so that they can be tightened one at a time
+        let mut insertions_by_solver: BTreeMap<String, (usize, usize)> = BTreeMap::new();
         for (input_year, input_day, stem) in available_inputs() {
             let input_key = || format!("{input_year:04}:{input_day:02}:{stem:7}");
-            let actual_answers = panic::catch_unwind(|| {
-                helper(
-                    &Cli::new(None, None, None, true),
-                    &read_input(input_year, input_day, &stem),
-                )
-            });
-            let mut actual_answers = match actual_answers {
-                Ok(Ok(a)) => a,
-                Ok(Err(_)) => {
-                    num_error += 1;
-                    continue;
+            let text = read_input(input_year, input_day, &stem);
+            let matching_days: BTreeSet<_> = registry::available_solvers()
+                .values()
+                .filter(|s| s.fingerprint().map_or(true, |f| f.matches(&text)))
+                .map(|s| (s.year(), s.day()))
+                .collect();
+            match (
+                matching_days.contains(&(input_year, input_day)),
+                matching_days.len(),
+            ) {
+                (false, _) => num_fingerprint_miss += 1,
+                (true, 1) => num_fingerprint_unique += 1,
+                (true, _) => num_fingerprint_ambiguous += 1,
+            }
+            if let Ok(Ok(answers)) =
+                panic::catch_unwind(|| helper(&Cli::new(None, None, None, true), &text))
+            {
+                num_fingerprint_insertion += answers
+                    .iter()
+                    .filter(|g| (g.year, g.day) != (input_year, input_day))
+                    .count();
+            }
+
+            if let Ok(Ok(answers)) = panic::catch_unwind(|| {
+                let mut args = Cli::new(None, None, None, true);
+                args.no_fingerprint = true;
+                args.strict = true;
+                helper(&args, &text)
+            }) {
+                for g in answers {
+                    if (g.year, g.day) != (input_year, input_day) {
+                        let key = format!("{:04}:{:02}::{}", g.year, g.day, g.part);
+                        insertions_by_solver.entry(key).or_default().1 += 1;
+                    }
                 }
-                Err(_) => {
+            }
+
+            let mut args = Cli::new(None, None, None, true);
+            args.no_fingerprint = true;
+            let (mut actual_answers, panicked): (BTreeMap<_, _>, BTreeSet<_>) =
+                match report(&args, &text) {
+                    Ok(r) => (
+                        r.answers
+                            .into_iter()
+                            .map(|g| ((g.year, g.day, g.part), g.answer))
+                            .collect(),
+                        r.rejected
+                            .into_iter()
+                            .filter(|r| r.panicked)
+                            .map(|r| (r.year, r.day, r.part))
+                            .collect(),
+                    ),
+                    Err(_) => {
+                        num_error += 1;
+                        continue;
+                    }
+                };
+            // Assume that if it is not implemented it will not be among actual_answers
+            for (solver_year, solver_day, part) in registry::available_solvers().into_keys() {
+                let solver_key = || format!("{solver_year}:{solver_day:02}::{part}");
+                if panicked.contains(&(solver_year, solver_day, part)) {
                     num_panic += 1;
+                    let entry = cols.entry(solver_key()).or_insert_with(BTreeMap::new);
+                    entry.insert(input_key(), "!".into());
                     continue;
                 }
-            };
-            // Assume that if it is not implemented it will not be among actual_answers
-            for (solver_year, solver_day, part) in _available_solvers().into_keys() {
-                let solver_key = || format!("{solver_year}:{solver_day:02}::{part}");
                 let correct_key = input_year == solver_year && input_day == solver_day;
                 let expected_answer = expected_answer(input_year, input_day, part, &stem);
                 let actual_answer = actual_answers.remove(&(solver_year, solver_day, part));
@@ -432,9 +854,10 @@ mod tests {
                     }
                     (false, Some(a), _) => {
                         num_insertion += 1;
+                        insertions_by_solver.entry(solver_key()).or_default().0 += 1;
                         let entry = cols.entry(solver_key()).or_insert_with(BTreeMap::new);
                         assert_eq!(
-                            entry.insert(input_key(), a),
+                            entry.insert(input_key(), a.to_string()),
                             None,
                             "Expected every input-solver pair to occur at most once"
                         );
@@ -454,14 +877,14 @@ mod tests {
                     (true, Some(_), None) => {
                         num_unknown += 1;
                     }
-                    (true, Some(a), Some(e)) if a == e => {
+                    (true, Some(a), Some(e)) if a.matches(&e) => {
                         num_correct += 1;
                     }
                     (true, Some(a), Some(_)) => {
                         num_substitution += 1;
                         let entry = cols.entry(solver_key()).or_insert_with(BTreeMap::new);
                         assert_eq!(
-                            entry.insert(input_key(), a),
+                            entry.insert(input_key(), a.to_string()),
                             None,
                             "Expected every input-solver pair to occur at most once"
                         );
@@ -477,8 +900,12 @@ mod tests {
         dbg!(num_unknown);
         dbg!(num_error);
         dbg!(num_panic);
+        dbg!(num_fingerprint_unique);
+        dbg!(num_fingerprint_ambiguous);
+        dbg!(num_fingerprint_miss);
+        dbg!(num_fingerprint_insertion);
         let num_input = available_inputs().len();
-        let num_solver = _available_solvers().len();
+        let num_solver = registry::available_solvers().len();
         let num_pair = num_correct
             + num_deletion
             + num_insertion
@@ -486,11 +913,18 @@ mod tests {
             + num_true_negative
             + num_unknown
             + num_error * num_solver
-            + num_panic * num_solver;
+            + num_panic;
         dbg!(num_input);
         dbg!(num_solver);
         dbg!(num_pair);
         println!("{}", as_ascii_table(&cols).unwrap());
+        println!("insertions by solver (default, strict):");
+        for (solver_key, (default, strict)) in insertions_by_solver
+            .iter()
+            .sorted_by_key(|(k, (d, s))| (Reverse(*d), Reverse(*s), *k))
+        {
+            println!("{solver_key} {default:>4} {strict:>4}");
+        }
         println!(
             "word error rate: {}",
             (num_deletion + num_insertion + num_substitution) as f64
@@ -0,0 +1,167 @@
+/// Solving many inputs in one go
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail};
+
+use crate::params::Mode;
+use crate::{rank, report, Cli, Guess};
+
+/// The outcome of solving one file
+pub struct Row {
+    pub path: PathBuf,
+    /// Every part of the best-ranked day, or why none was found
+    pub guesses: anyhow::Result<Vec<Guess>>,
+}
+
+/// Return the files that `pattern` refers to, sorted.
+///
+/// A directory refers to every `.txt` file below it.
+fn expand(pattern: &str) -> anyhow::Result<Vec<PathBuf>> {
+    let pattern = match Path::new(pattern).is_dir() {
+        true => format!("{}/**/*.txt", pattern.trim_end_matches('/')),
+        false => pattern.to_string(),
+    };
+    let mut result = glob::glob(&pattern)?.collect::<Result<Vec<_>, _>>()?;
+    result.retain(|p| p.is_file());
+    if result.is_empty() {
+        bail!("Expected at least one file matching {pattern}");
+    }
+    result.sort();
+    Ok(result)
+}
+
+fn solve(args: &Cli, path: &Path) -> anyhow::Result<Vec<Guess>> {
+    let text = fs::read_to_string(path)?;
+    let answers = report(args, &text)?.answers;
+    match rank::split_best(&answers) {
+        ([], _) => Err(anyhow!("Found no answer")),
+        (best, _) => Ok(best.to_vec()),
+    }
+}
+
+/// Solve every file matching any of `patterns`
+pub fn batch(args: &Cli, patterns: &[String]) -> anyhow::Result<Vec<Row>> {
+    let mut result = Vec::new();
+    for pattern in patterns {
+        for path in expand(pattern)? {
+            log::info!("Solving {}", path.display());
+            let guesses = solve(args, &path);
+            result.push(Row { path, guesses });
+        }
+    }
+    Ok(result)
+}
+
+/// Format rows as a table with one line per part, or per file if none was found, preceded by
+/// a header
+pub fn batch_text(rows: &[Row]) -> Vec<String> {
+    let cells: Vec<[String; 3]> = rows
+        .iter()
+        .flat_map(|row| {
+            let path = row.path.display().to_string();
+            match &row.guesses {
+                Ok(guesses) => guesses
+                    .iter()
+                    .map(|g| {
+                        [
+                            path.clone(),
+                            format!("{:04}:{:02}::{:01}", g.year, g.day, g.part),
+                            match g.mode {
+                                Mode::Real => g.answer.to_string(),
+                                Mode::Example => format!("{} (example)", g.answer),
+                            },
+                        ]
+                    })
+                    .collect(),
+                Err(e) => vec![[path, "-".to_string(), e.to_string()]],
+            }
+        })
+        .collect();
+    let header = [
+        "FILE".to_string(),
+        "PUZZLE".to_string(),
+        "ANSWER".to_string(),
+    ];
+    let width = |i: usize| {
+        cells
+            .iter()
+            .chain([&header])
+            .map(|c| c[i].chars().count())
+            .max()
+            .unwrap_or_default()
+    };
+    let (w0, w1) = (width(0), width(1));
+    [&header]
+        .into_iter()
+        .chain(cells.iter())
+        .map(|[a, b, c]| format!("{a:w0$}  {b:w1$}  {c}"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Answer, Part};
+
+    fn guess(part: Part, answer: i64) -> Guess {
+        Guess {
+            year: 2021,
+            day: 1,
+            part,
+            answer: Answer::Integer(answer),
+            confidence: 1.0,
+            mode: Mode::Example,
+            elapsed: Default::default(),
+            trace: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn batch_text_is_aligned() {
+        let rows = vec![
+            Row {
+                path: "a/long/path.txt".into(),
+                guesses: Ok(vec![guess(Part::One, 7), guess(Part::Two, 5)]),
+            },
+            Row {
+                path: "b.txt".into(),
+                guesses: Err(anyhow!("Found no answer")),
+            },
+        ];
+        assert_eq!(
+            batch_text(&rows),
+            vec![
+                "FILE             PUZZLE      ANSWER",
+                "a/long/path.txt  2021:01::1  7 (example)",
+                "a/long/path.txt  2021:01::2  5 (example)",
+                "b.txt            -           Found no answer",
+            ]
+        );
+    }
+
+    #[test]
+    fn every_part_of_the_best_day_is_solved() {
+        let dir = std::env::temp_dir().join(format!("aocoracle-batch-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("input.txt");
+        fs::write(&path, "+1\n-2\n+3\n+1\n").unwrap();
+        let args = Cli::new(Some(2018), Some(1), None, false);
+        let guesses = solve(&args, &path);
+        fs::remove_dir_all(&dir).unwrap();
+        let actual: Vec<_> = guesses
+            .unwrap()
+            .into_iter()
+            .map(|g| (g.part, g.answer.to_string()))
+            .collect();
+        assert_eq!(
+            actual,
+            vec![(Part::One, "3".to_string()), (Part::Two, "2".to_string())]
+        );
+    }
+
+    #[test]
+    fn expand_fails_when_nothing_matches() {
+        assert!(expand("no/such/*.txt").is_err());
+    }
+}
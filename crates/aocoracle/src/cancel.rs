@@ -0,0 +1,103 @@
+/// Cooperative cancellation of solvers that take too long
+///
+/// Solvers cannot be interrupted from the outside, not least in the single threaded webapp, so
+/// long running loops should call `checkpoint` regularly and give up when it returns an error.
+use std::cell::Cell;
+use std::time::Duration;
+
+use thiserror::Error;
+
+/// Number of calls to `checkpoint` between every time the clock is read
+const CALLS_PER_READING: u32 = 1024;
+
+#[derive(Debug, Error)]
+#[error("Timed out")]
+pub struct TimedOut;
+
+thread_local! {
+    static DEADLINE: Cell<Option<Duration>> = Cell::new(None);
+    static EXPIRED: Cell<bool> = Cell::new(false);
+    static CALLS: Cell<u32> = Cell::new(0);
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn now() -> Duration {
+    use std::sync::OnceLock;
+    use std::time::Instant;
+    static START: OnceLock<Instant> = OnceLock::new();
+    START.get_or_init(Instant::now).elapsed()
+}
+
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn now() -> Duration {
+    // `Instant` panics on wasm32-unknown-unknown
+    Duration::from_secs_f64(js_sys::Date::now() / 1000.0)
+}
+
+/// Return an error if the solver running on this thread has exceeded its budget
+pub fn checkpoint() -> Result<(), TimedOut> {
+    if EXPIRED.with(Cell::get) {
+        return Err(TimedOut);
+    }
+    let deadline = match DEADLINE.with(Cell::get) {
+        Some(deadline) => deadline,
+        None => return Ok(()),
+    };
+    let calls = CALLS.with(|c| {
+        c.set(c.get().wrapping_add(1));
+        c.get()
+    });
+    if calls % CALLS_PER_READING == 0 && deadline <= now() {
+        EXPIRED.with(|e| e.set(true));
+        return Err(TimedOut);
+    }
+    Ok(())
+}
+
+pub fn is_cancelled() -> bool {
+    checkpoint().is_err()
+}
+
+/// Run `func` with a `budget`, and report whether it was exceeded.
+pub fn with_budget<T>(budget: Option<Duration>, func: impl FnOnce() -> T) -> (T, bool) {
+    DEADLINE.with(|d| d.set(budget.map(|b| now() + b)));
+    EXPIRED.with(|e| e.set(false));
+    CALLS.with(|c| c.set(0));
+    let result = func();
+    let expired = EXPIRED.with(Cell::get)
+        || DEADLINE
+            .with(Cell::get)
+            .map_or(false, |deadline| deadline <= now());
+    DEADLINE.with(|d| d.set(None));
+    EXPIRED.with(|e| e.set(false));
+    (result, expired)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checkpoint_fails_after_budget_is_exceeded() {
+        let (calls, expired) = with_budget(Some(Duration::from_millis(10)), || {
+            let mut calls = 0u64;
+            while checkpoint().is_ok() {
+                calls += 1;
+            }
+            calls
+        });
+        assert!(expired);
+        assert!(0 < calls);
+        assert!(checkpoint().is_ok());
+    }
+
+    #[test]
+    fn checkpoint_never_fails_without_budget() {
+        let (_, expired) = with_budget(None, || {
+            for _ in 0..10 * CALLS_PER_READING {
+                checkpoint().unwrap();
+            }
+        });
+        assert!(!expired);
+    }
+}
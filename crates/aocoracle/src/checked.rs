@@ -0,0 +1,139 @@
+/// Arithmetic that reports overflow and division by zero as errors
+///
+/// Numbers parsed from an input can be as large as the type they are parsed into, so combining
+/// them with the operators can overflow. That panics in debug builds, aborts the webapp, and
+/// silently gives a wrong answer in release builds, so solvers should use these operations on
+/// anything that the input controls.
+use std::fmt::Display;
+
+use num::traits::CheckedRem;
+use num::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, One, Zero};
+use thiserror::Error;
+
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum ArithmeticError {
+    #[error("Could not compute {0} because it overflows")]
+    Overflow(String),
+    #[error("Could not compute {0} because it divides by zero")]
+    DivisionByZero(String),
+}
+
+fn overflow(lhs: impl Display, op: &str, rhs: impl Display) -> ArithmeticError {
+    ArithmeticError::Overflow(format!("{lhs} {op} {rhs}"))
+}
+
+pub trait Checked:
+    Copy + Display + CheckedAdd + CheckedSub + CheckedMul + CheckedDiv + One + Zero
+{
+    fn try_add(self, rhs: Self) -> Result<Self, ArithmeticError> {
+        self.checked_add(&rhs)
+            .ok_or_else(|| overflow(self, "+", rhs))
+    }
+
+    fn try_sub(self, rhs: Self) -> Result<Self, ArithmeticError> {
+        self.checked_sub(&rhs)
+            .ok_or_else(|| overflow(self, "-", rhs))
+    }
+
+    fn try_mul(self, rhs: Self) -> Result<Self, ArithmeticError> {
+        self.checked_mul(&rhs)
+            .ok_or_else(|| overflow(self, "*", rhs))
+    }
+
+    fn try_div(self, rhs: Self) -> Result<Self, ArithmeticError> {
+        if rhs.is_zero() {
+            return Err(ArithmeticError::DivisionByZero(format!("{self} / {rhs}")));
+        }
+        self.checked_div(&rhs)
+            .ok_or_else(|| overflow(self, "/", rhs))
+    }
+
+    fn try_rem(self, rhs: Self) -> Result<Self, ArithmeticError>
+    where
+        Self: CheckedRem,
+    {
+        if rhs.is_zero() {
+            return Err(ArithmeticError::DivisionByZero(format!("{self} % {rhs}")));
+        }
+        self.checked_rem(&rhs)
+            .ok_or_else(|| overflow(self, "%", rhs))
+    }
+
+    fn try_pow(self, exp: u32) -> Result<Self, ArithmeticError> {
+        num::checked_pow(self, exp as usize).ok_or_else(|| overflow(self, "^", exp))
+    }
+}
+
+impl<T> Checked for T where
+    T: Copy + Display + CheckedAdd + CheckedSub + CheckedMul + CheckedDiv + One + Zero
+{
+}
+
+/// Folds of iterators that fail instead of overflowing
+pub trait CheckedIter: Iterator {
+    fn try_sum(mut self) -> Result<Self::Item, ArithmeticError>
+    where
+        Self: Sized,
+        Self::Item: Checked,
+    {
+        self.try_fold(Self::Item::zero(), Checked::try_add)
+    }
+
+    fn try_product(mut self) -> Result<Self::Item, ArithmeticError>
+    where
+        Self: Sized,
+        Self::Item: Checked,
+    {
+        self.try_fold(Self::Item::one(), Checked::try_mul)
+    }
+}
+
+impl<T: ?Sized> CheckedIter for T where T: Iterator {}
+
+#[cfg(test)]
+mod tests {
+    use num::rational::Ratio;
+
+    use super::*;
+
+    #[test]
+    fn operations_agree_with_operators_when_in_range() {
+        assert_eq!(7i64.try_add(-3), Ok(4));
+        assert_eq!(7i64.try_sub(10), Ok(-3));
+        assert_eq!(7i64.try_mul(-3), Ok(-21));
+        assert_eq!(7i64.try_div(-3), Ok(-2));
+        assert_eq!(7i64.try_rem(-3), Ok(1));
+        assert_eq!(3u8.try_pow(5), Ok(243));
+        assert_eq!([1u32, 2, 3].into_iter().try_sum(), Ok(6));
+        assert_eq!([2u32, 3, 4].into_iter().try_product(), Ok(24));
+        assert_eq!(Vec::<u32>::new().into_iter().try_product(), Ok(1));
+        assert_eq!(
+            Ratio::new(1i64, 2).try_add(Ratio::new(1, 3)),
+            Ok(Ratio::new(5, 6))
+        );
+    }
+
+    #[test]
+    fn overflow_is_an_error() {
+        assert_eq!(
+            u32::MAX.try_add(1),
+            Err(ArithmeticError::Overflow("4294967295 + 1".to_string()))
+        );
+        assert!(0u32.try_sub(1).is_err());
+        assert!(i64::MIN.try_div(-1).is_err());
+        assert!(i64::MIN.try_rem(-1).is_err());
+        assert!(2u8.try_pow(8).is_err());
+        assert!([u64::MAX, 1].into_iter().try_sum().is_err());
+        assert!([1u64 << 32, 1 << 32].into_iter().try_product().is_err());
+    }
+
+    #[test]
+    fn division_by_zero_is_an_error() {
+        assert_eq!(
+            1i32.try_div(0).unwrap_err().to_string(),
+            "Could not compute 1 / 0 because it divides by zero"
+        );
+        assert!(1i32.try_rem(0).is_err());
+        assert!(Ratio::new(1i64, 2).try_div(Ratio::zero()).is_err());
+    }
+}
@@ -0,0 +1,234 @@
+/// Finding where a sequence of states starts repeating
+///
+/// Many puzzles ask for the state after far more steps than can be simulated. If the states
+/// eventually repeat, it is enough to simulate until the first repetition and extrapolate.
+use std::hash::Hash;
+
+use hashbrown::HashMap;
+
+use crate::cancel;
+
+/// The shape of a sequence `x_0, x_1, ...` that repeats.
+///
+/// The states `x_offset` to `x_{offset + len - 1}` are the first to repeat and
+/// `x_n == x_{n + len}` for every `n` not less than `offset`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Cycle {
+    /// Number of steps before the first state that is part of the cycle
+    pub offset: usize,
+    /// Number of steps in one cycle, always at least 1
+    pub len: usize,
+}
+
+impl Cycle {
+    /// Return the smallest step with the same state as step `n`
+    pub fn equivalent(&self, n: u64) -> usize {
+        let (offset, len) = (self.offset as u64, self.len as u64);
+        match n.checked_sub(offset) {
+            None => n as usize,
+            Some(rest) => (offset + rest % len) as usize,
+        }
+    }
+
+    /// Return the value of a counter at step `n`.
+    ///
+    /// The counter must increase by the same amount every cycle, like the height of a tower that
+    /// grows by the same pattern over and over, and `values` must hold its value at every step
+    /// up to and including `offset + len`.
+    pub fn extrapolate(&self, n: u64, values: &[i64]) -> i64 {
+        let equivalent = self.equivalent(n);
+        if (n as usize) == equivalent {
+            return values[equivalent];
+        }
+        let per_cycle = values[self.offset + self.len] - values[self.offset];
+        let num_cycle = (n - equivalent as u64) / self.len as u64;
+        values[equivalent] + num_cycle as i64 * per_cycle
+    }
+}
+
+/// Find the cycle in the states `start, step(start), step(step(start)), ...` using Brent's
+/// algorithm, which needs only a constant number of states in memory.
+///
+/// Gives up with an error if the budget of the solver is exceeded before a cycle is found.
+pub fn brent<S, F>(start: S, mut step: F) -> anyhow::Result<Cycle>
+where
+    S: Clone + PartialEq,
+    F: FnMut(&S) -> S,
+{
+    // Find the length by letting the hare search for the tortoise in ever longer windows
+    let mut power = 1;
+    let mut len = 1;
+    let mut tortoise = start.clone();
+    let mut hare = step(&start);
+    while tortoise != hare {
+        if power == len {
+            tortoise = hare.clone();
+            power *= 2;
+            len = 0;
+        }
+        hare = step(&hare);
+        len += 1;
+        cancel::checkpoint()?;
+    }
+
+    // Find the offset by moving two states that are one cycle apart until they meet
+    let mut tortoise = start.clone();
+    let mut hare = start;
+    for _ in 0..len {
+        hare = step(&hare);
+    }
+    let mut offset = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        offset += 1;
+        cancel::checkpoint()?;
+    }
+    Ok(Cycle { offset, len })
+}
+
+/// Find the cycle in the states `start, step(start), step(step(start)), ...` using Floyd's
+/// algorithm, which needs only a constant number of states in memory.
+///
+/// Gives up with an error if the budget of the solver is exceeded before a cycle is found.
+pub fn floyd<S, F>(start: S, mut step: F) -> anyhow::Result<Cycle>
+where
+    S: Clone + PartialEq,
+    F: FnMut(&S) -> S,
+{
+    // Find a step in the cycle by moving the hare twice as fast as the tortoise
+    let mut tortoise = step(&start);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        hare = step(&hare);
+        cancel::checkpoint()?;
+    }
+
+    // The distance from the start to the cycle equals the distance from the meeting point
+    let mut offset = 0;
+    let mut tortoise = start;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        offset += 1;
+        cancel::checkpoint()?;
+    }
+
+    let mut len = 1;
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        len += 1;
+        cancel::checkpoint()?;
+    }
+    Ok(Cycle { offset, len })
+}
+
+/// Finds cycles by remembering a key for every state seen so far.
+///
+/// Unlike `brent` and `floyd` this works for states that are expensive to clone or compare, as
+/// long as some smaller key determines how the state continues, and lets the caller drive the
+/// simulation and record counters along the way.
+pub struct Detector<K> {
+    seen: HashMap<K, usize>,
+}
+
+impl<K: Eq + Hash> Detector<K> {
+    pub fn new() -> Self {
+        Self {
+            seen: HashMap::new(),
+        }
+    }
+
+    /// Record the key of the next state and return the cycle if the key has been seen before.
+    ///
+    /// The first key observed is that of step 0.
+    pub fn observe(&mut self, key: K) -> Option<Cycle> {
+        let now = self.seen.len();
+        let then = *self.seen.entry(key).or_insert(now);
+        (then != now).then_some(Cycle {
+            offset: then,
+            len: now - then,
+        })
+    }
+}
+
+impl<K: Eq + Hash> Default for Detector<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sequence 0, 1, 2, 3, 4, 5, 6, 7, 3, 4, ... with offset 3 and length 5
+    fn step(x: &u32) -> u32 {
+        match x {
+            7 => 3,
+            x => x + 1,
+        }
+    }
+
+    const EXPECTED: Cycle = Cycle { offset: 3, len: 5 };
+
+    #[test]
+    fn brent_finds_offset_and_length() {
+        assert_eq!(brent(0, step).unwrap(), EXPECTED);
+        assert_eq!(brent(5, step).unwrap(), Cycle { offset: 0, len: 5 });
+    }
+
+    #[test]
+    fn floyd_finds_offset_and_length() {
+        assert_eq!(floyd(0, step).unwrap(), EXPECTED);
+        assert_eq!(floyd(5, step).unwrap(), Cycle { offset: 0, len: 5 });
+    }
+
+    #[test]
+    fn detector_finds_offset_and_length() {
+        let mut detector = Detector::new();
+        let mut x = 0;
+        let cycle = loop {
+            if let Some(cycle) = detector.observe(x) {
+                break cycle;
+            }
+            x = step(&x);
+        };
+        assert_eq!(cycle, EXPECTED);
+    }
+
+    #[test]
+    fn fixed_point_is_cycle_of_length_one() {
+        let cycle = Cycle { offset: 2, len: 1 };
+        assert_eq!(brent(0, |x: &u8| (x + 1).min(2)).unwrap(), cycle);
+        assert_eq!(floyd(0, |x: &u8| (x + 1).min(2)).unwrap(), cycle);
+    }
+
+    #[test]
+    fn extrapolate_matches_simulation() {
+        // The counter grows by the state, so it grows by 3 + 4 + 5 + 6 + 7 every cycle
+        let mut values = vec![0];
+        let mut x = 0;
+        for _ in 0..100 {
+            x = step(&x);
+            values.push(values.last().unwrap() + x as i64);
+        }
+        for n in 0..100 {
+            assert_eq!(EXPECTED.extrapolate(n, &values[..=8]), values[n as usize]);
+        }
+        assert_eq!(EXPECTED.equivalent(1_000_000_000_000), 5);
+    }
+
+    #[test]
+    fn search_is_cancelled_without_cycle() {
+        let (result, expired) =
+            cancel::with_budget(Some(std::time::Duration::from_millis(10)), || {
+                brent(0u64, |x| x + 1)
+            });
+        assert!(expired);
+        assert!(result.is_err());
+    }
+}
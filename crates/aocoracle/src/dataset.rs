@@ -0,0 +1,104 @@
+/// Collections of inputs and their answers stored on disk
+///
+/// The layout is `YYYY/DD/inputs/<stem>.txt` for inputs and `YYYY/DD/answers/P/<stem>.txt` for
+/// the answer to part `P` of the input with the same stem.
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context};
+
+use crate::Part;
+
+/// One stored input
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub struct Input {
+    pub year: u16,
+    pub day: u8,
+    pub stem: String,
+}
+
+pub struct Dataset {
+    root: PathBuf,
+}
+
+impl Dataset {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn input_path(&self, input: &Input) -> PathBuf {
+        self.root.join(format!(
+            "{:04}/{:02}/inputs/{}.txt",
+            input.year, input.day, input.stem
+        ))
+    }
+
+    pub fn answer_path(&self, input: &Input, part: Part) -> PathBuf {
+        self.root.join(format!(
+            "{:04}/{:02}/answers/{:01}/{}.txt",
+            input.year, input.day, part, input.stem
+        ))
+    }
+
+    /// Return every input in the dataset, sorted
+    pub fn inputs(&self) -> anyhow::Result<Vec<Input>> {
+        let pattern = self.root.join("*/*/inputs/*.txt");
+        let pattern = pattern
+            .to_str()
+            .ok_or_else(|| anyhow!("Expected root to be valid unicode"))?;
+        let mut result = Vec::new();
+        for entry in glob::glob(pattern)? {
+            let path = entry?;
+            result.push(parse_path(&path).with_context(|| format!("{}", path.display()))?);
+        }
+        result.sort();
+        Ok(result)
+    }
+
+    pub fn read_input(&self, input: &Input) -> anyhow::Result<String> {
+        let path = self.input_path(input);
+        fs::read_to_string(&path).with_context(|| format!("{}", path.display()))
+    }
+
+    /// Return the answer to `part` of `input`, if it is known
+    pub fn expected_answer(&self, input: &Input, part: Part) -> Option<String> {
+        fs::read_to_string(self.answer_path(input, part)).ok()
+    }
+}
+
+fn parse_path(path: &Path) -> anyhow::Result<Input> {
+    let name = |n: usize| {
+        path.ancestors()
+            .nth(n)
+            .and_then(|p| p.file_stem())
+            .and_then(|s| s.to_str())
+            .ok_or_else(|| anyhow!("Expected path to follow the dataset layout"))
+    };
+    Ok(Input {
+        year: name(3)?.parse()?,
+        day: name(2)?.parse()?,
+        stem: name(0)?.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paths_round_trip() {
+        let dataset = Dataset::new("data");
+        let input = Input {
+            year: 2021,
+            day: 1,
+            stem: "EXAMPLE".into(),
+        };
+        let path = dataset.input_path(&input);
+        assert_eq!(path, PathBuf::from("data/2021/01/inputs/EXAMPLE.txt"));
+        assert_eq!(parse_path(&path).unwrap(), input);
+        assert_eq!(
+            dataset.answer_path(&input, Part::Two),
+            PathBuf::from("data/2021/01/answers/2/EXAMPLE.txt")
+        );
+    }
+}
@@ -0,0 +1,312 @@
+/// Arithmetic expressions that refer to each other by name
+///
+/// Values are exact fractions so that solving for an unknown by undoing one operation at a time
+/// never loses precision.
+use std::fmt::{Debug, Display, Formatter};
+use std::hash::Hash;
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail};
+use hashbrown::HashMap;
+use num::rational::Ratio;
+use num::Zero;
+
+use crate::checked::{ArithmeticError, Checked};
+use crate::search;
+
+pub type Rational = Ratio<i128>;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Operator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+impl FromStr for Operator {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "+" => Ok(Self::Add),
+            "/" => Ok(Self::Div),
+            "*" => Ok(Self::Mul),
+            "-" => Ok(Self::Sub),
+            other => Err(anyhow!(
+                "Expected operator '+', '/', '*', '-' but got {other}"
+            )),
+        }
+    }
+}
+
+impl Display for Operator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let symbol = match self {
+            Self::Add => '+',
+            Self::Sub => '-',
+            Self::Mul => '*',
+            Self::Div => '/',
+        };
+        write!(f, "{symbol}")
+    }
+}
+
+impl Operator {
+    pub fn apply(self, lhs: Rational, rhs: Rational) -> Result<Rational, ArithmeticError> {
+        match self {
+            Self::Add => lhs.try_add(rhs),
+            Self::Sub => lhs.try_sub(rhs),
+            Self::Mul => lhs.try_mul(rhs),
+            Self::Div => lhs.try_div(rhs),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Expr<N> {
+    Number(Rational),
+    Operation(Operator, N, N),
+}
+
+/// The values that an unknown can take for an equation to hold
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Solutions {
+    None,
+    One(Rational),
+    /// Every value, except possibly a few where the equation is undefined
+    Any,
+}
+
+/// Named expressions where every name that is referred to is defined and no expression depends
+/// on itself.
+#[derive(Clone, Debug)]
+pub struct Expressions<N> {
+    exprs: HashMap<N, Expr<N>>,
+}
+
+impl<N: Clone + Debug + Eq + Hash + Ord> Expressions<N> {
+    pub fn new(exprs: HashMap<N, Expr<N>>) -> anyhow::Result<Self> {
+        let mut names: Vec<_> = exprs.keys().cloned().collect();
+        names.sort();
+        for name in &names {
+            if let Expr::Operation(_, lhs, rhs) = &exprs[name] {
+                for operand in [lhs, rhs] {
+                    if !exprs.contains_key(operand) {
+                        bail!("Expected {operand:?} to be defined since {name:?} refers to it");
+                    }
+                }
+            }
+        }
+        let result = Self { exprs };
+        search::topological_sort(names, |name| result.operands(name))?;
+        Ok(result)
+    }
+
+    pub fn get(&self, name: &N) -> Option<&Expr<N>> {
+        self.exprs.get(name)
+    }
+
+    fn operands(&self, name: &N) -> Vec<N> {
+        match &self.exprs[name] {
+            Expr::Number(_) => Vec::new(),
+            Expr::Operation(_, lhs, rhs) => vec![lhs.clone(), rhs.clone()],
+        }
+    }
+
+    /// Return the value of every expression that `roots` depend on, or `None` for those that
+    /// depend on `unknown`.
+    fn values<'a>(
+        &self,
+        roots: impl IntoIterator<Item = &'a N>,
+        unknown: Option<&N>,
+    ) -> anyhow::Result<HashMap<N, Option<Rational>>>
+    where
+        N: 'a,
+    {
+        let roots: Vec<N> = roots.into_iter().cloned().collect();
+        if let Some(name) = roots.iter().find(|name| !self.exprs.contains_key(*name)) {
+            bail!("Expected {name:?} to be defined");
+        }
+        // Every expression comes before its operands, so the reverse is safe to evaluate in
+        let order = search::topological_sort(roots, |name| self.operands(name))?;
+        let mut result = HashMap::new();
+        for name in order.into_iter().rev() {
+            let value = match &self.exprs[&name] {
+                _ if Some(&name) == unknown => None,
+                Expr::Number(value) => Some(*value),
+                Expr::Operation(op, lhs, rhs) => match (result[lhs], result[rhs]) {
+                    (Some(lhs), Some(rhs)) => Some(op.apply(lhs, rhs)?),
+                    _ => None,
+                },
+            };
+            result.insert(name, value);
+        }
+        Ok(result)
+    }
+
+    pub fn evaluate(&self, name: &N) -> anyhow::Result<Rational> {
+        Ok(self.values([name], None)?[name].expect("There is no unknown"))
+    }
+
+    /// Find the values of `unknown`, ignoring how it is defined, that make `lhs` equal `rhs`.
+    ///
+    /// The unknown must appear in only one operand of every operation, which makes it possible to
+    /// undo the operations one at a time starting from the equation.
+    pub fn solve(&self, lhs: &N, rhs: &N, unknown: &N) -> anyhow::Result<Solutions> {
+        let values = self.values([lhs, rhs], Some(unknown))?;
+        let (mut name, mut target) = match (values[lhs], values[rhs]) {
+            (Some(lhs), Some(rhs)) if lhs == rhs => return Ok(Solutions::Any),
+            (Some(_), Some(_)) => return Ok(Solutions::None),
+            (None, Some(rhs)) => (lhs, Solutions::One(rhs)),
+            (Some(lhs), None) => (rhs, Solutions::One(lhs)),
+            (None, None) => bail!("Expected {unknown:?} to appear on only one side"),
+        };
+        while name != unknown {
+            let Expr::Operation(op, lhs, rhs) = &self.exprs[name] else {
+                unreachable!("Only operations can depend on the unknown");
+            };
+            (name, target) = match (values[lhs], values[rhs]) {
+                (None, Some(known)) => (lhs, undo(*op, known, true, target)?),
+                (Some(known), None) => (rhs, undo(*op, known, false, target)?),
+                (None, None) => bail!("Expected {unknown:?} to appear only once in {name:?}"),
+                (Some(_), Some(_)) => unreachable!("One operand depends on the unknown"),
+            };
+            if target == Solutions::None {
+                break;
+            }
+        }
+        Ok(target)
+    }
+}
+
+/// Return the values of `x` that make `x op known`, or `known op x` if `x` is not on the left,
+/// equal `target`.
+fn undo(op: Operator, known: Rational, left: bool, target: Solutions) -> anyhow::Result<Solutions> {
+    use Operator::*;
+    let target = match target {
+        Solutions::None => return Ok(Solutions::None),
+        Solutions::Any if op == Div && left && known.is_zero() => return Ok(Solutions::None),
+        Solutions::Any => return Ok(Solutions::Any),
+        Solutions::One(target) => target,
+    };
+    let zero = Rational::zero();
+    Ok(match (op, left) {
+        (Add, _) => Solutions::One(Sub.apply(target, known)?),
+        (Sub, true) => Solutions::One(Add.apply(target, known)?),
+        (Sub, false) => Solutions::One(Sub.apply(known, target)?),
+        (Mul, _) if known == zero && target == zero => Solutions::Any,
+        (Mul, _) if known == zero => Solutions::None,
+        (Mul, _) => Solutions::One(Div.apply(target, known)?),
+        (Div, true) if known == zero => Solutions::None,
+        (Div, true) => Solutions::One(Mul.apply(target, known)?),
+        // Dividing by `x` is undefined for zero, which is the only `x` that gives zero when
+        // `known` is zero and cannot give zero otherwise
+        (Div, false) if known == zero && target == zero => Solutions::Any,
+        (Div, false) if known == zero || target == zero => Solutions::None,
+        (Div, false) => Solutions::One(Div.apply(known, target)?),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expressions(text: &str) -> anyhow::Result<Expressions<&str>> {
+        let mut exprs = HashMap::new();
+        for line in text.lines() {
+            let (name, expr) = line.split_once(": ").unwrap();
+            let expr = match expr.split_whitespace().collect::<Vec<_>>()[..] {
+                [value] => Expr::Number(Rational::from_integer(value.parse().unwrap())),
+                [lhs, op, rhs] => Expr::Operation(op.parse().unwrap(), lhs, rhs),
+                _ => panic!("Bad test input {line:?}"),
+            };
+            exprs.insert(name, expr);
+        }
+        Expressions::new(exprs)
+    }
+
+    fn one(numer: i128, denom: i128) -> Solutions {
+        Solutions::One(Rational::new(numer, denom))
+    }
+
+    #[test]
+    fn example_is_evaluated_and_solved() {
+        let exprs = expressions(
+            "root: pppw + sjmn
+dbpl: 5
+cczh: sllz + lgvd
+zczc: 2
+ptdq: humn - dvpt
+dvpt: 3
+lfqf: 4
+humn: 5
+ljgn: 2
+sjmn: drzm * dbpl
+sllz: 4
+pppw: cczh / lfqf
+lgvd: ljgn * ptdq
+drzm: hmdt - zczc
+hmdt: 32",
+        )
+        .unwrap();
+        assert_eq!(
+            exprs.evaluate(&"root").unwrap(),
+            Rational::from_integer(152)
+        );
+        assert_eq!(exprs.solve(&"pppw", &"sjmn", &"humn").unwrap(), one(301, 1));
+    }
+
+    #[test]
+    fn bad_references_are_rejected() {
+        let error = expressions("a: b + c\nb: 1").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Expected \"c\" to be defined since \"a\" refers to it"
+        );
+        let error = expressions("a: b + c\nb: c * a\nc: 1").unwrap_err();
+        assert!(error.to_string().ends_with("found \"a\" -> \"b\" -> \"a\""));
+    }
+
+    #[test]
+    fn arithmetic_is_exact_and_checked() {
+        let exprs = expressions("a: b / c\nb: 1\nc: 3\nd: a * c\ne: b / z\nz: 0").unwrap();
+        assert_eq!(exprs.evaluate(&"a").unwrap(), Rational::new(1, 3));
+        assert_eq!(exprs.evaluate(&"d").unwrap(), Rational::from_integer(1));
+        assert!(exprs.evaluate(&"e").is_err());
+        assert!(exprs.evaluate(&"y").is_err());
+
+        let text = format!("a: b * b\nb: {}", i128::MAX);
+        let exprs = expressions(&text).unwrap();
+        assert!(exprs.evaluate(&"a").is_err());
+    }
+
+    #[test]
+    fn all_kinds_of_solutions_are_reported() {
+        let exprs = expressions(
+            "x: 7
+zero: 0
+one: 1
+two: 2
+three: 3
+scaled: x * zero
+halved: x / zero
+inverted: three / x
+nothing: zero / x
+twice: x + x",
+        )
+        .unwrap();
+        let solve = |lhs, rhs| exprs.solve(&lhs, &rhs, &"x").unwrap();
+        assert_eq!(solve("x", "two"), one(2, 1));
+        assert_eq!(solve("inverted", "two"), one(3, 2));
+        assert_eq!(solve("scaled", "zero"), Solutions::Any);
+        assert_eq!(solve("scaled", "one"), Solutions::None);
+        assert_eq!(solve("halved", "one"), Solutions::None);
+        assert_eq!(solve("inverted", "zero"), Solutions::None);
+        assert_eq!(solve("nothing", "zero"), Solutions::Any);
+        assert_eq!(solve("one", "one"), Solutions::Any);
+        assert_eq!(solve("one", "two"), Solutions::None);
+        assert!(exprs.solve(&"twice", &"two", &"x").is_err());
+        assert!(exprs.solve(&"x", &"x", &"x").is_err());
+    }
+}
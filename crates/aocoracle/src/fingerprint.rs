@@ -0,0 +1,127 @@
+/// Cheap classification of inputs before running any solver
+use regex::Regex;
+
+/// A lightweight description of what the inputs for a day look like.
+///
+/// Unlike `Solver::accepts` a fingerprint may reject valid inputs, e.g. when an input is longer
+/// than any seen before, so it is used to decide which solvers to try first rather than which
+/// solvers to try at all.
+#[derive(Clone, Debug, Default)]
+pub struct Fingerprint {
+    min_lines: usize,
+    max_lines: Option<usize>,
+    alphabet: Option<&'static str>,
+    first_line: Option<Regex>,
+    every_line: Option<Regex>,
+}
+
+impl Fingerprint {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Require the number of lines to be in the range `[min, max]`
+    pub fn lines(mut self, min: usize, max: usize) -> Self {
+        self.min_lines = min;
+        self.max_lines = Some(max);
+        self
+    }
+
+    /// Require every character, except line breaks, to be one of `alphabet`
+    pub fn alphabet(mut self, alphabet: &'static str) -> Self {
+        self.alphabet = Some(alphabet);
+        self
+    }
+
+    /// Require the first line to match `pattern`
+    pub fn first_line(mut self, pattern: &str) -> Self {
+        self.first_line = Some(Regex::new(pattern).expect("Hard coded regex is valid"));
+        self
+    }
+
+    /// Require every line to match `pattern`
+    pub fn every_line(mut self, pattern: &str) -> Self {
+        self.every_line = Some(Regex::new(pattern).expect("Hard coded regex is valid"));
+        self
+    }
+
+    /// Number of requirements that an input must meet to match
+    pub fn num_requirement(&self) -> usize {
+        [
+            self.max_lines.is_some(),
+            self.alphabet.is_some(),
+            self.first_line.is_some(),
+            self.every_line.is_some(),
+        ]
+        .into_iter()
+        .filter(|r| *r)
+        .count()
+    }
+
+    pub fn matches(&self, text: &str) -> bool {
+        if let Some(alphabet) = self.alphabet {
+            if !text
+                .chars()
+                .all(|c| c == '\n' || c == '\r' || alphabet.contains(c))
+            {
+                return false;
+            }
+        }
+        if let Some(re) = &self.first_line {
+            if !re.is_match(text.lines().next().unwrap_or_default()) {
+                return false;
+            }
+        }
+        if let Some(re) = &self.every_line {
+            if !text.lines().all(|line| re.is_match(line)) {
+                return false;
+            }
+        }
+        let num_line = text.lines().count();
+        if num_line < self.min_lines {
+            return false;
+        }
+        if let Some(max_lines) = self.max_lines {
+            if max_lines < num_line {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+pub const DIGITS: &str = "0123456789";
+pub const LOWERCASE: &str = "abcdefghijklmnopqrstuvwxyz";
+pub const LETTERS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_fingerprint_matches_anything() {
+        assert!(Fingerprint::new().matches(""));
+        assert!(Fingerprint::new().matches("1\n2\n"));
+    }
+
+    #[test]
+    fn every_requirement_must_be_met() {
+        let fingerprint = Fingerprint::new()
+            .lines(1, 2)
+            .alphabet("0123456789,")
+            .first_line(r"^\d+(,\d+)*$");
+        assert!(fingerprint.matches("1,2,3\n"));
+        assert!(fingerprint.matches("1,2,3\n4\n"));
+        assert!(!fingerprint.matches("1,2,3\n4\n5\n"));
+        assert!(!fingerprint.matches("1, 2, 3\n"));
+        assert!(!fingerprint.matches(",1,2,3\n"));
+        assert!(!fingerprint.matches(""));
+    }
+
+    #[test]
+    fn every_line_must_match() {
+        let fingerprint = Fingerprint::new().every_line(r"^\d$");
+        assert!(fingerprint.matches("1\n2\n"));
+        assert!(!fingerprint.matches("1\n22\n"));
+    }
+}
@@ -0,0 +1,548 @@
+/// Points, directions and axis aligned boxes in two and three dimensions
+///
+/// Like in `grid`, `y` grows downwards so that `Up` is towards the first line of the input.
+use std::fmt::Debug;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+use crate::checked::{ArithmeticError, Checked};
+
+fn abs_diff<T: Checked + Ord>(a: T, b: T) -> Result<T, ArithmeticError> {
+    if a < b {
+        b.try_sub(a)
+    } else {
+        a.try_sub(b)
+    }
+}
+
+macro_rules! point {
+    ($name:ident, $($c:ident)*) => {
+        #[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+        pub struct $name<T> {
+            $(pub $c: T,)*
+        }
+
+        impl<T> $name<T> {
+            pub const fn new($($c: T),*) -> Self {
+                Self { $($c),* }
+            }
+        }
+
+        impl<T: Checked + Ord> $name<T> {
+            /// Return the number of steps between the points when moving along one axis at a time
+            pub fn manhattan(&self, other: &Self) -> Result<T, ArithmeticError> {
+                let distances = [$(abs_diff(self.$c, other.$c)?),*];
+                distances[1..].iter().try_fold(distances[0], |acc, &d| acc.try_add(d))
+            }
+
+            /// Return the number of steps between the points when moving diagonally is allowed
+            pub fn chebyshev(&self, other: &Self) -> Result<T, ArithmeticError> {
+                let distances = [$(abs_diff(self.$c, other.$c)?),*];
+                Ok(distances[1..].iter().fold(distances[0], |acc, &d| acc.max(d)))
+            }
+
+            pub fn try_add(self, rhs: Self) -> Result<Self, ArithmeticError> {
+                Ok(Self { $($c: self.$c.try_add(rhs.$c)?),* })
+            }
+
+            pub fn try_sub(self, rhs: Self) -> Result<Self, ArithmeticError> {
+                Ok(Self { $($c: self.$c.try_sub(rhs.$c)?),* })
+            }
+        }
+
+        impl<T: Add<Output = T>> Add for $name<T> {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                Self { $($c: self.$c + rhs.$c),* }
+            }
+        }
+
+        impl<T: Sub<Output = T>> Sub for $name<T> {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                Self { $($c: self.$c - rhs.$c),* }
+            }
+        }
+
+        impl<T: Neg<Output = T>> Neg for $name<T> {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                Self { $($c: -self.$c),* }
+            }
+        }
+
+        impl<T: Copy + Mul<Output = T>> Mul<T> for $name<T> {
+            type Output = Self;
+
+            fn mul(self, rhs: T) -> Self {
+                Self { $($c: self.$c * rhs),* }
+            }
+        }
+
+        impl<T: AddAssign> AddAssign for $name<T> {
+            fn add_assign(&mut self, rhs: Self) {
+                $(self.$c += rhs.$c;)*
+            }
+        }
+
+        impl<T: SubAssign> SubAssign for $name<T> {
+            fn sub_assign(&mut self, rhs: Self) {
+                $(self.$c -= rhs.$c;)*
+            }
+        }
+    };
+}
+
+point!(Point2, x y);
+point!(Point3, x y z);
+
+impl<T: Checked + Ord + From<i8>> Point2<T> {
+    /// Return the points that share a side with this one, clockwise from above
+    pub fn neighbours4(self) -> impl Iterator<Item = Result<Self, ArithmeticError>> {
+        Direction::ALL
+            .into_iter()
+            .map(move |d| self.try_add(d.offset()))
+    }
+
+    /// Return the points that share a side or a corner with this one, clockwise from above
+    pub fn neighbours8(self) -> impl Iterator<Item = Result<Self, ArithmeticError>> {
+        Direction8::ALL
+            .into_iter()
+            .map(move |d| self.try_add(d.offset()))
+    }
+}
+
+impl<T: Checked + Ord + From<i8>> Point3<T> {
+    /// Return the points that share a face with this one
+    pub fn neighbours6(self) -> impl Iterator<Item = Result<Self, ArithmeticError>> {
+        [
+            (-1, 0, 0),
+            (1, 0, 0),
+            (0, -1, 0),
+            (0, 1, 0),
+            (0, 0, -1),
+            (0, 0, 1),
+        ]
+        .into_iter()
+        .map(move |(x, y, z)| self.try_add(Point3::new(x.into(), y.into(), z.into())))
+    }
+
+    /// Return the points that share a face, an edge or a corner with this one
+    pub fn neighbours26(self) -> impl Iterator<Item = Result<Self, ArithmeticError>> {
+        (-1..=1)
+            .flat_map(|x| (-1..=1).flat_map(move |y| (-1..=1).map(move |z| (x, y, z))))
+            .filter(|&d| d != (0, 0, 0))
+            .map(move |(x, y, z)| self.try_add(Point3::new(x.into(), y.into(), z.into())))
+    }
+}
+
+/// One of the four directions along the axes of the plane
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+/// One of the eight directions along the axes and diagonals of the plane
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Direction8 {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+macro_rules! direction {
+    ($name:ident, $($variant:ident => ($dx:literal, $dy:literal),)*) => {
+        impl $name {
+            /// Every direction, clockwise from `Up`
+            pub const ALL: [Self; [$($dx),*].len()] = [$(Self::$variant),*];
+
+            fn rotated(self, steps: usize) -> Self {
+                Self::ALL[(self as usize + steps) % Self::ALL.len()]
+            }
+
+            /// Return the next direction clockwise
+            pub fn turned_right(self) -> Self {
+                self.rotated(1)
+            }
+
+            /// Return the next direction counterclockwise
+            pub fn turned_left(self) -> Self {
+                self.rotated(Self::ALL.len() - 1)
+            }
+
+            pub fn reversed(self) -> Self {
+                self.rotated(Self::ALL.len() / 2)
+            }
+
+            /// Return the change in position from taking one step in this direction
+            pub fn offset<T: From<i8>>(self) -> Point2<T> {
+                let (x, y): (i8, i8) = match self {
+                    $(Self::$variant => ($dx, $dy),)*
+                };
+                Point2::new(x.into(), y.into())
+            }
+        }
+    };
+}
+
+direction! {
+    Direction,
+    Up => (0, -1),
+    Right => (1, 0),
+    Down => (0, 1),
+    Left => (-1, 0),
+}
+
+direction! {
+    Direction8,
+    Up => (0, -1),
+    UpRight => (1, -1),
+    Right => (1, 0),
+    DownRight => (1, 1),
+    Down => (0, 1),
+    DownLeft => (-1, 1),
+    Left => (-1, 0),
+    UpLeft => (-1, -1),
+}
+
+/// Split `[lo, hi)` at the start and end of `[cut_lo, cut_hi)`, which must lie within it.
+///
+/// Returns the part before, the cut itself, and the part after; the first and last may be empty.
+fn split<T: Copy>(lo: T, hi: T, cut_lo: T, cut_hi: T) -> [(T, T); 3] {
+    [(lo, cut_lo), (cut_lo, cut_hi), (cut_hi, hi)]
+}
+
+#[allow(clippy::manual_non_exhaustive)] // to prevent instantiation within this crate
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Rectangle<T: Debug> {
+    pub left: T,
+    pub top: T,
+    // TODO: Consider constraining width and height to non-negative types
+    // Since constructors are only implemented for unsigned types, or check that the sides are
+    // positive, this is not yet a problem.
+    pub width: T,
+    pub height: T,
+    _prevent_instantiation: (),
+}
+
+impl<T: Add<Output = T> + Copy + Debug + Mul<Output = T> + Ord + Sub<Output = T>> Rectangle<T> {
+    /// Return the rectangle from `min`, inclusive, to `max`, exclusive, if it is not empty
+    pub fn from_corners(min: Point2<T>, max: Point2<T>) -> Option<Self> {
+        if max.x <= min.x || max.y <= min.y {
+            return None;
+        }
+        Some(Self {
+            left: min.x,
+            top: min.y,
+            width: max.x - min.x,
+            height: max.y - min.y,
+            _prevent_instantiation: (),
+        })
+    }
+
+    fn right(&self) -> T {
+        self.left + self.width
+    }
+
+    fn bottom(&self) -> T {
+        self.top + self.height
+    }
+
+    pub fn min(&self) -> Point2<T> {
+        Point2::new(self.left, self.top)
+    }
+
+    /// Return the corner opposite of `min`, which is just outside the rectangle
+    pub fn max(&self) -> Point2<T> {
+        Point2::new(self.right(), self.bottom())
+    }
+
+    pub fn area(&self) -> T {
+        self.width * self.height
+    }
+
+    pub fn contains(&self, other: &Rectangle<T>) -> bool {
+        self.left <= other.left
+            && self.top <= other.top
+            && other.right() <= self.right()
+            && other.bottom() <= self.bottom()
+    }
+
+    pub fn intersection(&self, other: &Rectangle<T>) -> Option<Self> {
+        let top = self.top.max(other.top);
+        let right = self.right().min(other.right());
+        let bottom = self.bottom().min(other.bottom());
+        let left = self.left.max(other.left);
+        if right <= left || bottom <= top {
+            return None;
+        }
+        Some(Self {
+            left,
+            top,
+            width: right - left,
+            height: bottom - top,
+            _prevent_instantiation: (),
+        })
+    }
+
+    /// Return the smallest rectangle that contains both rectangles
+    pub fn union(&self, other: &Rectangle<T>) -> Self {
+        let left = self.left.min(other.left);
+        let top = self.top.min(other.top);
+        Self {
+            left,
+            top,
+            width: self.right().max(other.right()) - left,
+            height: self.bottom().max(other.bottom()) - top,
+            _prevent_instantiation: (),
+        }
+    }
+
+    /// Return disjoint rectangles that together cover this rectangle except for `other`
+    pub fn subtract(&self, other: &Rectangle<T>) -> Vec<Self> {
+        let Some(cut) = self.intersection(other) else {
+            return vec![self.clone()];
+        };
+        let xs = split(self.left, self.right(), cut.left, cut.right());
+        let ys = split(self.top, self.bottom(), cut.top, cut.bottom());
+        let mut result = Vec::new();
+        for (i, &(y0, y1)) in ys.iter().enumerate() {
+            for (j, &(x0, x1)) in xs.iter().enumerate() {
+                if (i, j) == (1, 1) {
+                    continue;
+                }
+                result.extend(Self::from_corners(Point2::new(x0, y0), Point2::new(x1, y1)));
+            }
+        }
+        result
+    }
+}
+
+macro_rules! unsigned_integer_rectangle {
+    ($($a:ty)*) => ($(
+        impl Rectangle<$a> {
+            pub fn new(left: $a, top: $a, width: $a, height: $a) -> Self {
+                Self {
+                    left,
+                    top,
+                    width,
+                    height,
+                    _prevent_instantiation: (),
+                }
+            }
+
+            pub fn tiles(&self) -> impl Iterator<Item = ($a, $a)> + '_ {
+                (0..self.area()).map(|i| (self.left + i % self.width, self.top + i / self.width))
+            }
+        }
+    )*);
+}
+
+unsigned_integer_rectangle! { u32 usize }
+
+/// The three dimensional counterpart of `Rectangle`, which is never empty
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Cuboid<T> {
+    min: Point3<T>,
+    max: Point3<T>,
+}
+
+impl<T: Add<Output = T> + Copy + Debug + Mul<Output = T> + Ord + Sub<Output = T>> Cuboid<T> {
+    /// Return the cuboid from `min`, inclusive, to `max`, exclusive, if it is not empty
+    pub fn from_corners(min: Point3<T>, max: Point3<T>) -> Option<Self> {
+        if max.x <= min.x || max.y <= min.y || max.z <= min.z {
+            return None;
+        }
+        Some(Self { min, max })
+    }
+
+    pub fn min(&self) -> Point3<T> {
+        self.min
+    }
+
+    /// Return the corner opposite of `min`, which is just outside the cuboid
+    pub fn max(&self) -> Point3<T> {
+        self.max
+    }
+
+    pub fn volume(&self) -> T {
+        let size = self.max - self.min;
+        size.x * size.y * size.z
+    }
+
+    pub fn contains(&self, other: &Cuboid<T>) -> bool {
+        self.min.x <= other.min.x
+            && self.min.y <= other.min.y
+            && self.min.z <= other.min.z
+            && other.max.x <= self.max.x
+            && other.max.y <= self.max.y
+            && other.max.z <= self.max.z
+    }
+
+    pub fn intersection(&self, other: &Cuboid<T>) -> Option<Self> {
+        Self::from_corners(
+            Point3::new(
+                self.min.x.max(other.min.x),
+                self.min.y.max(other.min.y),
+                self.min.z.max(other.min.z),
+            ),
+            Point3::new(
+                self.max.x.min(other.max.x),
+                self.max.y.min(other.max.y),
+                self.max.z.min(other.max.z),
+            ),
+        )
+    }
+
+    /// Return the smallest cuboid that contains both cuboids
+    pub fn union(&self, other: &Cuboid<T>) -> Self {
+        Self {
+            min: Point3::new(
+                self.min.x.min(other.min.x),
+                self.min.y.min(other.min.y),
+                self.min.z.min(other.min.z),
+            ),
+            max: Point3::new(
+                self.max.x.max(other.max.x),
+                self.max.y.max(other.max.y),
+                self.max.z.max(other.max.z),
+            ),
+        }
+    }
+
+    /// Return disjoint cuboids that together cover this cuboid except for `other`
+    pub fn subtract(&self, other: &Cuboid<T>) -> Vec<Self> {
+        let Some(cut) = self.intersection(other) else {
+            return vec![self.clone()];
+        };
+        let xs = split(self.min.x, self.max.x, cut.min.x, cut.max.x);
+        let ys = split(self.min.y, self.max.y, cut.min.y, cut.max.y);
+        let zs = split(self.min.z, self.max.z, cut.min.z, cut.max.z);
+        let mut result = Vec::new();
+        for (i, &(z0, z1)) in zs.iter().enumerate() {
+            for (j, &(y0, y1)) in ys.iter().enumerate() {
+                for (k, &(x0, x1)) in xs.iter().enumerate() {
+                    if (i, j, k) == (1, 1, 1) {
+                        continue;
+                    }
+                    result.extend(Self::from_corners(
+                        Point3::new(x0, y0, z0),
+                        Point3::new(x1, y1, z1),
+                    ));
+                }
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distances_work_for_signed_and_unsigned() {
+        let a = Point2::new(-1i64, 2);
+        let b = Point2::new(3, -5);
+        assert_eq!(a.manhattan(&b), Ok(11));
+        assert_eq!(a.chebyshev(&b), Ok(7));
+        let a = Point3::new(1u32, 2, 3);
+        let b = Point3::new(4, 0, 3);
+        assert_eq!(a.manhattan(&b), Ok(5));
+        assert_eq!(b.chebyshev(&a), Ok(3));
+    }
+
+    #[test]
+    fn returns_error_on_overflow() {
+        let a = Point2::new(i64::MIN, 0);
+        let b = Point2::new(i64::MAX, 0);
+        assert!(a.manhattan(&b).is_err());
+        assert!(a.chebyshev(&b).is_err());
+        let a = Point2::new(i64::MAX, 0);
+        let b = Point2::new(0, i64::MAX);
+        assert!(Point2::new(0, 0).manhattan(&a).is_ok());
+        assert!(a.manhattan(&b).is_err());
+        assert!(a.try_add(b).is_ok());
+        assert!(a.try_add(a).is_err());
+        assert!(a.neighbours4().any(|n| n.is_err()));
+        let p = Point3::new(0, 0, i64::MIN);
+        assert!(p.neighbours6().any(|n| n.is_err()));
+        assert!(p.neighbours26().any(|n| n.is_err()));
+    }
+
+    #[test]
+    fn directions_rotate_and_move() {
+        assert_eq!(Direction::Up.turned_right(), Direction::Right);
+        assert_eq!(Direction::Up.turned_left(), Direction::Left);
+        assert_eq!(Direction::Left.reversed(), Direction::Right);
+        assert_eq!(Direction8::UpLeft.turned_right(), Direction8::Up);
+        assert_eq!(Direction8::DownRight.reversed(), Direction8::UpLeft);
+        let p = Point2::new(2i32, 2);
+        assert_eq!(p + Direction::Up.offset(), Point2::new(2, 1));
+        assert_eq!(p + Direction8::DownLeft.offset() * 2, Point2::new(0, 4));
+    }
+
+    #[test]
+    fn neighbours_are_distinct_and_adjacent() {
+        let p = Point2::new(0i64, 0);
+        assert!(p.neighbours4().all(|n| p.manhattan(&n.unwrap()) == Ok(1)));
+        assert!(p.neighbours8().all(|n| p.chebyshev(&n.unwrap()) == Ok(1)));
+        let p = Point3::new(0i64, 0, 0);
+        assert!(p.neighbours6().all(|n| p.manhattan(&n.unwrap()) == Ok(1)));
+        let mut neighbours: Vec<_> = p.neighbours26().map(Result::unwrap).collect();
+        neighbours.sort();
+        neighbours.dedup();
+        assert_eq!(neighbours.len(), 26);
+        assert!(neighbours.iter().all(|n| p.chebyshev(n) == Ok(1)));
+    }
+
+    #[test]
+    fn rectangle_subtraction_covers_the_difference() {
+        let a = Rectangle::<u32>::new(0, 0, 5, 4);
+        let b = Rectangle::<u32>::new(3, 1, 5, 2);
+        let pieces = a.subtract(&b);
+        assert_eq!(
+            pieces.iter().map(|r| r.area()).sum::<u32>(),
+            a.area() - a.intersection(&b).unwrap().area()
+        );
+        for (i, p) in pieces.iter().enumerate() {
+            assert!(a.contains(p));
+            assert!(p.intersection(&b).is_none());
+            assert!(pieces[i + 1..].iter().all(|q| p.intersection(q).is_none()));
+        }
+        assert_eq!(a.union(&b), Rectangle::<u32>::new(0, 0, 8, 4));
+        assert_eq!(
+            b.subtract(&Rectangle::<u32>::new(9, 9, 1, 1)),
+            vec![b.clone()]
+        );
+        assert!(b.subtract(&a.union(&b)).is_empty());
+    }
+
+    #[test]
+    fn cuboid_subtraction_covers_the_difference() {
+        let a = Cuboid::from_corners(Point3::new(-2i64, -2, -2), Point3::new(2, 2, 2)).unwrap();
+        let b = Cuboid::from_corners(Point3::new(0, -1, -5), Point3::new(1, 0, 5)).unwrap();
+        let pieces = a.subtract(&b);
+        assert_eq!(
+            pieces.iter().map(|c| c.volume()).sum::<i64>(),
+            a.volume() - a.intersection(&b).unwrap().volume()
+        );
+        for (i, p) in pieces.iter().enumerate() {
+            assert!(a.contains(p));
+            assert!(p.intersection(&b).is_none());
+            assert!(pieces[i + 1..].iter().all(|q| p.intersection(q).is_none()));
+        }
+        assert_eq!(a.union(&b).volume(), 4 * 4 * 10);
+        assert!(Cuboid::from_corners(Point3::new(0, 0, 0), Point3::new(1, 0, 1)).is_none());
+    }
+}
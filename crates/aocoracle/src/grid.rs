@@ -0,0 +1,414 @@
+/// Two dimensional maps of tiles, which is how many puzzles draw their inputs
+///
+/// Positions are `(x, y)` with `x` growing to the right and `y` growing downwards, so that the
+/// first character of the first line is at `(0, 0)`.
+use std::ops::{Index, IndexMut};
+
+use anyhow::{anyhow, bail};
+use hashbrown::HashMap;
+
+use crate::parse::Lines;
+
+/// Offsets to the four tiles that share a side with a tile, clockwise starting upwards
+pub const DIRECTIONS_4: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Offsets to the eight tiles that share a side or a corner with a tile, clockwise
+pub const DIRECTIONS_8: [(isize, isize); 8] = [
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+];
+
+/// A rectangle of tiles where every position has a value
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    tiles: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Create a grid from tiles in reading order
+    pub fn new(width: usize, tiles: Vec<T>) -> anyhow::Result<Self> {
+        if width == 0 || tiles.is_empty() {
+            bail!("Expected at least one tile");
+        }
+        if tiles.len() % width != 0 {
+            bail!(
+                "Expected a multiple of {width} tiles but got {}",
+                tiles.len()
+            );
+        }
+        Ok(Self {
+            width,
+            height: tiles.len() / width,
+            tiles,
+        })
+    }
+
+    pub fn filled(width: usize, height: usize, tile: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            tiles: vec![tile; width * height],
+        }
+    }
+
+    /// Parse a grid with one line per row and one character per tile.
+    ///
+    /// Every line must have the same length and `tile` is called with every character, in
+    /// reading order, to convert it.
+    pub fn parse<F>(text: &str, mut tile: F) -> anyhow::Result<Self>
+    where
+        F: FnMut(char) -> anyhow::Result<T>,
+    {
+        let mut width = None;
+        let mut tiles = Vec::new();
+        for (y, line) in Lines::new(text)?.enumerate() {
+            let before = tiles.len();
+            for (x, c) in line.chars().enumerate() {
+                tiles.push(tile(c).map_err(|e| anyhow!("Invalid tile at ({x}, {y}): {e}"))?);
+            }
+            let len = tiles.len() - before;
+            match width {
+                None => width = Some(len),
+                Some(width) if width != len => {
+                    bail!("Expected every row to have {width} tiles but row {y} has {len}")
+                }
+                Some(_) => {}
+            }
+        }
+        Self::new(width.unwrap_or_default(), tiles)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn index_of(&self, (x, y): (usize, usize)) -> Option<usize> {
+        (x < self.width && y < self.height).then_some(y * self.width + x)
+    }
+
+    pub fn get(&self, pos: (usize, usize)) -> Option<&T> {
+        self.index_of(pos).map(|i| &self.tiles[i])
+    }
+
+    pub fn get_mut(&mut self, pos: (usize, usize)) -> Option<&mut T> {
+        self.index_of(pos).map(|i| &mut self.tiles[i])
+    }
+
+    /// Return the position one step in `direction` from `pos` if it is in the grid
+    pub fn step(&self, (x, y): (usize, usize), (dx, dy): (isize, isize)) -> Option<(usize, usize)> {
+        let pos = (x.checked_add_signed(dx)?, y.checked_add_signed(dy)?);
+        self.index_of(pos).map(|_| pos)
+    }
+
+    /// Return the positions in the grid that share a side with `pos`
+    pub fn neighbours4(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        DIRECTIONS_4
+            .into_iter()
+            .filter_map(move |d| self.step(pos, d))
+    }
+
+    /// Return the positions in the grid that share a side or a corner with `pos`
+    pub fn neighbours8(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        DIRECTIONS_8
+            .into_iter()
+            .filter_map(move |d| self.step(pos, d))
+    }
+
+    /// Return the positions from `pos`, exclusive, to the edge of the grid in `direction`
+    pub fn ray(
+        &self,
+        pos: (usize, usize),
+        direction: (isize, isize),
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        std::iter::successors(self.step(pos, direction), move |&p| self.step(p, direction))
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.tiles[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.tiles.chunks(self.width)
+    }
+
+    pub fn column(&self, x: usize) -> impl DoubleEndedIterator<Item = &T> {
+        assert!(
+            x < self.width,
+            "Column {x} is outside grid of width {}",
+            self.width
+        );
+        self.tiles[x..].iter().step_by(self.width)
+    }
+
+    /// Return every position in reading order
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.tiles.len()).map(move |i| (i % width, i / width))
+    }
+
+    /// Return every position and its tile in reading order
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.positions().zip(self.tiles.iter())
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.tiles.iter()
+    }
+
+    pub fn into_values(self) -> impl Iterator<Item = T> {
+        self.tiles.into_iter()
+    }
+
+    fn into_iter_with_positions(self) -> impl Iterator<Item = ((usize, usize), T)> {
+        self.positions().zip(self.tiles)
+    }
+
+    /// Return the positions on the edge of the grid, each once, in no particular order
+    pub fn border(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let (w, h) = (self.width, self.height);
+        self.positions()
+            .filter(move |&(x, y)| x == 0 || y == 0 || x + 1 == w || y + 1 == h)
+    }
+
+    /// Return the position of the first tile, in reading order, that satisfies `predicate`
+    pub fn position<P>(&self, mut predicate: P) -> Option<(usize, usize)>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.iter().find(|(_, t)| predicate(t)).map(|(p, _)| p)
+    }
+
+    pub fn map<U, F>(&self, f: F) -> Grid<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        Grid {
+            width: self.width,
+            height: self.height,
+            tiles: self.tiles.iter().map(f).collect(),
+        }
+    }
+
+    /// Draw the grid with one string per row, e.g. for a trace
+    pub fn render<F>(&self, mut tile: F) -> Vec<String>
+    where
+        F: FnMut(&T) -> char,
+    {
+        self.rows()
+            .map(|row| row.iter().map(&mut tile).collect())
+            .collect()
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    /// # Panics
+    ///
+    /// If the position is outside the grid.
+    fn index(&self, pos: (usize, usize)) -> &Self::Output {
+        match self.index_of(pos) {
+            Some(i) => &self.tiles[i],
+            None => panic!(
+                "Position {pos:?} is outside grid of size {}x{}",
+                self.width, self.height
+            ),
+        }
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, pos: (usize, usize)) -> &mut Self::Output {
+        match self.index_of(pos) {
+            Some(i) => &mut self.tiles[i],
+            None => panic!(
+                "Position {pos:?} is outside grid of size {}x{}",
+                self.width, self.height
+            ),
+        }
+    }
+}
+
+/// Tiles at arbitrary, possibly negative, positions on an unbounded plane
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SparseGrid<T> {
+    tiles: HashMap<(i64, i64), T>,
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self {
+            tiles: HashMap::new(),
+        }
+    }
+
+    /// Parse a grid like `Grid::parse` but keep only the tiles for which `tile` returns a value
+    pub fn parse<F>(text: &str, mut tile: F) -> anyhow::Result<Self>
+    where
+        F: FnMut(char) -> anyhow::Result<Option<T>>,
+    {
+        let mut tiles = HashMap::new();
+        for ((x, y), t) in Grid::parse(text, &mut tile)?.into_iter_with_positions() {
+            if let Some(t) = t {
+                tiles.insert((x as i64, y as i64), t);
+            }
+        }
+        Ok(Self { tiles })
+    }
+
+    pub fn len(&self) -> usize {
+        self.tiles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+
+    pub fn get(&self, pos: (i64, i64)) -> Option<&T> {
+        self.tiles.get(&pos)
+    }
+
+    pub fn contains(&self, pos: (i64, i64)) -> bool {
+        self.tiles.contains_key(&pos)
+    }
+
+    pub fn insert(&mut self, pos: (i64, i64), tile: T) -> Option<T> {
+        self.tiles.insert(pos, tile)
+    }
+
+    pub fn remove(&mut self, pos: (i64, i64)) -> Option<T> {
+        self.tiles.remove(&pos)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = ((i64, i64), &T)> {
+        self.tiles.iter().map(|(p, t)| (*p, t))
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.tiles.values()
+    }
+
+    /// Return the smallest and the largest `x` and `y` of any tile, if there are tiles
+    pub fn bounds(&self) -> Option<((i64, i64), (i64, i64))> {
+        let mut keys = self.tiles.keys();
+        let &(x, y) = keys.next()?;
+        Some(
+            keys.fold(((x, y), (x, y)), |((x0, y0), (x1, y1)), &(x, y)| {
+                ((x0.min(x), y0.min(y)), (x1.max(x), y1.max(y)))
+            }),
+        )
+    }
+
+    /// Draw the bounding box of the tiles with one string per row, e.g. for a trace.
+    ///
+    /// Positions without a tile are drawn as `default`.
+    pub fn render<F>(&self, default: char, mut tile: F) -> Vec<String>
+    where
+        F: FnMut(&T) -> char,
+    {
+        let Some(((x0, y0), (x1, y1))) = self.bounds() else {
+            return Vec::new();
+        };
+        (y0..=y1)
+            .map(|y| {
+                (x0..=x1)
+                    .map(|x| self.tiles.get(&(x, y)).map_or(default, &mut tile))
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+impl<T> FromIterator<((i64, i64), T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = ((i64, i64), T)>>(iter: I) -> Self {
+        Self {
+            tiles: iter.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digit(c: char) -> anyhow::Result<u32> {
+        c.to_digit(10)
+            .ok_or_else(|| anyhow!("Expected a digit but got {c:?}"))
+    }
+
+    #[test]
+    fn parse_renders_back_to_text() {
+        let text = "123\n456\n";
+        let grid = Grid::parse(text, digit).unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(2, 1)], 6);
+        assert_eq!(grid.get((3, 1)), None);
+        assert_eq!(
+            grid.render(|d| char::from_digit(*d, 10).unwrap())
+                .join("\n")
+                + "\n",
+            text
+        );
+        assert_eq!(grid.column(1).rev().collect::<Vec<_>>(), vec![&5, &2]);
+        assert_eq!(grid.row(1), &[4, 5, 6]);
+    }
+
+    #[test]
+    fn parse_errors_name_the_problem() {
+        let error = Grid::parse("12\n3x\n", digit).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid tile at (1, 1): Expected a digit but got 'x'"
+        );
+        let error = Grid::parse("12\n345\n", digit).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Expected every row to have 2 tiles but row 1 has 3"
+        );
+        assert!(Grid::parse("", digit).is_err());
+    }
+
+    #[test]
+    fn neighbours_stay_in_bounds() {
+        let grid = Grid::filled(3, 3, ());
+        assert_eq!(grid.neighbours4((0, 0)).count(), 2);
+        assert_eq!(grid.neighbours8((0, 0)).count(), 3);
+        assert_eq!(grid.neighbours4((1, 1)).count(), 4);
+        assert_eq!(grid.neighbours8((1, 1)).count(), 8);
+        assert_eq!(grid.border().count(), 8);
+        assert_eq!(
+            grid.ray((0, 1), (1, 0)).collect::<Vec<_>>(),
+            vec![(1, 1), (2, 1)]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Position (3, 0) is outside grid of size 3x1")]
+    fn index_is_bounds_checked() {
+        let grid = Grid::filled(3, 1, 0);
+        let _ = grid[(3, 0)];
+    }
+
+    #[test]
+    fn sparse_grid_renders_bounding_box() {
+        let mut grid = SparseGrid::parse("#.\n.#\n", |c| Ok((c == '#').then_some('#'))).unwrap();
+        grid.insert((-1, 0), 'o');
+        assert_eq!(grid.bounds(), Some(((-1, 0), (1, 1))));
+        assert_eq!(grid.render('.', |c| *c), vec!["o#.", "..#"]);
+    }
+}
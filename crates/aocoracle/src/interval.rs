@@ -0,0 +1,275 @@
+/// Sets and maps over half-open ranges of integers
+///
+/// Puzzles often deal with far more numbers than can be enumerated, but in runs that can be
+/// handled one range at a time.
+use std::fmt::Debug;
+use std::ops::Range;
+
+use anyhow::bail;
+
+use crate::checked::{ArithmeticError, Checked, CheckedIter};
+
+/// A set of numbers stored as the fewest ranges that cover exactly those numbers.
+///
+/// The ranges are kept sorted, non-empty, and neither overlapping nor adjacent.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RangeSet<T> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T: Copy + Ord> RangeSet<T> {
+    pub fn new() -> Self {
+        Self { ranges: Vec::new() }
+    }
+
+    /// Build the set from ranges that may be empty, unsorted, overlapping or adjacent
+    fn normalized(mut ranges: Vec<Range<T>>) -> Self {
+        ranges.retain(|r| r.start < r.end);
+        ranges.sort_by_key(|r| r.start);
+        let mut result: Vec<Range<T>> = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            match result.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => result.push(range),
+            }
+        }
+        Self { ranges: result }
+    }
+
+    /// Return the ranges in ascending order
+    pub fn ranges(&self) -> &[Range<T>] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let i = self.ranges.partition_point(|r| r.end <= value);
+        self.ranges.get(i).map_or(false, |r| r.start <= value)
+    }
+
+    /// Return the smallest number in the set, if any
+    pub fn first(&self) -> Option<T> {
+        self.ranges.first().map(|r| r.start)
+    }
+
+    /// Return the largest number in the set, if any, as the end of an exclusive range
+    pub fn end(&self) -> Option<T> {
+        self.ranges.last().map(|r| r.end)
+    }
+
+    pub fn insert(&mut self, range: Range<T>) {
+        let mut ranges = std::mem::take(&mut self.ranges);
+        ranges.push(range);
+        *self = Self::normalized(ranges);
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self::normalized(self.ranges.iter().chain(&other.ranges).cloned().collect())
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut result = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while let (Some(a), Some(b)) = (self.ranges.get(i), other.ranges.get(j)) {
+            let start = a.start.max(b.start);
+            let end = a.end.min(b.end);
+            if start < end {
+                result.push(start..end);
+            }
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { ranges: result }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut result = Vec::new();
+        let mut j = 0;
+        for a in &self.ranges {
+            let mut start = a.start;
+            while let Some(b) = other.ranges.get(j) {
+                if a.end <= b.start {
+                    break;
+                }
+                if start < b.start {
+                    result.push(start..b.start);
+                }
+                start = start.max(b.end);
+                if a.end < b.end {
+                    break;
+                }
+                j += 1;
+            }
+            if start < a.end {
+                result.push(start..a.end);
+            }
+        }
+        Self { ranges: result }
+    }
+
+    /// Return the parts of the set inside `other` and the parts outside, in that order
+    pub fn split(&self, other: &Self) -> (Self, Self) {
+        (self.intersection(other), self.difference(other))
+    }
+}
+
+impl<T: Checked + Ord> RangeSet<T> {
+    /// Return the number of numbers in the set
+    pub fn len(&self) -> Result<T, ArithmeticError> {
+        self.ranges
+            .iter()
+            .map(|r| r.end.try_sub(r.start))
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .try_sum()
+    }
+}
+
+impl<T: Copy + Ord> From<Range<T>> for RangeSet<T> {
+    fn from(range: Range<T>) -> Self {
+        Self::normalized(vec![range])
+    }
+}
+
+impl<T: Copy + Ord> FromIterator<Range<T>> for RangeSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        Self::normalized(iter.into_iter().collect())
+    }
+}
+
+/// A function that shifts every number in some ranges by an offset per range and leaves the
+/// remaining numbers as they are, like the maps in an almanac.
+#[derive(Clone, Debug, Default)]
+pub struct IntervalMap<T> {
+    /// Disjoint source ranges, sorted by start, and the offset to add to the numbers in them
+    pieces: Vec<(Range<T>, T)>,
+}
+
+impl<T: Checked + Debug + Ord> IntervalMap<T> {
+    pub fn new() -> Self {
+        Self { pieces: Vec::new() }
+    }
+
+    /// Map every number in `source` to itself plus `offset`.
+    ///
+    /// Fails if `source` overlaps a range that is already mapped, since it would be ambiguous, or
+    /// if the image of `source` overflows.
+    pub fn insert(&mut self, source: Range<T>, offset: T) -> anyhow::Result<()> {
+        if source.end <= source.start {
+            return Ok(());
+        }
+        source.start.try_add(offset)?;
+        source.end.try_add(offset)?;
+        let i = self.pieces.partition_point(|(r, _)| r.start < source.start);
+        let overlaps = |j: usize| {
+            self.pieces
+                .get(j)
+                .map_or(false, |(r, _)| r.start < source.end && source.start < r.end)
+        };
+        if overlaps(i) || (0 < i && overlaps(i - 1)) {
+            bail!("Expected mapped ranges not to overlap but {source:?} does");
+        }
+        self.pieces.insert(i, (source, offset));
+        Ok(())
+    }
+
+    pub fn get(&self, value: T) -> T {
+        let i = self.pieces.partition_point(|(r, _)| r.end <= value);
+        match self.pieces.get(i) {
+            Some((r, offset)) if r.start <= value => value + *offset,
+            _ => value,
+        }
+    }
+
+    /// Return the image of every number in `set`
+    pub fn apply(&self, set: &RangeSet<T>) -> RangeSet<T> {
+        let mut result = Vec::with_capacity(set.ranges.len());
+        let mut unmapped = set.clone();
+        for (source, offset) in &self.pieces {
+            let (inside, outside) = unmapped.split(&RangeSet::from(source.clone()));
+            result.extend(
+                inside
+                    .ranges
+                    .into_iter()
+                    .map(|r| (r.start + *offset)..(r.end + *offset)),
+            );
+            unmapped = outside;
+        }
+        result.extend(unmapped.ranges);
+        RangeSet::normalized(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(ranges: &[(i32, i32)]) -> RangeSet<i32> {
+        ranges.iter().map(|&(a, b)| a..b).collect()
+    }
+
+    /// Enumerate the members in a window that covers every set used in these tests
+    fn members(set: &RangeSet<i32>) -> Vec<i32> {
+        (-50..50).filter(|&x| set.contains(x)).collect()
+    }
+
+    #[test]
+    fn sets_are_normalized() {
+        let actual = set(&[(5, 7), (0, 2), (2, 3), (6, 9), (4, 4)]);
+        assert_eq!(actual.ranges(), &[0..3, 5..9]);
+        assert_eq!(actual.len(), Ok(7));
+        assert_eq!(actual.first(), Some(0));
+        assert_eq!(actual.end(), Some(9));
+    }
+
+    #[test]
+    fn set_operations_agree_with_members() {
+        let a = set(&[(-10, -3), (0, 5), (8, 20), (30, 31)]);
+        let b = set(&[(-5, 1), (3, 4), (10, 12), (19, 40)]);
+        let (a_members, b_members) = (members(&a), members(&b));
+        let filtered = |f: &dyn Fn(&i32) -> bool| (-50..50).filter(f).collect::<Vec<_>>();
+        assert_eq!(
+            members(&a.union(&b)),
+            filtered(&|x| a_members.contains(x) || b_members.contains(x))
+        );
+        assert_eq!(
+            members(&a.intersection(&b)),
+            filtered(&|x| a_members.contains(x) && b_members.contains(x))
+        );
+        assert_eq!(
+            members(&a.difference(&b)),
+            filtered(&|x| a_members.contains(x) && !b_members.contains(x))
+        );
+        let (inside, outside) = a.split(&b);
+        assert_eq!(inside.union(&outside), a);
+        assert!(inside.intersection(&outside).is_empty());
+    }
+
+    #[test]
+    fn map_shifts_only_mapped_numbers() {
+        let mut map = IntervalMap::new();
+        map.insert(98..100, -48).unwrap();
+        map.insert(50..98, 2).unwrap();
+        assert!(map.insert(60..61, 0).is_err());
+        assert!(map.insert(0..i32::MAX, 1).is_err());
+        assert_eq!(map.get(79), 81);
+        assert_eq!(map.get(99), 51);
+        assert_eq!(map.get(13), 13);
+
+        let image = map.apply(&set(&[(79, 93), (55, 68), (97, 101)]));
+        assert_eq!(image.ranges(), &[50..52, 57..70, 81..95, 99..101]);
+    }
+
+    #[test]
+    fn returns_error_on_overflow() {
+        assert!(set(&[(i32::MIN, i32::MAX)]).len().is_err());
+        assert!(set(&[(i32::MIN, -1), (0, i32::MAX)]).len().is_err());
+        assert_eq!(set(&[(i32::MIN, -2), (0, 1)]).len(), Ok(i32::MAX));
+    }
+}
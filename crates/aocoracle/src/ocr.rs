@@ -0,0 +1,318 @@
+/// Reading the letters that some puzzles draw with lit pixels
+///
+/// Two fonts are known: a small one that is 6 pixels high and used in most years, and a large
+/// one that is 10 pixels high and used in 2018. Glyphs are separated by at least one dark column
+/// so their width and spacing does not need to be known in advance.
+use std::fmt::Write;
+
+use hashbrown::HashSet;
+use thiserror::Error;
+
+type Glyphs = &'static [(char, &'static [&'static str])];
+
+const SMALL: Glyphs = &[
+    ('A', &[".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', &["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', &[".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', &["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', &["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', &[".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', &["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', &["###", ".#.", ".#.", ".#.", ".#.", "###"]),
+    ('J', &["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', &["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', &["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', &[".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', &["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', &["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', &[".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', &["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', &["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', &["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+const LARGE: Glyphs = &[
+    (
+        'A',
+        &[
+            "..##..", ".#..#.", "#....#", "#....#", "#....#", "######", "#....#", "#....#",
+            "#....#", "#....#",
+        ],
+    ),
+    (
+        'B',
+        &[
+            "#####.", "#....#", "#....#", "#....#", "#####.", "#....#", "#....#", "#....#",
+            "#....#", "#####.",
+        ],
+    ),
+    (
+        'C',
+        &[
+            ".####.", "#....#", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....",
+            "#....#", ".####.",
+        ],
+    ),
+    (
+        'E',
+        &[
+            "######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....",
+            "#.....", "######",
+        ],
+    ),
+    (
+        'F',
+        &[
+            "######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....",
+            "#.....", "#.....",
+        ],
+    ),
+    (
+        'G',
+        &[
+            ".####.", "#....#", "#.....", "#.....", "#.....", "#..###", "#....#", "#....#",
+            "#...##", ".###.#",
+        ],
+    ),
+    (
+        'H',
+        &[
+            "#....#", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#",
+            "#....#", "#....#",
+        ],
+    ),
+    (
+        'J',
+        &[
+            "...###", "....#.", "....#.", "....#.", "....#.", "....#.", "....#.", "#...#.",
+            "#...#.", ".###..",
+        ],
+    ),
+    (
+        'K',
+        &[
+            "#....#", "#...#.", "#..#..", "#.#...", "##....", "##....", "#.#...", "#..#..",
+            "#...#.", "#....#",
+        ],
+    ),
+    (
+        'L',
+        &[
+            "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....",
+            "#.....", "######",
+        ],
+    ),
+    (
+        'N',
+        &[
+            "#....#", "##...#", "##...#", "#.#..#", "#.#..#", "#..#.#", "#..#.#", "#...##",
+            "#...##", "#....#",
+        ],
+    ),
+    (
+        'P',
+        &[
+            "#####.", "#....#", "#....#", "#....#", "#####.", "#.....", "#.....", "#.....",
+            "#.....", "#.....",
+        ],
+    ),
+    (
+        'R',
+        &[
+            "#####.", "#....#", "#....#", "#....#", "#####.", "#..#..", "#...#.", "#...#.",
+            "#....#", "#....#",
+        ],
+    ),
+    (
+        'X',
+        &[
+            "#....#", "#....#", ".#..#.", ".#..#.", "..##..", "..##..", ".#..#.", ".#..#.",
+            "#....#", "#....#",
+        ],
+    ),
+    (
+        'Z',
+        &[
+            "######", ".....#", ".....#", "....#.", "...#..", "..#...", ".#....", "#.....",
+            "#.....", "######",
+        ],
+    ),
+];
+
+#[derive(Debug, Error, PartialEq)]
+pub enum OcrError {
+    #[error("Expected at least one lit pixel")]
+    Empty,
+    #[error("Expected pixel to be one of '#', '.' but got {0:?}")]
+    InvalidPixel(char),
+    #[error("Expected text to be 6 or 10 pixels high but it is {0}")]
+    UnknownHeight(usize),
+    #[error("Could not recognise glyphs marked with '?' in {text:?}:\n{}", render(.unknown))]
+    Unrecognized {
+        /// The text with every glyph that could not be recognised replaced by `?`
+        text: String,
+        /// Drawings of the glyphs that could not be recognised, in the order they appear
+        unknown: Vec<Vec<String>>,
+    },
+}
+
+fn render(glyphs: &[Vec<String>]) -> String {
+    let mut result = String::new();
+    for glyph in glyphs {
+        for row in glyph {
+            writeln!(result, "{row}").expect("Writing to a string does not fail");
+        }
+        result.push('\n');
+    }
+    result
+}
+
+/// Return the rows of a glyph with the dark columns on either side removed
+fn trimmed(rows: &[&str]) -> Vec<String> {
+    let lit = |col: usize| {
+        rows.iter()
+            .any(|row| row.as_bytes().get(col) == Some(&b'#'))
+    };
+    let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    let first = (0..width).find(|&col| lit(col)).unwrap_or(0);
+    let last = (0..width).rev().find(|&col| lit(col)).unwrap_or(0);
+    rows.iter()
+        .map(|row| {
+            (first..=last)
+                .map(|col| row.as_bytes().get(col).map_or('.', |&b| b as char))
+                .collect()
+        })
+        .collect()
+}
+
+/// Read the text drawn by the lit pixels at `(x, y)`, with `y` growing downwards
+pub fn read_points<I>(points: I) -> Result<String, OcrError>
+where
+    I: IntoIterator<Item = (i64, i64)>,
+{
+    let points: HashSet<_> = points.into_iter().collect();
+    let (Some(x_min), Some(x_max)) = (
+        points.iter().map(|p| p.0).min(),
+        points.iter().map(|p| p.0).max(),
+    ) else {
+        return Err(OcrError::Empty);
+    };
+    let y_min = points.iter().map(|p| p.1).min().expect("There are points");
+    let y_max = points.iter().map(|p| p.1).max().expect("There are points");
+    let height = (y_max - y_min + 1) as usize;
+    let glyphs = match height {
+        6 => SMALL,
+        10 => LARGE,
+        _ => return Err(OcrError::UnknownHeight(height)),
+    };
+
+    let lit_columns: HashSet<_> = points.iter().map(|p| p.0).collect();
+    let mut text = String::new();
+    let mut unknown = Vec::new();
+    let mut x = x_min;
+    while x <= x_max {
+        let start = x;
+        while lit_columns.contains(&x) {
+            x += 1;
+        }
+        let rows: Vec<String> = (y_min..=y_max)
+            .map(|y| {
+                (start..x)
+                    .map(|x| match points.contains(&(x, y)) {
+                        true => '#',
+                        false => '.',
+                    })
+                    .collect()
+            })
+            .collect();
+        match glyphs.iter().find(|(_, glyph)| trimmed(glyph) == rows) {
+            Some((letter, _)) => text.push(*letter),
+            None => {
+                text.push('?');
+                unknown.push(rows);
+            }
+        }
+        while x <= x_max && !lit_columns.contains(&x) {
+            x += 1;
+        }
+    }
+
+    match unknown.is_empty() {
+        true => Ok(text),
+        false => Err(OcrError::Unrecognized { text, unknown }),
+    }
+}
+
+/// Read the text drawn with `#` for lit pixels and `.` or space for dark pixels
+pub fn read_text(text: &str) -> Result<String, OcrError> {
+    let mut points = Vec::new();
+    for (y, line) in text.lines().enumerate() {
+        for (x, pixel) in line.chars().enumerate() {
+            match pixel {
+                '#' => points.push((x as i64, y as i64)),
+                '.' | ' ' => {}
+                _ => return Err(OcrError::InvalidPixel(pixel)),
+            }
+        }
+    }
+    read_points(points)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Draw `word` the way the puzzles do, with `spacing` dark columns between glyphs
+    fn drawn(glyphs: Glyphs, word: &str, spacing: usize) -> String {
+        let height = glyphs[0].1.len();
+        let mut rows = vec![String::new(); height];
+        for letter in word.chars() {
+            let (_, glyph) = glyphs.iter().find(|(l, _)| *l == letter).unwrap();
+            for (row, line) in rows.iter_mut().zip(glyph.iter()) {
+                row.push_str(line);
+                row.push_str(&".".repeat(spacing));
+            }
+        }
+        rows.join("\n")
+    }
+
+    #[test]
+    fn every_glyph_is_read_back() {
+        let small: String = SMALL.iter().map(|(l, _)| l).collect();
+        assert_eq!(read_text(&drawn(SMALL, &small, 1)).unwrap(), small);
+        let large: String = LARGE.iter().map(|(l, _)| l).collect();
+        assert_eq!(read_text(&drawn(LARGE, &large, 2)).unwrap(), large);
+    }
+
+    #[test]
+    fn position_and_spacing_do_not_matter() {
+        let text = drawn(SMALL, "ZIP", 3);
+        let points = text.lines().enumerate().flat_map(|(y, line)| {
+            line.char_indices()
+                .filter(|(_, c)| *c == '#')
+                .map(move |(x, _)| (x as i64 - 100, y as i64 + 7))
+        });
+        assert_eq!(read_points(points).unwrap(), "ZIP");
+    }
+
+    #[test]
+    fn unknown_glyphs_are_reported() {
+        let mut text: Vec<String> = drawn(SMALL, "HELLO", 1).lines().map(String::from).collect();
+        text[0].replace_range(5..9, "#..#");
+        match read_text(&text.join("\n")) {
+            Err(OcrError::Unrecognized { text, unknown }) => {
+                assert_eq!(text, "H?LLO");
+                assert_eq!(unknown.len(), 1);
+                assert_eq!(unknown[0][0], "#..#");
+            }
+            other => panic!("Expected unrecognised glyph but got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn other_heights_are_rejected() {
+        assert_eq!(read_text("#\n#\n#"), Err(OcrError::UnknownHeight(3)));
+        assert_eq!(read_text("..."), Err(OcrError::Empty));
+        assert_eq!(read_text("#x"), Err(OcrError::InvalidPixel('x')));
+    }
+}
@@ -0,0 +1,39 @@
+/// Running candidates on a pool of threads
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use crate::{attempt, Attempt, Settings, Solver};
+
+/// Attempt every solver on `text` using `jobs` threads.
+///
+/// The attempts are returned in the same order as the solvers.
+pub fn attempt_all(
+    solvers: &[&dyn Solver],
+    text: &str,
+    jobs: usize,
+    settings: &Settings,
+) -> Vec<Attempt> {
+    let next = AtomicUsize::new(0);
+    let mut attempts: Vec<(usize, Attempt)> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..jobs.min(solvers.len()))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut result = Vec::new();
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        match solvers.get(i) {
+                            Some(solver) => result.push((i, attempt(*solver, text, settings))),
+                            None => return result,
+                        }
+                    }
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("Attempts catch panics"))
+            .collect()
+    });
+    attempts.sort_by_key(|(i, _)| *i);
+    attempts.into_iter().map(|(_, attempt)| attempt).collect()
+}
@@ -0,0 +1,169 @@
+/// Numbers that differ between the examples and the real inputs of a puzzle
+///
+/// Some puzzles state a number in the text, like the row to inspect, that is different for the
+/// example. Solvers declare such numbers as parameters with the value for real inputs as the
+/// default, and callers may override them by name. Solvers may also describe what the example
+/// looks like so that the example values can be used without the caller asking for them.
+use std::cell::RefCell;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use serde::Serialize;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Param {
+    pub name: &'static str,
+    /// The value for real inputs
+    pub default: i64,
+    /// The value for the example in the puzzle text, if it is different
+    pub example: Option<i64>,
+    pub description: &'static str,
+}
+
+impl Param {
+    pub const fn new(name: &'static str, default: i64, description: &'static str) -> Self {
+        Self {
+            name,
+            default,
+            example: None,
+            description,
+        }
+    }
+
+    pub const fn with_example(self, example: i64) -> Self {
+        Self {
+            example: Some(example),
+            ..self
+        }
+    }
+
+    /// Return the value of the parameter for the solver running on this thread
+    pub fn get(&self) -> i64 {
+        USED.with(|u| {
+            if let Some(used) = u.borrow_mut().as_mut() {
+                if !used.contains(&self.name) {
+                    used.push(self.name);
+                }
+            }
+        });
+        OVERRIDES.with(|o| {
+            o.borrow()
+                .iter()
+                .rev()
+                .find(|o| o.name == self.name)
+                .map_or(self.default, |o| o.value)
+        })
+    }
+}
+
+impl Display for Param {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}={}", self.name, self.default)
+    }
+}
+
+/// A value to use instead of the default of every parameter with the same name
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Override {
+    pub name: String,
+    pub value: i64,
+}
+
+impl Override {
+    pub fn new(name: &str, value: i64) -> Self {
+        Self {
+            name: name.to_string(),
+            value,
+        }
+    }
+}
+
+impl FromStr for Override {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, value) = s
+            .split_once('=')
+            .ok_or_else(|| anyhow!("Expected parameter like name=value but got {s:?}"))?;
+        Ok(Self::new(name, value.parse()?))
+    }
+}
+
+/// What kind of input a solver assumed that it was given
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Mode {
+    Real,
+    /// The input looks like the example, so the example values of parameters were used
+    Example,
+}
+
+impl Display for Mode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Real => "real",
+            Self::Example => "example",
+        })
+    }
+}
+
+/// Return overrides that give every parameter in `params` its example value
+pub fn example_overrides(params: &[Param]) -> Vec<Override> {
+    params
+        .iter()
+        .filter_map(|p| Some(Override::new(p.name, p.example?)))
+        .collect()
+}
+
+thread_local! {
+    static OVERRIDES: RefCell<Vec<Override>> = RefCell::new(Vec::new());
+    static USED: RefCell<Option<Vec<&'static str>>> = RefCell::new(None);
+}
+
+/// Run `func` with the parameters in `overrides` set, later overrides taking precedence.
+pub fn with_overrides<T>(overrides: &[Override], func: impl FnOnce() -> T) -> T {
+    let previous = OVERRIDES.with(|o| o.replace(overrides.to_vec()));
+    let result = func();
+    OVERRIDES.with(|o| o.replace(previous));
+    result
+}
+
+/// Run `func` and return the names of the parameters that it read
+pub fn with_usage<T>(func: impl FnOnce() -> T) -> (T, Vec<&'static str>) {
+    let previous = USED.with(|u| u.replace(Some(Vec::new())));
+    let result = func();
+    let used = USED.with(|u| u.replace(previous)).unwrap_or_default();
+    (result, used)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROW: Param = Param::new("row", 2000000, "Row to inspect").with_example(10);
+    const BOUND: Param = Param::new("bound", 4000000, "Largest coordinate");
+
+    #[test]
+    fn overrides_are_scoped() {
+        assert_eq!(ROW.get(), 2000000);
+        assert_eq!(with_usage(|| BOUND.get()).1, vec!["bound"]);
+        let overrides = ["row=10".parse().unwrap(), "other=1".parse().unwrap()];
+        assert_eq!(with_overrides(&overrides, || ROW.get()), 10);
+        assert_eq!(ROW.get(), 2000000);
+    }
+
+    #[test]
+    fn explicit_overrides_take_precedence_over_examples() {
+        let mut overrides = example_overrides(&[ROW, BOUND]);
+        assert_eq!(overrides, vec![Override::new("row", 10)]);
+        overrides.push(Override::new("row", 11));
+        assert_eq!(with_overrides(&overrides, || ROW.get()), 11);
+    }
+
+    #[test]
+    fn override_must_have_a_value() {
+        assert!("row".parse::<Override>().is_err());
+        assert!("row=ten".parse::<Override>().is_err());
+    }
+}
@@ -0,0 +1,173 @@
+/// Shared helpers for parsers that must account for every line of the input
+///
+/// Many puzzles have inputs that are just lists of numbers, so lenient parsers happily produce
+/// answers for inputs meant for other days. In strict mode the helpers reject anything that
+/// would not appear in an input generated for the puzzle, such as leading zeros and trailing
+/// blank lines.
+use std::cell::Cell;
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail};
+
+thread_local! {
+    static STRICT: Cell<bool> = Cell::new(false);
+}
+
+pub fn is_strict() -> bool {
+    STRICT.with(Cell::get)
+}
+
+/// Run `func` with strict parsing enabled or disabled.
+pub fn with_strict<T>(strict: bool, func: impl FnOnce() -> T) -> T {
+    let previous = STRICT.with(|s| s.replace(strict));
+    let result = func();
+    STRICT.with(|s| s.set(previous));
+    result
+}
+
+/// Parse a whole line as a number.
+///
+/// In strict mode the canonical form is required, i.e. no explicit `+` and no leading zeros.
+pub fn number<T>(s: &str) -> anyhow::Result<T>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    if is_strict() {
+        let digits = s.strip_prefix('-').unwrap_or(s);
+        if digits.starts_with('+') {
+            bail!("Expected no explicit sign but got {s:?}");
+        }
+        if digits.len() > 1 && digits.starts_with('0') {
+            bail!("Expected no leading zeros but got {s:?}");
+        }
+    }
+    Ok(s.parse()?)
+}
+
+/// Lines of an input that remember how far they have been consumed
+pub struct Lines<'a> {
+    lines: Vec<&'a str>,
+    next: usize,
+}
+
+impl<'a> Lines<'a> {
+    /// In strict mode the text must use `\n` line breaks and end with exactly one of them.
+    pub fn new(text: &'a str) -> anyhow::Result<Self> {
+        if is_strict() {
+            if text.contains('\r') {
+                bail!("Expected no carriage returns");
+            }
+            if !text.ends_with('\n') {
+                bail!("Expected input to end with a line break");
+            }
+        }
+        Ok(Self {
+            lines: text.lines().collect(),
+            next: 0,
+        })
+    }
+
+    /// Number of the line that would be returned next, counting from 1
+    pub fn line_number(&self) -> usize {
+        self.next + 1
+    }
+
+    pub fn peek(&self) -> Option<&'a str> {
+        self.lines.get(self.next).copied()
+    }
+
+    /// Parse every remaining line with `func`, naming the offending line on error
+    pub fn parse_rest<T>(
+        &mut self,
+        mut func: impl FnMut(&'a str) -> anyhow::Result<T>,
+    ) -> anyhow::Result<Vec<T>> {
+        let mut result = Vec::new();
+        while let Some(line) = self.peek() {
+            // The cause goes in the message so that it is not lost when only that is shown
+            let item = func(line).map_err(|e| anyhow!("On line {}: {e:#}", self.line_number()))?;
+            result.push(item);
+            self.next += 1;
+        }
+        Ok(result)
+    }
+
+    /// Return an error if there are unconsumed lines.
+    ///
+    /// Outside of strict mode trailing empty lines are ignored.
+    pub fn finish(self) -> anyhow::Result<()> {
+        let rest = &self.lines[self.next..];
+        if rest.is_empty() || !is_strict() && rest.iter().all(|l| l.is_empty()) {
+            return Ok(());
+        }
+        bail!(
+            "Expected end of input but got {} more lines starting with {:?} on line {}",
+            rest.len(),
+            rest[0],
+            self.line_number()
+        )
+    }
+}
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let line = self.peek()?;
+        self.next += 1;
+        Some(line)
+    }
+}
+
+/// Parse every line of `text` as a number, consuming the whole input.
+pub fn numbers<T>(text: &str) -> anyhow::Result<Vec<T>>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    let mut lines = Lines::new(text)?;
+    let result = lines.parse_rest(number)?;
+    lines.finish()?;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strict_numbers_must_be_canonical() {
+        assert_eq!(number::<i32>("+1").unwrap(), 1);
+        assert_eq!(number::<i32>("007").unwrap(), 7);
+        with_strict(true, || {
+            assert!(number::<i32>("+1").is_err());
+            assert!(number::<i32>("007").is_err());
+            assert!(number::<i32>("-07").is_err());
+            assert_eq!(number::<i32>("0").unwrap(), 0);
+            assert_eq!(number::<i32>("-10").unwrap(), -10);
+        });
+    }
+
+    #[test]
+    fn strict_lines_must_be_consumed() {
+        let mut lines = Lines::new("1\n2\n\n").unwrap();
+        assert_eq!(lines.by_ref().take(2).collect::<Vec<_>>(), vec!["1", "2"]);
+        assert!(lines.finish().is_ok());
+        with_strict(true, || {
+            let mut lines = Lines::new("1\n2\n\n").unwrap();
+            assert_eq!(lines.by_ref().take(2).collect::<Vec<_>>(), vec!["1", "2"]);
+            assert!(lines.finish().is_err());
+            assert!(Lines::new("1\n2").is_err());
+            assert!(Lines::new("1\r\n").is_err());
+        });
+    }
+
+    #[test]
+    fn errors_name_the_line_and_the_cause() {
+        let error = numbers::<i32>("1\nx\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "On line 2: invalid digit found in string"
+        );
+    }
+}
@@ -0,0 +1,151 @@
+/// Ordering answers by how likely they are to be right
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::params::Mode;
+use crate::trace::Event;
+use crate::{Answer, Part, Solver};
+
+/// An answer together with how confident the oracle is that it is the right one
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Guess {
+    pub year: u16,
+    pub day: u8,
+    pub part: Part,
+    #[serde(flatten)]
+    pub answer: Answer,
+    /// A number in the range `(0, 1]`, higher is better
+    pub confidence: f64,
+    /// Whether the solver took the input to be the example from the puzzle text
+    pub mode: Mode,
+    /// Time it took the solver to find the answer
+    #[serde(
+        rename = "elapsed_seconds",
+        serialize_with = "crate::report::serialize_seconds"
+    )]
+    pub elapsed: Duration,
+    /// Explanation of how the answer was found, if asked for
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub trace: Vec<Event>,
+}
+
+/// How much the fingerprint of the solver supports that the input is meant for it.
+///
+/// Fingerprints with more requirements are harder to match by accident and so give stronger
+/// support, and a fingerprint that does not match is evidence against.
+fn fingerprint_factor(solver: &dyn Solver, text: &str) -> f64 {
+    match solver.fingerprint() {
+        None => 0.5,
+        Some(fingerprint) if fingerprint.matches(text) => {
+            0.5 + 0.5 * fingerprint.num_requirement() as f64 / 3.0
+        }
+        Some(_) => 0.25,
+    }
+}
+
+/// How much the answer itself looks like an Advent of Code answer.
+///
+/// Answers are practically always positive, so zero and negative numbers are more likely to be
+/// the result of running a solver on input that happens to parse.
+fn plausibility_factor(answer: &Answer) -> f64 {
+    match answer {
+        Answer::Integer(0) => 0.5,
+        _ if answer.is_non_positive() => 0.75,
+        _ => 1.0,
+    }
+}
+
+pub fn confidence(solver: &dyn Solver, text: &str, answer: &Answer) -> f64 {
+    fingerprint_factor(solver, text) * plausibility_factor(answer)
+}
+
+/// Sort guesses from most to least confident day, keeping the parts of each day together.
+///
+/// The parts of a day answer the same input rather than compete with each other, so a day is
+/// as confident as its most confident part. Days that are equally confident keep their order.
+pub fn ranked(guesses: Vec<Guess>) -> Vec<Guess> {
+    let mut days: Vec<Vec<Guess>> = Vec::new();
+    for guess in guesses {
+        match days
+            .iter_mut()
+            .find(|d| (d[0].year, d[0].day) == (guess.year, guess.day))
+        {
+            Some(day) => day.push(guess),
+            None => days.push(vec![guess]),
+        }
+    }
+    let confidence = |day: &[Guess]| day.iter().map(|g| g.confidence).fold(0.0, f64::max);
+    days.sort_by(|a, b| confidence(b).total_cmp(&confidence(a)));
+    for day in days.iter_mut() {
+        day.sort_by_key(|g| g.part);
+    }
+    days.into_iter().flatten().collect()
+}
+
+/// Split ranked guesses into those for the best day and those for the runners-up
+pub fn split_best(guesses: &[Guess]) -> (&[Guess], &[Guess]) {
+    let num_best = match guesses.first() {
+        None => 0,
+        Some(best) => guesses
+            .iter()
+            .take_while(|g| (g.year, g.day) == (best.year, best.day))
+            .count(),
+    };
+    guesses.split_at(num_best)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guess(day: u8, part: Part, confidence: f64) -> Guess {
+        Guess {
+            year: 2022,
+            day,
+            part,
+            answer: Answer::Integer(1),
+            confidence,
+            mode: Mode::Real,
+            elapsed: Duration::ZERO,
+            trace: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn ranked_is_stable_and_descending() {
+        let actual: Vec<_> = ranked(vec![
+            guess(1, Part::One, 0.5),
+            guess(2, Part::One, 1.0),
+            guess(3, Part::One, 0.5),
+        ])
+        .into_iter()
+        .map(|g| g.day)
+        .collect();
+        assert_eq!(actual, vec![2, 1, 3]);
+    }
+
+    #[test]
+    fn parts_of_a_day_are_ranked_together() {
+        let guesses = ranked(vec![
+            guess(1, Part::One, 1.0),
+            guess(3, Part::One, 0.75),
+            guess(1, Part::Two, 0.5),
+        ]);
+        let actual: Vec<_> = guesses.iter().map(|g| (g.day, g.part)).collect();
+        assert_eq!(actual, vec![(1, Part::One), (1, Part::Two), (3, Part::One)]);
+
+        let (best, runners_up) = split_best(&guesses);
+        assert_eq!(best.len(), 2);
+        assert_eq!(runners_up.len(), 1);
+        assert_eq!(split_best(&[]), (&[][..], &[][..]));
+    }
+
+    #[test]
+    fn positive_answers_are_most_plausible() {
+        let factor = |s| plausibility_factor(&Answer::parse(s));
+        assert!(factor("0") < factor("-3"));
+        assert!(factor("-3") < factor("3"));
+        assert_eq!(factor("CMZ"), 1.0);
+    }
+}
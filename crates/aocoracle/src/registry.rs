@@ -0,0 +1,299 @@
+/// Solvers that register themselves where they are implemented
+use std::collections::BTreeMap;
+
+use std::any::type_name;
+
+use anyhow::anyhow;
+
+use crate::fingerprint::Fingerprint;
+use crate::params::Param;
+use crate::{y2018, y2020, y2021, y2022, y2023, Answer, AnyError, Part, Solver, Solvers};
+
+/// Return types that solver functions are allowed to have
+pub trait SolverOutput {
+    fn answer_type() -> &'static str;
+    fn into_answer(self) -> anyhow::Result<Answer>;
+}
+
+/// Older solvers format their answers themselves, so the kind has to be inferred
+impl SolverOutput for Result<String, AnyError> {
+    fn answer_type() -> &'static str {
+        type_name::<String>()
+    }
+
+    fn into_answer(self) -> anyhow::Result<Answer> {
+        self.map(|ok| Answer::parse(&ok))
+            .map_err(|err| anyhow!("{err:}"))
+    }
+}
+
+impl<T: Into<Answer>> SolverOutput for anyhow::Result<T> {
+    fn answer_type() -> &'static str {
+        type_name::<T>()
+    }
+
+    fn into_answer(self) -> anyhow::Result<Answer> {
+        self.map(Into::into)
+    }
+}
+
+type SolverFn = dyn Fn(&str) -> anyhow::Result<Answer> + Send + Sync;
+
+/// A cheap check that returns `false` only for inputs that cannot be meant for the solver
+pub type AcceptsFn = fn(&str) -> bool;
+
+pub struct Registration {
+    year: u16,
+    day: u8,
+    part: Part,
+    title: &'static str,
+    answer_type: &'static str,
+    fingerprint: Option<Fingerprint>,
+    params: Vec<Param>,
+    example: Option<Fingerprint>,
+    accepts: Option<AcceptsFn>,
+    /// Path of the module that registered the solver, used in error messages
+    module: &'static str,
+    func: Box<SolverFn>,
+}
+
+impl Registration {
+    pub fn new<F, T>(
+        year: u16,
+        day: u8,
+        part: Part,
+        title: &'static str,
+        module: &'static str,
+        func: F,
+    ) -> Self
+    where
+        F: Fn(&str) -> T + Send + Sync + 'static,
+        T: SolverOutput,
+    {
+        Self {
+            year,
+            day,
+            part,
+            title,
+            answer_type: T::answer_type(),
+            fingerprint: None,
+            params: Vec::new(),
+            example: None,
+            accepts: None,
+            module,
+            func: Box::new(move |s| func(s).into_answer()),
+        }
+    }
+
+    pub fn with_fingerprint(mut self, fingerprint: Option<Fingerprint>) -> Self {
+        self.fingerprint = fingerprint;
+        self
+    }
+
+    pub fn with_params(mut self, params: &[Param]) -> Self {
+        self.params = params.to_vec();
+        self
+    }
+
+    pub fn with_example(mut self, example: Option<Fingerprint>) -> Self {
+        self.example = example;
+        self
+    }
+
+    pub fn with_accepts(mut self, accepts: Option<AcceptsFn>) -> Self {
+        self.accepts = accepts;
+        self
+    }
+}
+
+impl Solver for Registration {
+    fn year(&self) -> u16 {
+        self.year
+    }
+
+    fn day(&self) -> u8 {
+        self.day
+    }
+
+    fn part(&self) -> Part {
+        self.part
+    }
+
+    fn title(&self) -> &'static str {
+        self.title
+    }
+
+    fn answer_type(&self) -> &'static str {
+        self.answer_type
+    }
+
+    fn fingerprint(&self) -> Option<&Fingerprint> {
+        self.fingerprint.as_ref()
+    }
+
+    fn params(&self) -> &[Param] {
+        &self.params
+    }
+
+    fn example(&self) -> Option<&Fingerprint> {
+        self.example.as_ref()
+    }
+
+    /// No puzzle has an empty input, whatever else the module checks
+    fn accepts(&self, text: &str) -> bool {
+        !text.is_empty() && self.accepts.map_or(true, |accepts| accepts(text))
+    }
+
+    fn solve(&self, text: &str) -> anyhow::Result<Answer> {
+        (self.func)(text)
+    }
+}
+
+/// Declare the solvers implemented in the calling module.
+///
+/// Example:
+/// ```ignore
+/// crate::registry::register_solvers! {
+///     2022, 15, "Beacon Exclusion Zone";
+///     fingerprint = Fingerprint::new().first_line(r"^Sensor at");
+///     params = [ROW, BOUND];
+///     example = Fingerprint::new().first_line(r"^Sensor at x=\d, ");
+///     accepts = |text| text.starts_with("Sensor at x=");
+///     One => part_1,
+///     Two => part_2,
+/// }
+/// ```
+///
+/// The `fingerprint`, `params`, `example` and `accepts` clauses are all optional.
+macro_rules! register_solvers {
+    (@ $h:tt [$($fp:tt)*] [$($param:tt)*] [$($ex:tt)*] [$($acc:tt)*]; fingerprint = $v:expr; $($rest:tt)*) => {
+        $crate::registry::register_solvers!(@ $h [Some($v)] [$($param)*] [$($ex)*] [$($acc)*]; $($rest)*);
+    };
+    (@ $h:tt [$($fp:tt)*] [$($param:tt)*] [$($ex:tt)*] [$($acc:tt)*]; params = [$($v:expr),* $(,)?]; $($rest:tt)*) => {
+        $crate::registry::register_solvers!(@ $h [$($fp)*] [$($v),*] [$($ex)*] [$($acc)*]; $($rest)*);
+    };
+    (@ $h:tt [$($fp:tt)*] [$($param:tt)*] [$($ex:tt)*] [$($acc:tt)*]; example = $v:expr; $($rest:tt)*) => {
+        $crate::registry::register_solvers!(@ $h [$($fp)*] [$($param)*] [Some($v)] [$($acc)*]; $($rest)*);
+    };
+    (@ $h:tt [$($fp:tt)*] [$($param:tt)*] [$($ex:tt)*] [$($acc:tt)*]; accepts = $v:expr; $($rest:tt)*) => {
+        $crate::registry::register_solvers!(@ $h [$($fp)*] [$($param)*] [$($ex)*] [Some($v)]; $($rest)*);
+    };
+    (@ [$y:literal, $d:literal, $t:literal] [$fp:expr] [$($param:expr),*] [$ex:expr] [$acc:expr]; $($p:ident => $f:expr),+ $(,)?) => {
+        pub(crate) fn registrations() -> Vec<$crate::registry::Registration> {
+            let fingerprint = $fp;
+            let params: &[$crate::params::Param] = &[$($param),*];
+            let example = $ex;
+            let accepts: Option<$crate::registry::AcceptsFn> = $acc;
+            vec![$(
+                $crate::registry::Registration::new(
+                    $y,
+                    $d,
+                    $crate::Part::$p,
+                    $t,
+                    module_path!(),
+                    $f,
+                )
+                .with_fingerprint(fingerprint.clone())
+                .with_params(params)
+                .with_example(example.clone())
+                .with_accepts(accepts),
+            )+]
+        }
+    };
+    ($y:literal, $d:literal, $t:literal; $($rest:tt)*) => {
+        $crate::registry::register_solvers!(@ [$y, $d, $t] [None] [] [None] [None]; $($rest)*);
+    };
+}
+pub(crate) use register_solvers;
+
+/// Declare the day modules of the calling year module and collect their solvers.
+macro_rules! register_days {
+    ($($d:ident),+ $(,)?) => {
+        $(pub mod $d;)+
+
+        pub(crate) fn registrations() -> Vec<$crate::registry::Registration> {
+            let mut result = Vec::new();
+            $(result.extend($d::registrations());)+
+            result
+        }
+    };
+}
+pub(crate) use register_days;
+
+fn registrations() -> impl Iterator<Item = Registration> {
+    y2018::registrations()
+        .into_iter()
+        .chain(y2020::registrations())
+        .chain(y2021::registrations())
+        .chain(y2022::registrations())
+        .chain(y2023::registrations())
+}
+
+/// Collect every registered solver.
+///
+/// # Panics
+///
+/// If more than one module registers a solver for the same year, day and part.
+pub fn available_solvers() -> Solvers {
+    unique_solvers(registrations())
+}
+
+fn unique_solvers<I>(registrations: I) -> Solvers
+where
+    I: IntoIterator<Item = Registration>,
+{
+    let mut modules: BTreeMap<_, &'static str> = BTreeMap::new();
+    let mut solvers: Solvers = BTreeMap::new();
+    for registration in registrations {
+        let key = (registration.year, registration.day, registration.part);
+        if let Some(other) = modules.insert(key, registration.module) {
+            panic!(
+                "Solver for {:04}:{:02}::{} is registered by both {} and {}",
+                key.0, key.1, key.2, other, registration.module
+            );
+        }
+        solvers.insert(key, Box::new(registration));
+    }
+    solvers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solver(_: &str) -> anyhow::Result<u8> {
+        Ok(0)
+    }
+
+    #[test]
+    fn every_solver_is_registered_once() {
+        assert_eq!(available_solvers().len(), registrations().count());
+    }
+
+    #[test]
+    fn metadata_matches_key() {
+        for ((year, day, part), solver) in available_solvers() {
+            assert_eq!(solver.year(), year);
+            assert_eq!(solver.day(), day);
+            assert_eq!(solver.part(), part);
+            assert!(!solver.title().is_empty());
+            assert!(!solver.answer_type().is_empty());
+        }
+    }
+
+    #[test]
+    fn no_solver_accepts_empty_input() {
+        for solver in available_solvers().values() {
+            assert!(!solver.accepts(""));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "registered by both aoclib::y2018::d01 and aoclib::y2018::d02")]
+    fn duplicate_registration_names_both_modules() {
+        unique_solvers([
+            Registration::new(2018, 1, Part::One, "", "aoclib::y2018::d01", solver),
+            Registration::new(2018, 1, Part::One, "", "aoclib::y2018::d02", solver),
+        ]);
+    }
+}
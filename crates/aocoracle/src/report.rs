@@ -0,0 +1,168 @@
+/// Machine readable accounts of what the oracle found
+use std::str::FromStr;
+use std::time::Duration;
+
+use serde::{Serialize, Serializer};
+
+use crate::{Guess, Part};
+
+/// How the result of a run is printed
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Format {
+    /// Bare answers, or `YYYY:DD::P = answer` lines when exhaustive
+    Text,
+    /// One JSON object with every answer and every rejected candidate
+    Json,
+    /// One JSON object per answer or rejected candidate, one per line
+    Jsonl,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            "jsonl" => Ok(Self::Jsonl),
+            _ => Err(format!(
+                "Expected one of 'text', 'json' or 'jsonl' but got {s}"
+            )),
+        }
+    }
+}
+
+pub(crate) fn serialize_seconds<S: Serializer>(d: &Duration, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_f64(d.as_secs_f64())
+}
+
+/// A candidate that did not produce an answer
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Rejection {
+    pub year: u16,
+    pub day: u8,
+    pub part: Part,
+    pub error: String,
+    /// The error is the message of a panic, which means that the solver has a bug
+    pub panicked: bool,
+}
+
+/// Everything that was learned from trying the candidates on one input
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct Report {
+    /// Ranked from most to least likely to be right
+    pub answers: Vec<Guess>,
+    pub rejected: Vec<Rejection>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum Record<'a> {
+    Answer(&'a Guess),
+    Rejected(&'a Rejection),
+}
+
+impl Report {
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    /// Return one line per answer, in rank order, followed by one line per rejected candidate
+    pub fn to_jsonl(&self) -> anyhow::Result<Vec<String>> {
+        let answers = self.answers.iter().map(Record::Answer);
+        let rejected = self.rejected.iter().map(Record::Rejected);
+        Ok(answers
+            .chain(rejected)
+            .map(|r| serde_json::to_string(&r))
+            .collect::<Result<_, _>>()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::params::Mode;
+    use crate::Answer;
+
+    fn report() -> Report {
+        Report {
+            answers: vec![Guess {
+                year: 2022,
+                day: 1,
+                part: Part::Two,
+                answer: Answer::Integer(45000),
+                confidence: 0.75,
+                mode: Mode::Real,
+                elapsed: Duration::from_millis(1500),
+                trace: Vec::new(),
+            }],
+            rejected: vec![Rejection {
+                year: 2021,
+                day: 1,
+                part: Part::Two,
+                error: "Expected at least 4 depths, got 3".into(),
+                panicked: false,
+            }],
+        }
+    }
+
+    // Scripts depend on these, so any change to the schema must be deliberate
+    #[test]
+    fn json_schema_is_stable() {
+        let actual: serde_json::Value = serde_json::from_str(&report().to_json().unwrap()).unwrap();
+        let expected = json!({
+            "answers": [{
+                "year": 2022,
+                "day": 1,
+                "part": 2,
+                "answer": "45000",
+                "answer_kind": "integer",
+                "confidence": 0.75,
+                "mode": "real",
+                "elapsed_seconds": 1.5,
+            }],
+            "rejected": [{
+                "year": 2021,
+                "day": 1,
+                "part": 2,
+                "error": "Expected at least 4 depths, got 3",
+                "panicked": false,
+            }],
+        });
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn jsonl_schema_is_stable() {
+        let actual: Vec<serde_json::Value> = report()
+            .to_jsonl()
+            .unwrap()
+            .iter()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let expected = vec![
+            json!({
+                "kind": "answer",
+                "year": 2022,
+                "day": 1,
+                "part": 2,
+                "answer": "45000",
+                "answer_kind": "integer",
+                "confidence": 0.75,
+                "mode": "real",
+                "elapsed_seconds": 1.5,
+            }),
+            json!({
+                "kind": "rejected",
+                "year": 2021,
+                "day": 1,
+                "part": 2,
+                "error": "Expected at least 4 depths, got 3",
+                "panicked": false,
+            }),
+        ];
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,256 @@
+/// Running tasks that depend on each other on a limited number of workers
+///
+/// A task can start once every task it depends on has finished and a worker is idle. Whenever
+/// several tasks could start, the one with the smallest priority goes first, so schedules are
+/// deterministic.
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, BinaryHeap};
+use std::fmt::Debug;
+use std::hash::Hash;
+
+use anyhow::bail;
+use hashbrown::HashMap;
+
+use crate::cancel;
+use crate::search;
+use crate::trace::Event;
+
+/// Tasks and the order in which they must be done
+#[derive(Clone, Debug, Default)]
+pub struct Dependencies<T> {
+    /// Every task and the tasks that cannot start before it has finished
+    successors: HashMap<T, BTreeSet<T>>,
+}
+
+impl<T: Clone + Debug + Eq + Hash + Ord> Dependencies<T> {
+    pub fn new() -> Self {
+        Self {
+            successors: HashMap::new(),
+        }
+    }
+
+    pub fn insert_task(&mut self, task: T) {
+        self.successors.entry(task).or_default();
+    }
+
+    /// Require `before` to finish before `after` can start
+    pub fn insert_edge(&mut self, before: T, after: T) {
+        self.insert_task(after.clone());
+        self.successors.entry(before).or_default().insert(after);
+    }
+
+    /// Return every task in ascending order
+    pub fn tasks(&self) -> Vec<T> {
+        self.successors
+            .keys()
+            .cloned()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    pub fn successors(&self, task: &T) -> impl Iterator<Item = &T> {
+        self.successors.get(task).into_iter().flatten()
+    }
+
+    /// Return the tasks in the order one worker would do them, picking the smallest when there is
+    /// a choice, or an error naming a cycle if the tasks cannot all be done.
+    pub fn order(&self) -> anyhow::Result<Vec<T>> {
+        search::topological_sort(self.tasks(), |task| {
+            self.successors(task).cloned().collect::<Vec<_>>()
+        })
+    }
+
+    /// Do every task on `num_workers` workers, given how long each task takes and its priority.
+    ///
+    /// Idle workers take the ready task with the smallest priority, and the worker with the
+    /// smallest number takes it. Tasks that finish at the same time all finish before any new
+    /// task starts.
+    pub fn schedule<K, FD, FP>(
+        &self,
+        num_workers: usize,
+        mut duration: FD,
+        mut priority: FP,
+    ) -> anyhow::Result<Timeline<T>>
+    where
+        K: Ord,
+        FD: FnMut(&T) -> u64,
+        FP: FnMut(&T) -> K,
+    {
+        if num_workers == 0 {
+            bail!("Expected at least one worker");
+        }
+        // Fails if there is a cycle, so every task is eventually done
+        self.order()?;
+
+        let mut num_predecessors: HashMap<&T, usize> =
+            self.successors.keys().map(|task| (task, 0)).collect();
+        for task in self.successors.values().flatten() {
+            *num_predecessors
+                .get_mut(task)
+                .expect("Every successor is a task") += 1;
+        }
+        let mut ready: BinaryHeap<_> = num_predecessors
+            .iter()
+            .filter(|(_, n)| **n == 0)
+            .map(|(&task, _)| Reverse((priority(task), task)))
+            .collect();
+
+        let mut now = 0;
+        let mut running: Vec<Option<Slot<T>>> = vec![None; num_workers];
+        let mut slots = Vec::with_capacity(self.successors.len());
+        loop {
+            for (worker, slot) in running.iter_mut().enumerate() {
+                if slot.is_some() {
+                    continue;
+                }
+                let Some(Reverse((_, task))) = ready.pop() else {
+                    break;
+                };
+                *slot = Some(Slot {
+                    task: task.clone(),
+                    worker,
+                    start: now,
+                    end: now + duration(task),
+                });
+            }
+
+            let Some(end) = running.iter().flatten().map(|slot| slot.end).min() else {
+                break;
+            };
+            now = end;
+            for slot in running.iter_mut() {
+                if slot.as_ref().map_or(true, |slot| slot.end != now) {
+                    continue;
+                }
+                let slot = slot.take().expect("Slot is running");
+                for successor in self.successors(&slot.task) {
+                    let n = num_predecessors
+                        .get_mut(successor)
+                        .expect("Every successor is a task");
+                    *n -= 1;
+                    if *n == 0 {
+                        ready.push(Reverse((priority(successor), successor)));
+                    }
+                }
+                slots.push(slot);
+            }
+            cancel::checkpoint()?;
+        }
+        slots.sort_by_key(|slot| (slot.start, slot.worker));
+        Ok(Timeline { num_workers, slots })
+    }
+}
+
+/// One task done by one worker
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Slot<T> {
+    pub task: T,
+    pub worker: usize,
+    pub start: u64,
+    /// The time at which the task has finished, exclusive
+    pub end: u64,
+}
+
+/// Who did what and when
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Timeline<T> {
+    pub num_workers: usize,
+    /// Every task, in the order they started
+    pub slots: Vec<Slot<T>>,
+}
+
+impl<T> Timeline<T> {
+    /// Return the time at which every task has finished
+    pub fn end(&self) -> u64 {
+        self.slots.iter().map(|slot| slot.end).max().unwrap_or(0)
+    }
+
+    /// Draw one row per worker and one column per unit of time, with `label` of the task that the
+    /// worker is doing or `.` if it is idle.
+    pub fn gantt(&self, label: impl Fn(&T) -> char) -> Vec<String> {
+        let mut rows = vec![vec!['.'; self.end() as usize]; self.num_workers];
+        for slot in &self.slots {
+            for column in &mut rows[slot.worker][slot.start as usize..slot.end as usize] {
+                *column = label(&slot.task);
+            }
+        }
+        rows.into_iter().map(String::from_iter).collect()
+    }
+
+    pub fn event(&self, label: impl Fn(&T) -> char) -> Event {
+        Event::grid("Timeline", self.gantt(label))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The dependencies between the steps of the example for 2018 day 7
+    fn example() -> Dependencies<char> {
+        let mut result = Dependencies::new();
+        for (before, after) in [
+            ('C', 'A'),
+            ('C', 'F'),
+            ('A', 'B'),
+            ('A', 'D'),
+            ('B', 'E'),
+            ('D', 'E'),
+            ('F', 'E'),
+        ] {
+            result.insert_edge(before, after);
+        }
+        result
+    }
+
+    fn duration(task: &char) -> u64 {
+        (*task as u8 - b'A' + 1) as u64
+    }
+
+    #[test]
+    fn tasks_are_ordered_lexicographically() {
+        let order: String = example().order().unwrap().into_iter().collect();
+        assert_eq!(order, "CABDFE");
+        let timeline = example().schedule(1, |_| 1, |&task| task).unwrap();
+        let order: String = timeline.slots.iter().map(|slot| slot.task).collect();
+        assert_eq!(order, "CABDFE");
+    }
+
+    #[test]
+    fn workers_share_tasks() {
+        let timeline = example().schedule(2, duration, |&task| task).unwrap();
+        assert_eq!(timeline.end(), 15);
+        assert_eq!(
+            timeline.gantt(|&task| task),
+            vec!["CCCABBDDDDEEEEE", "...FFFFFF......"]
+        );
+    }
+
+    #[test]
+    fn priority_breaks_ties() {
+        let timeline = example()
+            .schedule(1, duration, |&task| Reverse(task))
+            .unwrap();
+        let order: String = timeline.slots.iter().map(|slot| slot.task).collect();
+        assert_eq!(order, "CFADBE");
+        assert_eq!(timeline.end(), 21);
+    }
+
+    #[test]
+    fn cycles_are_named() {
+        let mut dependencies = example();
+        dependencies.insert_edge('E', 'A');
+        dependencies.insert_task('G');
+        let error = dependencies
+            .schedule(2, duration, |&task| task)
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Expected graph to be acyclic but found 'A' -> 'B' -> 'E' -> 'A'"
+        );
+        assert!(Dependencies::<char>::new()
+            .schedule(0, duration, |&t| t)
+            .is_err());
+    }
+}
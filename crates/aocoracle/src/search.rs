@@ -0,0 +1,403 @@
+/// Searches over graphs given implicitly by a function from a node to its successors
+///
+/// Every shortest path search can start from several nodes at once and stops at the first node
+/// that satisfies a predicate, so multi-source and multi-target searches need no extra code.
+/// Searches count the nodes they expand, report the count in their outcome and in the trace so
+/// that heuristics can be compared, and give up with an error when the solver is cancelled.
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+use anyhow::bail;
+use hashbrown::{HashMap, HashSet};
+use itertools::Itertools;
+use num::Zero;
+
+use crate::cancel;
+use crate::trace::{self, Event};
+
+/// A path through a graph and the sum of the costs of its edges
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Path<N, C> {
+    /// Every node on the path, from the start it was found from to the goal, both included
+    pub nodes: Vec<N>,
+    pub cost: C,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Outcome<N, C> {
+    /// The cheapest path from any start to any goal, if there is one
+    pub path: Option<Path<N, C>>,
+    /// Number of nodes whose successors were generated
+    pub expanded: usize,
+}
+
+/// Finish a search from the virtual root that precedes every start
+fn finished<N, C>(
+    found: Option<(Vec<Option<N>>, C)>,
+    expanded: usize,
+) -> anyhow::Result<Outcome<N, C>> {
+    // Cancellation empties the frontier so whatever was found may not be the cheapest
+    cancel::checkpoint()?;
+    trace::emit(|| Event::message(format!("Search expanded {expanded} nodes")));
+    let path = found.map(|(nodes, cost)| Path {
+        nodes: nodes.into_iter().flatten().collect(),
+        cost,
+    });
+    Ok(Outcome { path, expanded })
+}
+
+/// Find a path with the fewest edges from any of `starts` to any node that is a `success`
+pub fn bfs<N, FN, IN, FS>(
+    starts: impl IntoIterator<Item = N>,
+    mut successors: FN,
+    mut success: FS,
+) -> anyhow::Result<Outcome<N, usize>>
+where
+    N: Clone + Eq + Hash,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = N>,
+    FS: FnMut(&N) -> bool,
+{
+    let starts: Vec<N> = starts.into_iter().collect();
+    let mut expanded = 0;
+    let found = pathfinding::prelude::bfs(
+        &None,
+        |node: &Option<N>| match node {
+            None => starts.iter().cloned().map(Some).collect(),
+            Some(_) if cancel::is_cancelled() => Vec::new(),
+            Some(node) => {
+                expanded += 1;
+                successors(node).into_iter().map(Some).collect::<Vec<_>>()
+            }
+        },
+        |node| node.as_ref().map_or(false, &mut success),
+    );
+    // The path begins with the root, which is not counted
+    let found = found.map(|nodes| {
+        let cost = nodes.len() - 2;
+        (nodes, cost)
+    });
+    finished(found, expanded)
+}
+
+/// Find a cheapest path from any of `starts` to any node that is a `success`.
+///
+/// The `heuristic` must never overestimate the cost of reaching a goal from a node or the path
+/// found may not be the cheapest.
+pub fn astar<N, C, FN, IN, FH, FS>(
+    starts: impl IntoIterator<Item = N>,
+    mut successors: FN,
+    mut heuristic: FH,
+    mut success: FS,
+) -> anyhow::Result<Outcome<N, C>>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Zero,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (N, C)>,
+    FH: FnMut(&N) -> C,
+    FS: FnMut(&N) -> bool,
+{
+    let starts: Vec<N> = starts.into_iter().collect();
+    let mut expanded = 0;
+    let found = pathfinding::prelude::astar(
+        &None,
+        |node: &Option<N>| match node {
+            None => starts
+                .iter()
+                .map(|s| (Some(s.clone()), C::zero()))
+                .collect(),
+            Some(_) if cancel::is_cancelled() => Vec::new(),
+            Some(node) => {
+                expanded += 1;
+                successors(node)
+                    .into_iter()
+                    .map(|(n, c)| (Some(n), c))
+                    .collect::<Vec<_>>()
+            }
+        },
+        |node| node.as_ref().map_or(C::zero(), &mut heuristic),
+        |node| node.as_ref().map_or(false, &mut success),
+    );
+    finished(found, expanded)
+}
+
+/// Find a cheapest path from any of `starts` to any node that is a `success`
+pub fn dijkstra<N, C, FN, IN, FS>(
+    starts: impl IntoIterator<Item = N>,
+    successors: FN,
+    success: FS,
+) -> anyhow::Result<Outcome<N, C>>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Zero,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (N, C)>,
+    FS: FnMut(&N) -> bool,
+{
+    astar(starts, successors, |_| C::zero(), success)
+}
+
+/// Return every node that can be reached from `starts`, including the starts themselves
+pub fn reachable<N, FN, IN>(
+    starts: impl IntoIterator<Item = N>,
+    mut successors: FN,
+) -> anyhow::Result<HashSet<N>>
+where
+    N: Eq + Hash,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = N>,
+{
+    let mut result = HashSet::new();
+    let mut remaining: Vec<N> = starts.into_iter().collect();
+    while let Some(node) = remaining.pop() {
+        if result.contains(&node) {
+            continue;
+        }
+        cancel::checkpoint()?;
+        remaining.extend(successors(&node));
+        result.insert(node);
+    }
+    Ok(result)
+}
+
+/// Order every node reachable from `roots` so that each comes after all its predecessors.
+///
+/// Whenever several nodes could come next the smallest is picked, so the order is unique; wrap
+/// the nodes in `Reverse` to pick the largest instead. Fails naming a cycle if there is one.
+pub fn topological_sort<N, FN, IN>(
+    roots: impl IntoIterator<Item = N>,
+    mut successors: FN,
+) -> anyhow::Result<Vec<N>>
+where
+    N: Clone + Debug + Eq + Hash + Ord,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = N>,
+{
+    let mut graph: HashMap<N, Vec<N>> = HashMap::new();
+    let mut num_predecessors: HashMap<N, usize> = HashMap::new();
+    let mut remaining: Vec<N> = roots.into_iter().collect();
+    while let Some(node) = remaining.pop() {
+        if graph.contains_key(&node) {
+            continue;
+        }
+        cancel::checkpoint()?;
+        let nexts: Vec<N> = successors(&node).into_iter().collect();
+        num_predecessors.entry(node.clone()).or_default();
+        for next in &nexts {
+            *num_predecessors.entry(next.clone()).or_default() += 1;
+            remaining.push(next.clone());
+        }
+        graph.insert(node, nexts);
+    }
+
+    let mut ready: BinaryHeap<_> = num_predecessors
+        .iter()
+        .filter(|(_, n)| **n == 0)
+        .map(|(node, _)| Reverse(node.clone()))
+        .collect();
+    let mut result = Vec::with_capacity(graph.len());
+    while let Some(Reverse(node)) = ready.pop() {
+        for next in &graph[&node] {
+            let n = num_predecessors
+                .get_mut(next)
+                .expect("Every successor has been counted");
+            *n -= 1;
+            if *n == 0 {
+                ready.push(Reverse(next.clone()));
+            }
+        }
+        result.push(node);
+    }
+
+    if result.len() < graph.len() {
+        let mut blocked: Vec<_> = num_predecessors
+            .into_iter()
+            .filter(|(_, n)| 0 < *n)
+            .map(|(node, _)| node)
+            .collect();
+        blocked.sort();
+        let cycle = find_cycle(blocked, |node| graph[node].clone())?
+            .expect("Nodes are only blocked by a cycle");
+        bail!(
+            "Expected graph to be acyclic but found {}",
+            cycle
+                .iter()
+                .chain(cycle.first())
+                .map(|node| format!("{node:?}"))
+                .join(" -> ")
+        );
+    }
+    Ok(result)
+}
+
+/// Return the nodes on a cycle reachable from `roots`, in the order they are visited, if any
+pub fn find_cycle<N, FN, IN>(
+    roots: impl IntoIterator<Item = N>,
+    mut successors: FN,
+) -> anyhow::Result<Option<Vec<N>>>
+where
+    N: Clone + Eq + Hash,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = N>,
+{
+    let mut finished = HashSet::new();
+    for root in roots {
+        if finished.contains(&root) {
+            continue;
+        }
+        // A depth first search where `path` holds the nodes being visited and `pending` holds
+        // the successors of each that remain to be visited
+        let mut pending = vec![successors(&root)
+            .into_iter()
+            .collect::<Vec<_>>()
+            .into_iter()];
+        let mut path = vec![root];
+        while let Some(nexts) = pending.last_mut() {
+            cancel::checkpoint()?;
+            match nexts.next() {
+                Some(next) => {
+                    if let Some(i) = path.iter().position(|node| *node == next) {
+                        return Ok(Some(path.split_off(i)));
+                    }
+                    if !finished.contains(&next) {
+                        pending.push(
+                            successors(&next)
+                                .into_iter()
+                                .collect::<Vec<_>>()
+                                .into_iter(),
+                        );
+                        path.push(next);
+                    }
+                }
+                None => {
+                    pending.pop();
+                    finished.insert(path.pop().expect("Path is as long as pending"));
+                }
+            }
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    type Point = (i32, i32);
+
+    /// Step to the four neighbours on an open grid of `size` by `size` tiles
+    fn open(size: i32) -> impl Fn(&Point) -> Vec<(Point, u32)> {
+        move |&(x, y)| {
+            [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)]
+                .into_iter()
+                .filter(|&(x, y)| 0 <= x && x < size && 0 <= y && y < size)
+                .map(|p| (p, 1))
+                .collect()
+        }
+    }
+
+    #[test]
+    fn bfs_finds_nearest_pair_of_start_and_goal() {
+        let line = |&n: &i32| [n - 1, n + 1].into_iter().filter(|n| (0..=20).contains(n));
+        let outcome = bfs([0, 10], line, |&n| n == 7 || n == 20).unwrap();
+        let path = outcome.path.unwrap();
+        assert_eq!(path.nodes, vec![10, 9, 8, 7]);
+        assert_eq!(path.cost, 3);
+
+        let outcome = bfs([3], line, |&n| n == 3).unwrap();
+        assert_eq!(outcome.path.unwrap().cost, 0);
+        assert_eq!(outcome.expanded, 0);
+
+        assert!(bfs([0], line, |&n| n == 21).unwrap().path.is_none());
+        assert!(bfs([], line, |_| true).unwrap().path.is_none());
+    }
+
+    #[test]
+    fn heuristic_reduces_expanded_nodes_but_not_cost() {
+        let goal = (19, 19);
+        let success = |p: &Point| *p == goal;
+        let manhattan = |&(x, y): &Point| (goal.0 - x).unsigned_abs() + (goal.1 - y).unsigned_abs();
+
+        let uninformed = dijkstra([(0, 0)], open(20), success).unwrap();
+        let informed = astar([(0, 0)], open(20), manhattan, success).unwrap();
+        assert_eq!(uninformed.path.as_ref().unwrap().cost, 38);
+        assert_eq!(informed.path.as_ref().unwrap().cost, 38);
+        assert_eq!(informed.path.unwrap().nodes.len(), 39);
+        assert!(informed.expanded < uninformed.expanded);
+    }
+
+    #[test]
+    fn reachable_includes_starts() {
+        let walls = |&(x, y): &Point| x == 2 || y == 2;
+        let rooms = |p: &Point| {
+            open(5)(p)
+                .into_iter()
+                .map(|(p, _)| p)
+                .filter(|p| !walls(p))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(reachable([(0, 0)], rooms).unwrap().len(), 4);
+        assert_eq!(reachable([(0, 0), (4, 4)], rooms).unwrap().len(), 8);
+    }
+
+    #[test]
+    fn topological_sort_breaks_ties_by_order() {
+        let edges = [
+            ('C', 'A'),
+            ('C', 'F'),
+            ('A', 'B'),
+            ('A', 'D'),
+            ('B', 'E'),
+            ('D', 'E'),
+            ('F', 'E'),
+        ];
+        let successors = |&node: &char| {
+            edges
+                .iter()
+                .filter(move |(prec, _)| *prec == node)
+                .map(|(_, succ)| *succ)
+        };
+        let actual: String = topological_sort(['C'], successors)
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(actual, "CABDFE");
+
+        let reversed: String = topological_sort([Reverse('C')], |node: &Reverse<char>| {
+            successors(&node.0).map(Reverse)
+        })
+        .unwrap()
+        .into_iter()
+        .map(|node| node.0)
+        .collect();
+        assert_eq!(reversed, "CFADBE");
+    }
+
+    #[test]
+    fn topological_sort_rejects_cycles() {
+        let successors = |&n: &u8| match n {
+            0 => vec![1],
+            1 => vec![2],
+            2 => vec![3, 1],
+            _ => vec![],
+        };
+        let error = topological_sort([0], successors).unwrap_err();
+        assert!(error.to_string().ends_with("found 1 -> 2 -> 1"));
+        assert_eq!(find_cycle([0], successors).unwrap(), Some(vec![1, 2]));
+        assert_eq!(find_cycle([3], successors).unwrap(), None);
+    }
+
+    #[test]
+    fn searches_give_up_when_cancelled() {
+        let (outcome, expired) = cancel::with_budget(Some(Duration::ZERO), || {
+            bfs([0u64], |&n| [n + 1], |_| false)
+        });
+        assert!(expired);
+        assert!(outcome.is_err());
+    }
+}
@@ -1,8 +1,7 @@
+use crate::dataset::{Dataset, Input};
 use crate::Part;
-use glob::glob;
 use std::any::type_name;
 use std::fmt::Debug;
-use std::fs;
 
 fn year_day(file: &str) -> (u16, u8) {
     let re = regex::Regex::new(r"y(\d{4})/d(\d{2})").expect("Hard coded regex is valid");
@@ -12,42 +11,27 @@ fn year_day(file: &str) -> (u16, u8) {
     (year, day)
 }
 
+fn dataset() -> Dataset {
+    Dataset::new("../../data")
+}
+
 pub fn read_input(year: u16, day: u8, stem: &str) -> String {
-    fs::read_to_string(format!(
-        "../../data/{:04}/{:02}/inputs/{}.txt",
-        year, day, stem
-    ))
-    .unwrap()
+    dataset()
+        .read_input(&Input {
+            year,
+            day,
+            stem: stem.to_string(),
+        })
+        .unwrap()
 }
 
 pub fn available_inputs() -> Vec<(u16, u8, String)> {
-    let mut result = Vec::new();
-    for entry in glob("../../data/*/*/inputs/*.txt").unwrap() {
-        let path = entry.unwrap();
-        let stem = path.file_stem().unwrap().to_str().unwrap();
-        let day = path
-            .ancestors()
-            .nth(2)
-            .unwrap()
-            .file_stem()
-            .unwrap()
-            .to_str()
-            .unwrap();
-        let year = path
-            .ancestors()
-            .nth(3)
-            .unwrap()
-            .file_stem()
-            .unwrap()
-            .to_str()
-            .unwrap();
-        result.push((
-            year.parse().unwrap(),
-            day.parse().unwrap(),
-            String::from(stem),
-        ))
-    }
-    result
+    dataset()
+        .inputs()
+        .unwrap()
+        .into_iter()
+        .map(|i| (i.year, i.day, i.stem))
+        .collect()
 }
 
 pub fn actual_answer<F, T, U>(file: &str, func: F, stem: &str) -> Result<T, U>
@@ -59,10 +43,14 @@ where
 }
 
 pub fn expected_answer(year: u16, day: u8, part: Part, stem: &str) -> Option<String> {
-    fs::read_to_string(format!(
-        "../../data/{year:04}/{day:02}/answers/{part:01}/{stem}.txt"
-    ))
-    .ok()
+    dataset().expected_answer(
+        &Input {
+            year,
+            day,
+            stem: stem.to_string(),
+        },
+        part,
+    )
 }
 
 pub fn assert_correct_answer_on_correct_input_given_file<F, T, U>(
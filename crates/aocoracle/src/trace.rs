@@ -0,0 +1,118 @@
+/// Optional explanations of how a solver arrived at its answer
+///
+/// Solvers emit events unconditionally; they are only constructed and kept when the caller has
+/// asked for a trace, so tracing costs next to nothing otherwise.
+use std::cell::RefCell;
+use std::fmt::{Display, Formatter};
+
+use serde::Serialize;
+
+/// Number of events after which further events are dropped
+const MAX_EVENTS: usize = 1000;
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Event {
+    Message {
+        text: String,
+    },
+    /// A picture of some two dimensional state, one string per row
+    Grid {
+        label: String,
+        rows: Vec<String>,
+    },
+    /// A description of some other state
+    State {
+        label: String,
+        state: String,
+    },
+}
+
+impl Event {
+    pub fn message(text: impl Into<String>) -> Self {
+        Self::Message { text: text.into() }
+    }
+
+    pub fn grid(label: impl Into<String>, rows: impl IntoIterator<Item = String>) -> Self {
+        Self::Grid {
+            label: label.into(),
+            rows: rows.into_iter().collect(),
+        }
+    }
+
+    pub fn state(label: impl Into<String>, state: impl Into<String>) -> Self {
+        Self::State {
+            label: label.into(),
+            state: state.into(),
+        }
+    }
+}
+
+impl Display for Event {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Message { text } => write!(f, "{text}"),
+            Self::Grid { label, rows } => {
+                write!(f, "{label}:")?;
+                for row in rows {
+                    write!(f, "\n{row}")?;
+                }
+                Ok(())
+            }
+            Self::State { label, state } => write!(f, "{label}: {state}"),
+        }
+    }
+}
+
+thread_local! {
+    static EVENTS: RefCell<Option<Vec<Event>>> = RefCell::new(None);
+}
+
+pub fn is_enabled() -> bool {
+    EVENTS.with(|e| e.borrow().is_some())
+}
+
+/// Record the event returned by `func` if the solver running on this thread is being traced
+pub fn emit(func: impl FnOnce() -> Event) {
+    EVENTS.with(|e| {
+        if let Some(events) = e.borrow_mut().as_mut() {
+            match events.len() {
+                n if n < MAX_EVENTS => events.push(func()),
+                MAX_EVENTS => events.push(Event::message("Trace truncated")),
+                _ => {}
+            }
+        }
+    })
+}
+
+/// Run `func` and return the events it emitted, which are always none unless `enabled`
+pub fn with_trace<T>(enabled: bool, func: impl FnOnce() -> T) -> (T, Vec<Event>) {
+    let previous = EVENTS.with(|e| e.replace(enabled.then(Vec::new)));
+    let result = func();
+    let events = EVENTS.with(|e| e.replace(previous));
+    (result, events.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_are_only_kept_when_enabled() {
+        let emit_one = || emit(|| Event::message("Hello"));
+        assert_eq!(with_trace(false, emit_one).1, vec![]);
+        assert_eq!(with_trace(true, emit_one).1, vec![Event::message("Hello")]);
+        assert!(!is_enabled());
+    }
+
+    #[test]
+    fn trace_is_truncated() {
+        let (_, events) = with_trace(true, || {
+            for i in 0..2 * MAX_EVENTS {
+                emit(|| Event::state("i", i.to_string()));
+            }
+        });
+        assert_eq!(events.len(), MAX_EVENTS + 1);
+        assert_eq!(events.last(), Some(&Event::message("Trace truncated")));
+    }
+}
@@ -0,0 +1,202 @@
+/// Checking solvers against the answers stored in a dataset
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Display, Formatter};
+
+use crate::dataset::{Dataset, Input};
+use crate::{candidates, report, Cli, Part};
+
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum Verdict {
+    Correct,
+    /// The solver for the day gave another answer
+    Wrong,
+    /// A solver gave an answer for an input meant for another day, registered or not
+    Inserted,
+    /// The solver for the day gave no answer although one is stored
+    Missing,
+    /// The solver panicked, which is a bug whichever day the input is for
+    Panicked,
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Correct => "correct",
+            Self::Wrong => "wrong",
+            Self::Inserted => "inserted",
+            Self::Missing => "missing",
+            Self::Panicked => "panicked",
+        })
+    }
+}
+
+/// The verdict on one solver for one input
+pub struct Check {
+    pub input: Input,
+    pub solver: (u16, u8, Part),
+    pub verdict: Verdict,
+}
+
+/// Run every candidate on every input in `dataset`.
+///
+/// Inputs for days without a candidate are included so that candidates that wrongly answer
+/// them are reported.
+pub fn verify(args: &Cli, dataset: &Dataset) -> anyhow::Result<Vec<Check>> {
+    let keys: Vec<_> = candidates(args)?.into_keys().collect();
+    // With a day given the first failing candidate would fail the whole report, taking the
+    // answers of the other candidates with it, so try every solver and keep the relevant ones.
+    let mut args = args.clone();
+    args.year = None;
+    args.day = None;
+    args.part = None;
+    args.exhaustive = true;
+    args.no_fingerprint = true;
+
+    let mut result = Vec::new();
+    for input in dataset.inputs()? {
+        log::info!("Verifying {:?}", input);
+        let text = dataset.read_input(&input)?;
+        let report = report(&args, &text)?;
+        let answers: BTreeMap<_, _> = report
+            .answers
+            .into_iter()
+            .map(|g| ((g.year, g.day, g.part), g.answer))
+            .filter(|(key, _)| keys.contains(key))
+            .collect();
+        let panicked: BTreeSet<_> = report
+            .rejected
+            .into_iter()
+            .filter(|r| r.panicked)
+            .map(|r| (r.year, r.day, r.part))
+            .filter(|key| keys.contains(key))
+            .collect();
+        for &key in &keys {
+            if panicked.contains(&key) {
+                result.push(Check {
+                    input: input.clone(),
+                    solver: key,
+                    verdict: Verdict::Panicked,
+                });
+                continue;
+            }
+            let verdict = match (
+                (key.0, key.1) == (input.year, input.day),
+                answers.get(&key),
+                dataset.expected_answer(&input, key.2),
+            ) {
+                (false, None, _) => continue,
+                (false, Some(_), _) => Verdict::Inserted,
+                (true, _, None) => continue,
+                (true, None, Some(_)) => Verdict::Missing,
+                (true, Some(a), Some(e)) if a.matches(&e) => Verdict::Correct,
+                (true, Some(_), Some(_)) => Verdict::Wrong,
+            };
+            result.push(Check {
+                input: input.clone(),
+                solver: key,
+                verdict,
+            });
+        }
+    }
+    Ok(result)
+}
+
+/// Return one line per check that is not correct followed by the number of checks per verdict
+pub fn verify_text(checks: &[Check]) -> Vec<String> {
+    let mut result: Vec<String> = checks
+        .iter()
+        .filter(|c| c.verdict != Verdict::Correct)
+        .map(|c| {
+            let Input { year, day, stem } = &c.input;
+            let (solver_year, solver_day, part) = c.solver;
+            format!(
+                "{year:04}:{day:02}:{stem} {solver_year:04}:{solver_day:02}::{part:01} {}",
+                c.verdict
+            )
+        })
+        .collect();
+    let count = |verdict| checks.iter().filter(|c| c.verdict == verdict).count();
+    result.push(
+        [
+            Verdict::Correct,
+            Verdict::Wrong,
+            Verdict::Inserted,
+            Verdict::Missing,
+            Verdict::Panicked,
+        ]
+        .map(|verdict| format!("{verdict}: {}", count(verdict)))
+        .join(", "),
+    );
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verify_text_lists_problems_and_counts() {
+        let input = Input {
+            year: 2022,
+            day: 1,
+            stem: "EXAMPLE".into(),
+        };
+        let check = |part, verdict| Check {
+            input: input.clone(),
+            solver: (2022, 1, part),
+            verdict,
+        };
+        let checks = vec![
+            check(Part::One, Verdict::Correct),
+            check(Part::Two, Verdict::Missing),
+        ];
+        assert_eq!(
+            verify_text(&checks),
+            vec![
+                "2022:01:EXAMPLE 2022:01::2 missing",
+                "correct: 1, wrong: 0, inserted: 0, missing: 1, panicked: 0",
+            ]
+        );
+    }
+
+    #[test]
+    fn failures_of_other_years_do_not_hide_correct_answers() {
+        let root = std::env::temp_dir().join(format!("aocoracle-verify-{}", std::process::id()));
+        let dataset = Dataset::new(&root);
+        let input = Input {
+            year: 2018,
+            day: 1,
+            stem: "EXAMPLE".into(),
+        };
+        // No solver is registered for 2019 so any answer is an insertion
+        let unregistered = Input {
+            year: 2019,
+            ..input.clone()
+        };
+        // The negative number makes the solver for 2020 day 1 fail
+        for (path, text) in [
+            (dataset.input_path(&input), "+1\n-2\n+3\n+1\n"),
+            (dataset.answer_path(&input, Part::One), "3\n"),
+            (dataset.answer_path(&input, Part::Two), "2\n"),
+            (dataset.input_path(&unregistered), "+1\n-2\n+3\n+1\n"),
+        ] {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, text).unwrap();
+        }
+
+        let checks = verify(&Cli::new(None, Some(1), None, false), &dataset);
+        std::fs::remove_dir_all(&root).unwrap();
+
+        let verdicts: BTreeMap<_, _> = checks
+            .unwrap()
+            .into_iter()
+            .map(|c| ((c.input.year, c.solver), c.verdict))
+            .collect();
+        assert_eq!(verdicts[&(2018, (2018, 1, Part::One))], Verdict::Correct);
+        assert_eq!(verdicts[&(2018, (2018, 1, Part::Two))], Verdict::Correct);
+        assert!(!verdicts.contains_key(&(2018, (2020, 1, Part::One))));
+        assert_eq!(verdicts[&(2019, (2018, 1, Part::One))], Verdict::Inserted);
+        assert_eq!(verdicts[&(2019, (2018, 1, Part::Two))], Verdict::Inserted);
+        assert!(verdicts.keys().all(|(_, (_, day, _))| *day == 1));
+    }
+}
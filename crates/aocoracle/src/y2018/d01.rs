@@ -1,20 +1,25 @@
+use crate::checked::{Checked, CheckedIter};
+use crate::fingerprint::Fingerprint;
+use crate::parse::{self, Lines};
 use crate::AnyError;
 use hashbrown::HashSet;
 
 fn _changes(text: &str) -> Result<Vec<i32>, AnyError> {
     let re = regex::Regex::new(r"^([-+]\d+)$").expect("Hard coded regex is valid");
-    let mut result = Vec::new();
-    for line in text.lines() {
+    let mut lines = Lines::new(text)?;
+    let result = lines.parse_rest(|line| {
         let cap = re
             .captures(line)
-            .ok_or(format!("Could not capture a password on line {}", line))?;
-        result.push(cap[1].parse::<i32>()?);
-    }
+            .ok_or_else(|| anyhow::anyhow!("Could not capture a change on line {}", line))?;
+        // Every change has an explicit sign so only the digits need to be canonical
+        parse::number(cap[1].strip_prefix('+').unwrap_or(&cap[1]))
+    })?;
+    lines.finish()?;
     Ok(result)
 }
 
 pub fn part_1(input: &str) -> Result<String, AnyError> {
-    let frequency: i32 = _changes(input)?.into_iter().sum();
+    let frequency = _changes(input)?.into_iter().try_sum()?;
     Ok(format!("{}", frequency))
 }
 
@@ -24,7 +29,7 @@ pub fn part_2(input: &str) -> Result<String, AnyError> {
     let mut frequency = 0;
     for delta in _changes(input)?.into_iter().cycle() {
         seen.insert(frequency);
-        frequency += delta;
+        frequency = frequency.try_add(delta)?;
         if seen.contains(&frequency) {
             return Ok(format!("{}", frequency));
         }
@@ -34,6 +39,13 @@ pub fn part_2(input: &str) -> Result<String, AnyError> {
     )
 }
 
+crate::registry::register_solvers! {
+    2018, 1, "Chronal Calibration";
+    fingerprint = Fingerprint::new().alphabet("+-0123456789").first_line(r"^[+-]\d+$");
+    One => part_1,
+    Two => part_2,
+}
+
 #[cfg(test)]
 mod tests {
     use crate::testing::{assert_correct_answer_on_correct_input, assert_error_on_wrong_input};
@@ -65,4 +77,11 @@ mod tests {
     fn returns_error_on_wrong_input() {
         assert_error_on_wrong_input!(part_1, part_2);
     }
+
+    #[test]
+    fn returns_error_on_overflow() {
+        let input = format!("+{}\n+1", i32::MAX);
+        assert!(part_1(&input).is_err());
+        assert!(part_2(&input).is_err());
+    }
 }
@@ -1,3 +1,5 @@
+use crate::fingerprint::{Fingerprint, LOWERCASE};
+use crate::parse::Lines;
 use crate::AnyError;
 use hashbrown::HashSet;
 use itertools::Itertools;
@@ -14,7 +16,7 @@ impl FromStr for Input {
         let mut expected_len = None;
         let mut ids = Vec::new();
         let re = regex::Regex::new(r"^([a-z]+)$").expect("Hard coded regex is valid");
-        for line in s.lines() {
+        for line in Lines::new(s)? {
             let id: String = re
                 .captures(line)
                 .ok_or(format!("Could not capture an id on line {}", line))?[1]
@@ -93,6 +95,13 @@ pub fn part_2(input: &str) -> Result<String, AnyError> {
     )?)
 }
 
+crate::registry::register_solvers! {
+    2018, 2, "Inventory Management System";
+    fingerprint = Fingerprint::new().alphabet(LOWERCASE).first_line(r"^[a-z]+$");
+    One => part_1,
+    Two => part_2,
+}
+
 #[cfg(test)]
 mod tests {
     use crate::testing::{assert_correct_answer_on_correct_input, assert_error_on_wrong_input};
@@ -5,7 +5,9 @@ use std::str::FromStr;
 
 use itertools::Itertools;
 
-use crate::rect::Rectangle;
+use crate::fingerprint::Fingerprint;
+use crate::geom::Rectangle;
+use crate::parse::{self, Lines};
 use crate::AnyError;
 
 struct Input {
@@ -50,18 +52,18 @@ impl FromStr for Input {
         let mut claims = HashMap::new();
         let re = regex::Regex::new(r"^#(\d+) @ (\d+),(\d+): (\d+)x(\d+)$")
             .expect("Hard coded regex is valid");
-        for line in s.lines() {
+        for line in Lines::new(s)? {
             let cap = re
                 .captures(line)
                 .ok_or(format!("Could not capture a claim on line {}", line))?;
 
             claims.insert(
-                cap[1].parse::<usize>()?,
+                parse::number::<usize>(&cap[1])?,
                 Rectangle::<u32>::new(
-                    cap[2].parse::<u32>()?,
-                    cap[3].parse::<u32>()?,
-                    cap[4].parse::<u32>()?,
-                    cap[5].parse::<u32>()?,
+                    parse::number(&cap[2])?,
+                    parse::number(&cap[3])?,
+                    parse::number(&cap[4])?,
+                    parse::number(&cap[5])?,
                 ),
             );
         }
@@ -77,6 +79,13 @@ pub fn part_2(input: &str) -> Result<String, AnyError> {
     Ok(Input::from_str(input)?.try_part_two()?.to_string())
 }
 
+crate::registry::register_solvers! {
+    2018, 3, "No Matter How You Slice It";
+    fingerprint = Fingerprint::new().first_line(r"^#\d+ @ \d+,\d+: \d+x\d+$");
+    One => part_1,
+    Two => part_2,
+}
+
 #[cfg(test)]
 mod tests {
     use crate::testing::{assert_correct_answer_on_correct_input, assert_error_on_wrong_input};
@@ -3,7 +3,9 @@ use std::str::FromStr;
 
 use itertools::Itertools;
 
+use crate::fingerprint::Fingerprint;
 use crate::itersum::Itersum;
+use crate::parse::{self, Lines};
 
 #[derive(Debug, Eq, PartialEq, PartialOrd, Ord)]
 enum Entry {
@@ -62,17 +64,18 @@ impl FromStr for Input {
         let mut with_time = Vec::new();
         let re = regex::Regex::new(r"^\[(\d{4}-\d{2}-\d{2} \d{2}:(\d{2}))\] (Guard #(\d+) begins shift|falls asleep|wakes up)$")
             .expect("Hard coded regex is valid");
-        for line in s.lines() {
+        for line in Lines::new(s)? {
             let cap = re
                 .captures(line)
                 .ok_or_else(|| anyhow!("Could not capture line {line:}"))?;
 
             let datetime = String::from(&cap[1]);
+            // Minutes are zero padded to two digits
             let minute = cap[2].parse::<u8>()?;
             let entry = match &cap[3] {
                 "falls asleep" => Entry::Sleep(minute),
                 "wakes up" => Entry::Wake(minute),
-                _ => Entry::Begin(cap[4].parse::<usize>()?),
+                _ => Entry::Begin(parse::number(&cap[4])?),
             };
             with_time.push((datetime, entry));
         }
@@ -82,12 +85,19 @@ impl FromStr for Input {
     }
 }
 
-pub fn part_1(input: &str) -> anyhow::Result<String> {
-    Ok(Input::from_str(input)?.try_part_one()?.to_string())
+pub fn part_1(input: &str) -> anyhow::Result<usize> {
+    Input::from_str(input)?.try_part_one()
 }
 
-pub fn part_2(input: &str) -> anyhow::Result<String> {
-    Ok(Input::from_str(input)?.try_part_two()?.to_string())
+pub fn part_2(input: &str) -> anyhow::Result<usize> {
+    Input::from_str(input)?.try_part_two()
+}
+
+crate::registry::register_solvers! {
+    2018, 4, "Repose Record";
+    fingerprint = Fingerprint::new().first_line(r"^\[\d{4}-\d{2}-\d{2} \d{2}:\d{2}\] ");
+    One => part_1,
+    Two => part_2,
 }
 
 #[cfg(test)]
@@ -1,3 +1,4 @@
+use crate::fingerprint::{Fingerprint, LETTERS};
 use anyhow::anyhow;
 
 fn same_type_opposite_polarity(left: u8, right: u8) -> bool {
@@ -55,6 +56,14 @@ pub fn part_2(input: &str) -> anyhow::Result<usize> {
         .expect("Hard coded range is not empty"))
 }
 
+crate::registry::register_solvers! {
+    2018, 5, "Alchemical Reduction";
+    fingerprint = Fingerprint::new().lines(1, 1).alphabet(LETTERS);
+    accepts = |text| Fingerprint::new().alphabet(LETTERS).matches(text);
+    One => part_1,
+    Two => part_2,
+}
+
 #[cfg(test)]
 mod tests {
     use crate::testing::{assert_correct_answer_on_correct_input, assert_error_on_wrong_input};
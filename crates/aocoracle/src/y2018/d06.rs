@@ -1,87 +1,68 @@
+use crate::fingerprint::Fingerprint;
+use crate::geom::Rectangle;
+use crate::grid::Grid;
 use crate::itersum::unambiguous_argmin;
-use crate::rect::Rectangle;
-use anyhow::anyhow;
+use crate::params::Param;
+use crate::parse::{self, Lines};
+use crate::trace::{self, Event};
+use anyhow::{anyhow, bail};
 use hashbrown::HashSet;
 use itertools::{Itertools, MinMaxResult};
 use std::str::FromStr;
-fn bounding_box(coordinates: &[(usize, usize)]) -> Rectangle<usize> {
+
+const THRESHOLD: Param = Param::new(
+    "threshold",
+    10000,
+    "Total distance to all coordinates below which a location is safe",
+)
+.with_example(32);
+
+fn bounding_box(coordinates: &[(usize, usize)]) -> anyhow::Result<Rectangle<usize>> {
     let (left, right) = match coordinates.iter().map(|(x, _)| x).minmax() {
-        MinMaxResult::NoElements => {
-            panic!("Expected at least one coordinate")
-        }
+        MinMaxResult::NoElements => bail!("Expected at least one coordinate"),
         MinMaxResult::OneElement(x) => (*x, *x),
         MinMaxResult::MinMax(lo, hi) => (*lo, *hi),
     };
     let (top, bottom) = match coordinates.iter().map(|(_, y)| y).minmax() {
-        MinMaxResult::NoElements => {
-            panic!("Expected at least one coordinate")
-        }
+        MinMaxResult::NoElements => bail!("Expected at least one coordinate"),
         MinMaxResult::OneElement(y) => (*y, *y),
         MinMaxResult::MinMax(lo, hi) => (*lo, *hi),
     };
     let width = right - left + 1;
     let height = bottom - top + 1;
-    Rectangle::<usize>::new(left, top, width, height)
+    Ok(Rectangle::<usize>::new(left, top, width, height))
 }
 
-struct Grid<T> {
-    shape: Rectangle<usize>,
-    data: Vec<T>,
-}
-
-impl<T> Grid<T>
+/// Return a grid covering the bounding box of `coordinates` and the position of its top left tile
+fn grid_from_coordinates<T, F>(
+    coordinates: &[(usize, usize)],
+    f: F,
+) -> anyhow::Result<(Grid<T>, (usize, usize))>
 where
-    T: Clone,
+    F: Fn((usize, usize)) -> T,
 {
-    fn from_coordinates<F>(coordinates: &[(usize, usize)], f: F) -> Self
-    where
-        F: Fn((usize, usize)) -> T,
-    {
-        let shape = bounding_box(coordinates);
-        let data = shape.tiles().map(f).collect();
-        Self { shape, data }
-    }
-
-    fn border_values(&mut self) -> impl Iterator<Item = &T> {
-        let mut result = Vec::with_capacity(2 * self.shape.width + 2 * self.shape.height - 2);
-        for col in 0..self.shape.width {
-            for row in [0, self.shape.height - 1] {
-                result.push(&self.data[col + row * self.shape.width]);
-            }
-        }
-        for row in 0..self.shape.height {
-            for col in [0, self.shape.width - 1] {
-                result.push(&self.data[col + row * self.shape.width]);
-            }
-        }
-        result.into_iter()
-    }
+    let shape = bounding_box(coordinates)?;
+    let grid = Grid::new(shape.width, shape.tiles().map(f).collect())?;
+    Ok((grid, (shape.left, shape.top)))
 }
 
-impl Grid<Option<u8>> {
-    fn _print(&self, coordinates: &[(usize, usize)]) {
-        let coordinates: HashSet<_> = coordinates.iter().collect();
-        let mut line = String::with_capacity(self.shape.width);
-        for (i, label) in self.data.iter().enumerate() {
-            let x = self.shape.left + i % self.shape.width;
-            let y = self.shape.top + i / self.shape.width;
-            if x == self.shape.left {
-                println!("{}", line);
-                line.clear();
-            }
-            line.push(match label {
-                None => '.',
-                Some(label) => {
-                    if coordinates.contains(&(x, y)) {
-                        (label + 65) as char
-                    } else {
-                        (label + 97) as char
-                    }
-                }
-            });
+/// Draw closest coordinates as lowercase letters and the coordinates themselves as uppercase
+fn closest_event(
+    label: &str,
+    grid: &Grid<Option<u8>>,
+    (left, top): (usize, usize),
+    coordinates: &[(usize, usize)],
+) -> Event {
+    let mut tiles = grid.map(|label| match label {
+        None => '.',
+        Some(label) => (label + 97) as char,
+    });
+    for &(x, y) in coordinates {
+        if let Some(tile) = tiles.get_mut((x - left, y - top)) {
+            *tile = tile.to_ascii_uppercase();
         }
-        println!("{}", line);
     }
+    Event::grid(label, tiles.render(|&tile| tile))
 }
 
 #[derive(Debug)]
@@ -91,7 +72,7 @@ struct Input {
 
 impl Input {
     fn try_part_one(&self) -> anyhow::Result<usize> {
-        let mut grid = Grid::from_coordinates(&self.coordinates, |(x, y)| {
+        let (grid, origin) = grid_from_coordinates(&self.coordinates, |(x, y)| {
             unambiguous_argmin(
                 self.coordinates
                     .iter()
@@ -99,11 +80,13 @@ impl Input {
                     .map(|(l, c)| (Some(l as u8), x.abs_diff(c.0) + y.abs_diff(c.1))),
             )
             .unwrap_or(None)
-        });
+        })?;
+
+        trace::emit(|| closest_event("Closest coordinates", &grid, origin, &self.coordinates));
 
-        let infinite_areas: HashSet<u8> = grid.border_values().flatten().cloned().collect();
+        let infinite_areas: HashSet<u8> = grid.border().filter_map(|pos| grid[pos]).collect();
 
-        let mut finite_areas = grid.data.into_iter().flatten().counts();
+        let mut finite_areas = grid.into_values().flatten().counts();
         finite_areas.retain(|k, _| !infinite_areas.contains(k));
 
         finite_areas
@@ -112,21 +95,15 @@ impl Input {
             .ok_or_else(|| anyhow!("All points are equidistant"))
     }
 
-    fn part_two_x(&self, threshold: usize) -> usize {
-        let grid = Grid::from_coordinates(&self.coordinates, |(x, y)| {
+    fn try_part_two(&self, threshold: i64) -> anyhow::Result<usize> {
+        let threshold = usize::try_from(threshold)?;
+        let (grid, _) = grid_from_coordinates(&self.coordinates, |(x, y)| {
             self.coordinates
                 .iter()
                 .map(|c| x.abs_diff(c.0) + y.abs_diff(c.1))
                 .sum::<usize>()
-        });
-        grid.data.into_iter().filter(|d| *d < threshold).count()
-    }
-
-    fn _part_two_a(&self) -> usize {
-        self.part_two_x(32)
-    }
-    fn part_two_b(&self) -> usize {
-        self.part_two_x(10000)
+        })?;
+        Ok(grid.values().filter(|d| **d < threshold).count())
     }
 }
 
@@ -136,32 +113,38 @@ impl FromStr for Input {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut coordinates = Vec::new();
         let re = regex::Regex::new(r"^(\d+), (\d+)$").expect("Hard coded regex is valid");
-        for line in s.lines() {
+        for line in Lines::new(s)? {
             let cap = re
                 .captures(line)
                 .ok_or_else(|| anyhow!("Regex \"{re:?}\" could not capture line {line:?}"))?;
-            coordinates.push((cap[1].parse()?, cap[2].parse()?))
+            coordinates.push((parse::number(&cap[1])?, parse::number(&cap[2])?))
         }
         Ok(Self { coordinates })
     }
 }
 
-pub fn part_1(input: &str) -> anyhow::Result<String> {
-    Ok(Input::from_str(input)?.try_part_one()?.to_string())
+pub fn part_1(input: &str) -> anyhow::Result<usize> {
+    Input::from_str(input)?.try_part_one()
 }
 
-pub fn _part_2a(input: &str) -> anyhow::Result<String> {
-    Ok(Input::from_str(input)?._part_two_a().to_string())
+pub fn part_2(input: &str) -> anyhow::Result<usize> {
+    Input::from_str(input)?.try_part_two(THRESHOLD.get())
 }
 
-pub fn part_2b(input: &str) -> anyhow::Result<String> {
-    Ok(Input::from_str(input)?.part_two_b().to_string())
+crate::registry::register_solvers! {
+    2018, 6, "Chronal Coordinates";
+    fingerprint = Fingerprint::new().alphabet("0123456789, ").first_line(r"^\d+, \d+$");
+    params = [THRESHOLD];
+    example = Fingerprint::new().lines(1, 10).every_line(r"^\d, \d$");
+    One => part_1,
+    Two => part_2,
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::params::{with_overrides, Override};
     use crate::testing::{assert_correct_answer_on_correct_input, assert_error_on_wrong_input};
-    use crate::Part;
+    use crate::{Part, Solver};
 
     use super::*;
 
@@ -177,16 +160,25 @@ mod tests {
 
     #[test]
     fn part_2_works_on_example() {
-        assert_correct_answer_on_correct_input!(_part_2a, "EXAMPLE", Part::Two);
+        let part_2 =
+            |text: &str| with_overrides(&[Override::new("threshold", 32)], || part_2(text));
+        assert_correct_answer_on_correct_input!(part_2, "EXAMPLE", Part::Two);
     }
 
     #[test]
     fn part_2_works_on_input() {
-        assert_correct_answer_on_correct_input!(part_2b, "8b1268273d98c3e6", Part::Two);
+        assert_correct_answer_on_correct_input!(part_2, "8b1268273d98c3e6", Part::Two);
     }
 
     #[test]
     fn returns_error_on_wrong_input() {
-        assert_error_on_wrong_input!(part_1, part_2b);
+        assert_error_on_wrong_input!(part_1, part_2);
+    }
+
+    #[test]
+    fn example_is_recognised_by_content() {
+        let example = registrations()[0].example().unwrap().clone();
+        assert!(example.matches("1, 1\n1, 6\n8, 3\n3, 4\n5, 5\n8, 9\n"));
+        assert!(!example.matches("181, 47\n337, 53\n331, 40\n137, 57\n200, 96\n351, 180\n"));
     }
 }
@@ -1,132 +1,35 @@
+use std::fmt::{Debug, Formatter};
 use std::str::FromStr;
 
+use crate::fingerprint::Fingerprint;
+use crate::params::Param;
+use crate::parse::Lines;
+use crate::schedule::Dependencies;
+use crate::trace;
 use anyhow::{anyhow, bail};
-use hashbrown::HashMap;
 use itertools::Itertools;
 
-mod dag {
-    use std::fmt::Debug;
-    use std::hash::Hash;
+const WORKERS: Param =
+    Param::new("workers", 5, "Number of workers, including yourself").with_example(2);
+const BASE_DURATION: Param = Param::new(
+    "base_duration",
+    60,
+    "Number of seconds that every step takes in addition to its letter",
+)
+.with_example(0);
 
-    use anyhow::{anyhow, bail};
-    use hashbrown::{HashMap, HashSet};
-
-    pub struct Graph<T> {
-        succ2precs: HashMap<T, HashSet<T>>,
-    }
-
-    impl<T: Copy + Eq + Hash + Debug> Graph<T> {
-        pub fn new() -> Self {
-            Self {
-                succ2precs: HashMap::new(),
-            }
-        }
-
-        pub fn insert_edge(&mut self, prec: T, succ: T) {
-            self.succ2precs.entry(succ).or_default().insert(prec);
-            self.succ2precs.entry(prec).or_default();
-        }
-
-        pub fn into_planner(self) -> Planner<T> {
-            Planner::new(self.succ2precs)
-        }
-    }
-
-    pub struct Planner<T> {
-        succ2precs: HashMap<T, HashSet<T>>,
-        ready: HashSet<T>,
-        started: HashSet<T>,
-    }
-
-    impl<T: Eq + Hash + Debug> Planner<T> {
-        pub fn new(mut succ2precs: HashMap<T, HashSet<T>>) -> Self {
-            let ready = succ2precs
-                .drain_filter(|_, precs| precs.is_empty())
-                .map(|(succ, _)| succ)
-                .collect();
-            Self {
-                succ2precs,
-                ready,
-                started: HashSet::new(),
-            }
-        }
-
-        pub fn ready(&self) -> &HashSet<T> {
-            &self.ready
-        }
-
-        pub fn start(&mut self, task: &T) -> anyhow::Result<()> {
-            let task = self
-                .ready
-                .take(task)
-                .ok_or_else(|| anyhow!("Cannot start task {task:?} because it is not ready"))?;
-            self.started.insert(task);
-            Ok(())
-        }
-
-        pub fn complete(&mut self, task: &T) -> anyhow::Result<()> {
-            if !self.started.remove(task) {
-                bail!("Cannot complete task {task:?} because is not started")
-            }
-            self.succ2precs.values_mut().for_each(|precs| {
-                precs.remove(task);
-            });
-            self.ready.extend(
-                self.succ2precs
-                    .drain_filter(|_, precs| precs.is_empty())
-                    .map(|(succ, _)| succ),
-            );
-            Ok(())
-        }
-    }
-}
-
-struct Executor {
-    now: u16,
-    base_duration: u16,
-    capacity: usize,
-    tasks: HashMap<Task, u16>,
-}
-
-impl Executor {
-    fn new(base_duration: u16, capacity: usize) -> Self {
-        Self {
-            now: 0,
-            base_duration,
-            capacity,
-            tasks: HashMap::new(),
-        }
-    }
-
-    fn start(&mut self, task: Task) -> Result<(), ()> {
-        if self.capacity == self.tasks.len() {
-            return Err(());
-        }
-        let end_time = self.now + self.base_duration + task.duration();
-        self.tasks.insert(task, end_time);
-        Ok(())
-    }
-
-    fn drain_completed(&mut self) -> impl Iterator<Item = Task> + '_ {
-        if let Some(&now) = self.tasks.values().min() {
-            self.now = now;
-        }
-        self.tasks
-            .drain_filter(|_, end_time| *end_time == self.now)
-            .map(|(task, _)| task)
-    }
+#[derive(Clone, Copy, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct Task(u8);
 
-    fn now(self) -> u16 {
-        self.now
+impl Debug for Task {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0 as char)
     }
 }
 
-#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
-struct Task(u8);
-
 impl Task {
-    fn duration(&self) -> u16 {
-        self.0 as u16 - 64
+    fn duration(&self) -> u64 {
+        self.0 as u64 - 64
     }
 }
 
@@ -152,41 +55,23 @@ impl From<Task> for char {
 }
 
 struct Input {
-    pub graph: dag::Graph<Task>,
+    pub dependencies: Dependencies<Task>,
 }
 
 impl Input {
     fn try_part_one(self) -> anyhow::Result<String> {
-        let mut planner = self.graph.into_planner();
-        let mut result = String::new();
-        while let Some(&task) = planner.ready().iter().min() {
-            planner.start(&task).unwrap();
-            planner.complete(&task).unwrap();
-            result.push(task.into());
-        }
-        Ok(result)
+        let order = self.dependencies.order()?;
+        Ok(order.into_iter().map(char::from).collect())
     }
 
-    fn try_part_two(self, num_worker: usize, base_duration: u16) -> anyhow::Result<String> {
-        let mut planner = self.graph.into_planner();
-        let mut executor = Executor::new(base_duration, num_worker);
-        loop {
-            while let Some(&task) = planner.ready().iter().min() {
-                if executor.start(task).is_err() {
-                    break;
-                }
-                planner.start(&task).unwrap();
-            }
-
-            let mut completed_tasks = executor.drain_completed().peekable();
-            if completed_tasks.peek().is_none() {
-                break; // Either done or encountered a cycle
-            }
-            for task in completed_tasks {
-                planner.complete(&task)?;
-            }
-        }
-        Ok(executor.now().to_string())
+    fn try_part_two(self, num_worker: usize, base_duration: u64) -> anyhow::Result<u64> {
+        let timeline = self.dependencies.schedule(
+            num_worker,
+            |task| base_duration + task.duration(),
+            |&task| task,
+        )?;
+        trace::emit(|| timeline.event(|&task| task.into()));
+        Ok(timeline.end())
     }
 }
 
@@ -194,11 +79,11 @@ impl FromStr for Input {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut graph = dag::Graph::new();
+        let mut dependencies = Dependencies::new();
         let re =
             regex::Regex::new(r"^Step ([A-Z]) must be finished before step ([A-Z]) can begin.$")
                 .expect("Hard coded regex is valid");
-        for line in s.lines() {
+        for line in Lines::new(s)? {
             let cap = re
                 .captures(line)
                 .ok_or_else(|| anyhow!("Regex \"{re:?}\" could not capture line {line:?}"))?;
@@ -214,9 +99,9 @@ impl FromStr for Input {
                 .expect("Regex matches exactly one char")
                 .try_into()
                 .expect("Regex matches valid task");
-            graph.insert_edge(predecessor, successor);
+            dependencies.insert_edge(predecessor, successor);
         }
-        Ok(Self { graph })
+        Ok(Self { dependencies })
     }
 }
 
@@ -224,18 +109,28 @@ pub fn part_1(input: &str) -> anyhow::Result<String> {
     Input::from_str(input)?.try_part_one()
 }
 
-pub fn _part_2a(input: &str) -> anyhow::Result<String> {
-    Input::from_str(input)?.try_part_two(2, 0)
+pub fn part_2(input: &str) -> anyhow::Result<u64> {
+    let num_worker = usize::try_from(WORKERS.get())?;
+    let base_duration = u64::try_from(BASE_DURATION.get())?;
+    Input::from_str(input)?.try_part_two(num_worker, base_duration)
 }
 
-pub fn part_2b(input: &str) -> anyhow::Result<String> {
-    Input::from_str(input)?.try_part_two(5, 60)
+crate::registry::register_solvers! {
+    2018, 7, "The Sum of Its Parts";
+    fingerprint = Fingerprint::new().first_line(r"^Step [A-Z] must be finished before step [A-Z] can begin.$");
+    params = [WORKERS, BASE_DURATION];
+    example = Fingerprint::new()
+        .every_line(r"^Step [A-F] must be finished before step [A-F] can begin.$");
+    One => part_1,
+    Two => part_2,
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::params::Mode;
+    use crate::params::{with_overrides, Override};
     use crate::testing::{assert_correct_answer_on_correct_input, assert_error_on_wrong_input};
-    use crate::Part;
+    use crate::{helper, Cli, Part, Solver};
 
     use super::*;
 
@@ -251,16 +146,64 @@ mod tests {
 
     #[test]
     fn part_2_works_on_example() {
-        assert_correct_answer_on_correct_input!(_part_2a, "EXAMPLE", Part::Two);
+        let part_2 = |text: &str| {
+            with_overrides(
+                &[
+                    Override::new("workers", 2),
+                    Override::new("base_duration", 0),
+                ],
+                || part_2(text),
+            )
+        };
+        assert_correct_answer_on_correct_input!(part_2, "EXAMPLE", Part::Two);
     }
 
     #[test]
     fn part_2_works_on_input() {
-        assert_correct_answer_on_correct_input!(part_2b, "8a463747b9895b96", Part::Two);
+        assert_correct_answer_on_correct_input!(part_2, "8a463747b9895b96", Part::Two);
     }
 
     #[test]
     fn returns_error_on_wrong_input() {
-        assert_error_on_wrong_input!(part_1, part_2b);
+        assert_error_on_wrong_input!(part_1, part_2);
+    }
+
+    const EXAMPLE: &str = "Step C must be finished before step A can begin.
+Step C must be finished before step F can begin.
+Step A must be finished before step B can begin.
+Step A must be finished before step D can begin.
+Step B must be finished before step E can begin.
+Step D must be finished before step E can begin.
+Step F must be finished before step E can begin.
+";
+
+    #[test]
+    fn only_parts_with_example_params_are_solved_as_example() {
+        let args = Cli::new(Some(2018), Some(7), None, true);
+        let modes: Vec<_> = helper(&args, EXAMPLE)
+            .unwrap()
+            .into_iter()
+            .map(|g| (g.part, g.mode))
+            .collect();
+        assert_eq!(
+            modes,
+            vec![(Part::One, Mode::Real), (Part::Two, Mode::Example)]
+        );
+    }
+
+    #[test]
+    fn example_is_recognised_by_content() {
+        let example = registrations()[0].example().unwrap().clone();
+        assert!(example.matches(EXAMPLE));
+        assert!(!example.matches(
+            "Step G must be finished before step T can begin.
+Step L must be finished before step V can begin.
+Step D must be finished before step P can begin.
+Step J must be finished before step K can begin.
+Step N must be finished before step B can begin.
+Step K must be finished before step W can begin.
+Step T must be finished before step I can begin.
+"
+        ));
     }
 }
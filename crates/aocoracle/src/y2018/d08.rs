@@ -1,3 +1,6 @@
+use crate::checked::{ArithmeticError, Checked, CheckedIter};
+use crate::fingerprint::Fingerprint;
+use crate::parse::{self, Lines};
 use anyhow::{anyhow, bail};
 
 struct Node {
@@ -6,15 +9,15 @@ struct Node {
 }
 
 impl Node {
-    fn entries_sum(&self) -> usize {
-        self.child_nodes
-            .iter()
-            .map(|child| child.entries_sum())
-            .sum::<usize>()
-            + self.metadata_entries.iter().sum::<usize>()
+    fn entries_sum(&self) -> Result<usize, ArithmeticError> {
+        let mut result = self.metadata_entries.iter().copied().try_sum()?;
+        for child in &self.child_nodes {
+            result = result.try_add(child.entries_sum()?)?;
+        }
+        Ok(result)
     }
 
-    fn value(&self) -> usize {
+    fn value(&self) -> Result<usize, ArithmeticError> {
         if self.child_nodes.is_empty() {
             return self.entries_sum();
         }
@@ -25,10 +28,10 @@ impl Node {
                 continue;
             }
             if let Some(node) = self.child_nodes.get(*i - 1) {
-                result += node.value()
+                result = result.try_add(node.value()?)?;
             }
         }
-        result
+        Ok(result)
     }
 }
 
@@ -36,14 +39,16 @@ fn try_take_node(depth: usize, numbers: &mut Vec<&str>) -> anyhow::Result<Node>
     if depth > 99 {
         bail!("Tree deeper than expected, bailing to prevent stack overflow")
     }
-    let num_child: usize = numbers
-        .pop()
-        .ok_or_else(|| anyhow!("Expected quantity of child nodes"))?
-        .parse()?;
-    let num_entry: usize = numbers
-        .pop()
-        .ok_or_else(|| anyhow!("Expected quantity of metadata entries"))?
-        .parse()?;
+    let num_child: usize = parse::number(
+        numbers
+            .pop()
+            .ok_or_else(|| anyhow!("Expected quantity of child nodes"))?,
+    )?;
+    let num_entry: usize = parse::number(
+        numbers
+            .pop()
+            .ok_or_else(|| anyhow!("Expected quantity of metadata entries"))?,
+    )?;
 
     let mut child_nodes = Vec::with_capacity(num_child);
     for _ in 0..num_child {
@@ -52,12 +57,11 @@ fn try_take_node(depth: usize, numbers: &mut Vec<&str>) -> anyhow::Result<Node>
 
     let mut metadata_entries = Vec::with_capacity(num_entry);
     for _ in 0..num_entry {
-        metadata_entries.push(
+        metadata_entries.push(parse::number(
             numbers
                 .pop()
-                .ok_or_else(|| anyhow!("Expected metadata entry"))?
-                .parse()?,
-        )
+                .ok_or_else(|| anyhow!("Expected metadata entry"))?,
+        )?)
     }
 
     Ok(Node {
@@ -67,7 +71,7 @@ fn try_take_node(depth: usize, numbers: &mut Vec<&str>) -> anyhow::Result<Node>
 }
 
 fn tree_from_str(s: &str) -> anyhow::Result<Node> {
-    let mut lines = s.lines();
+    let mut lines = Lines::new(s)?;
     let line = lines
         .next()
         .ok_or_else(|| anyhow!("Expected exactly one line but got 0"))?;
@@ -75,17 +79,31 @@ fn tree_from_str(s: &str) -> anyhow::Result<Node> {
         bail!("Expected exactly one line but got at least 2");
     }
     let mut numbers: Vec<_> = line.split(' ').rev().collect();
-    try_take_node(0, &mut numbers)
+    let root = try_take_node(0, &mut numbers)?;
+    if parse::is_strict() && !numbers.is_empty() {
+        bail!(
+            "Expected end of input but got {} more numbers",
+            numbers.len()
+        );
+    }
+    Ok(root)
 }
 
 pub fn part_1(input: &str) -> anyhow::Result<usize> {
     let tree = tree_from_str(input)?;
-    Ok(tree.entries_sum())
+    Ok(tree.entries_sum()?)
 }
 
 pub fn part_2(input: &str) -> anyhow::Result<usize> {
     let tree = tree_from_str(input)?;
-    Ok(tree.value())
+    Ok(tree.value()?)
+}
+
+crate::registry::register_solvers! {
+    2018, 8, "Memory Maneuver";
+    fingerprint = Fingerprint::new().lines(1, 1).alphabet("0123456789 ");
+    One => part_1,
+    Two => part_2,
 }
 
 #[cfg(test)]
@@ -119,4 +137,11 @@ mod tests {
     fn returns_error_on_wrong_input() {
         assert_error_on_wrong_input!(part_1, part_2);
     }
+
+    #[test]
+    fn returns_error_on_overflow() {
+        let input = format!("0 2 {} 1", usize::MAX);
+        assert!(part_1(&input).is_err());
+        assert!(part_2(&input).is_err());
+    }
 }
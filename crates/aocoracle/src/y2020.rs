@@ -1,2 +1,4 @@
-pub mod d01;
-pub mod d02;
+crate::registry::register_days! {
+    d01,
+    d02,
+}
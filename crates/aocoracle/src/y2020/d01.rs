@@ -1,22 +1,22 @@
+use crate::checked::Checked;
+use crate::fingerprint::{Fingerprint, DIGITS};
+use crate::parse;
 use crate::AnyError;
 
 fn _numbers(text: &str) -> Result<Vec<u32>, AnyError> {
-    let mut result = Vec::new();
-    for line in text.lines() {
-        if line.starts_with('0') {
-            return Err("Integers do not usually start with 0".into());
-        }
-        result.push(line.parse::<u32>()?);
+    if text.lines().any(|line| line.starts_with('0')) {
+        return Err("Integers do not usually start with 0".into());
     }
-    Ok(result)
+    Ok(parse::numbers(text)?)
 }
 
 pub fn part_1(input: &str) -> Result<String, AnyError> {
     let numbers = _numbers(input)?;
     for x in numbers.iter() {
         for y in numbers.iter() {
-            if x + y == 2020 {
-                return Ok(format!("{}", x * y));
+            // A sum that does not fit cannot be 2020 either
+            if x.checked_add(*y) == Some(2020) {
+                return Ok(format!("{}", x.try_mul(*y)?));
             }
         }
     }
@@ -27,12 +27,13 @@ pub fn part_2(input: &str) -> Result<String, AnyError> {
     let numbers = _numbers(input)?;
     for x in numbers.iter() {
         for y in numbers.iter() {
-            if x + y >= 2020 {
-                continue;
-            }
+            let xy = match x.checked_add(*y) {
+                Some(xy) if xy < 2020 => xy,
+                _ => continue,
+            };
             for z in numbers.iter() {
-                if x + y + z == 2020 {
-                    return Ok(format!("{}", x * y * z));
+                if xy.checked_add(*z) == Some(2020) {
+                    return Ok(format!("{}", x.try_mul(*y)?.try_mul(*z)?));
                 }
             }
         }
@@ -40,6 +41,13 @@ pub fn part_2(input: &str) -> Result<String, AnyError> {
     Err("No answer".into())
 }
 
+crate::registry::register_solvers! {
+    2020, 1, "Report Repair";
+    fingerprint = Fingerprint::new().alphabet(DIGITS);
+    One => part_1,
+    Two => part_2,
+}
+
 #[cfg(test)]
 mod tests {
     use crate::testing::{assert_correct_answer_on_correct_input, assert_error_on_wrong_input};
@@ -72,4 +80,11 @@ mod tests {
     fn returns_error_on_wrong_input() {
         assert_error_on_wrong_input!(&part_1, &part_2);
     }
+
+    #[test]
+    fn ignores_numbers_too_large_to_add() {
+        let input = format!("{}\n1\n2019\n3\n2016", u32::MAX);
+        assert_eq!(part_1(&input).unwrap(), "2019");
+        assert_eq!(part_2(&input).unwrap(), "6048");
+    }
 }
@@ -1,3 +1,5 @@
+use crate::fingerprint::Fingerprint;
+use crate::parse::{self, Lines};
 use crate::AnyError;
 use itertools::Itertools;
 
@@ -34,14 +36,14 @@ impl Policy {
 fn _passwords(text: &str) -> Result<Vec<(Policy, String)>, AnyError> {
     let re = regex::Regex::new(r"^(\d+)-(\d+) (\w): (\w+)$").expect("Hard coded regex is valid");
     let mut result = Vec::new();
-    for line in text.lines() {
+    for line in Lines::new(text)? {
         let cap = re
             .captures(line)
             .ok_or(format!("Could not capture a password on line {}", line))?;
         result.push((
             Policy {
-                lo: cap[1].parse::<usize>()?,
-                hi: cap[2].parse::<usize>()?,
+                lo: parse::number(&cap[1])?,
+                hi: parse::number(&cap[2])?,
                 ch: cap[3]
                     .chars()
                     .exactly_one()
@@ -68,6 +70,13 @@ pub fn part_2(input: &str) -> Result<String, AnyError> {
         .sum();
     Ok(format!("{}", num_valid))
 }
+crate::registry::register_solvers! {
+    2020, 2, "Password Philosophy";
+    fingerprint = Fingerprint::new().first_line(r"^\d+-\d+ [a-z]: [a-z]+$");
+    One => part_1,
+    Two => part_2,
+}
+
 #[cfg(test)]
 mod tests {
     use crate::testing::{assert_correct_answer_on_correct_input, assert_error_on_wrong_input};
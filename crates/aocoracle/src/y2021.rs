@@ -1,14 +1,16 @@
-pub mod d01;
-pub mod d02;
-pub mod d03;
-pub mod d04;
-pub mod d05;
-pub mod d06;
-pub mod d07;
-pub mod d08;
-pub mod d20;
-pub mod d21;
-pub mod d22;
-pub mod d23;
-pub mod d24;
-pub mod d25;
+crate::registry::register_days! {
+    d01,
+    d02,
+    d03,
+    d04,
+    d05,
+    d06,
+    d07,
+    d08,
+    d20,
+    d21,
+    d22,
+    d23,
+    d24,
+    d25,
+}
@@ -1,17 +1,20 @@
+use crate::fingerprint::{Fingerprint, DIGITS};
+use crate::parse::{self, Lines};
+
 fn _depths(text: &str) -> Result<Vec<u32>, Box<dyn std::error::Error>> {
-    let mut result = Vec::new();
-    for line in text.lines() {
+    let mut lines = Lines::new(text)?;
+    let result = lines.parse_rest(|line| {
         if line
             .chars()
             .next()
-            .ok_or("Cannot parse empty line as depth")?
+            .ok_or_else(|| anyhow::anyhow!("Cannot parse empty line as depth"))?
             == '0'
         {
-            return Err("Refusing to parse line starting with 0 as depth".into());
+            anyhow::bail!("Refusing to parse line starting with 0 as depth");
         }
-        let depth = line.parse::<u32>()?;
-        result.push(depth);
-    }
+        parse::number(line)
+    })?;
+    lines.finish()?;
     Ok(result)
 }
 
@@ -39,6 +42,13 @@ pub fn part_2(input: &str) -> Result<String, Box<dyn std::error::Error>> {
     ))
 }
 
+crate::registry::register_solvers! {
+    2021, 1, "Sonar Sweep";
+    fingerprint = Fingerprint::new().alphabet(DIGITS);
+    One => part_1,
+    Two => part_2,
+}
+
 #[cfg(test)]
 mod tests {
     use crate::testing::{assert_correct_answer_on_correct_input, assert_error_on_wrong_input};
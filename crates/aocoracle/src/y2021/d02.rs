@@ -1,6 +1,9 @@
 use itertools::Itertools;
 use std::collections::HashMap;
 
+use crate::checked::{ArithmeticError, Checked, CheckedIter};
+use crate::fingerprint::Fingerprint;
+use crate::parse::{self, Lines};
 use std::hash::Hash;
 
 #[derive(Clone, Copy, Eq, Hash, PartialEq)]
@@ -17,9 +20,7 @@ struct Command {
 
 impl Command {
     fn parse(line: &str) -> Option<Command> {
-        let mut parts = line.split_whitespace();
-        let direction = parts.next()?;
-        let distance = parts.next()?;
+        let (direction, distance) = line.split_once(' ')?;
 
         Some(Command {
             direction: match direction {
@@ -28,7 +29,7 @@ impl Command {
                 "up" => Some(Direction::Up),
                 _ => None,
             }?,
-            magnitude: distance.parse::<u32>().unwrap(),
+            magnitude: parse::number(distance).ok()?,
         })
     }
 }
@@ -54,19 +55,20 @@ where
 
 pub fn part_1(input: &str) -> Result<String, Box<dyn std::error::Error>> {
     let mut commands = Vec::new();
-    for line in input.lines() {
+    for line in Lines::new(input)? {
         let command = Command::parse(line).ok_or("Could not parse command")?;
         commands.push(command);
     }
-    let mut counts: HashMap<Direction, u32> = _map_reduce(
+    let mut counts: HashMap<Direction, Result<u32, ArithmeticError>> = _map_reduce(
         commands.iter(),
         |v| v.direction,
         |v| v.magnitude,
-        |vs| vs.iter().sum(),
+        |vs| vs.into_iter().try_sum(),
     );
-    let result = counts.remove(&Direction::Forward).unwrap_or(0)
-        * (counts.remove(&Direction::Down).unwrap_or(0)
-            - counts.remove(&Direction::Up).unwrap_or(0));
+    let mut count = |direction| counts.remove(&direction).unwrap_or(Ok(0));
+    let forward = count(Direction::Forward)?;
+    let depth = count(Direction::Down)?.try_sub(count(Direction::Up)?)?;
+    let result = forward.try_mul(depth)?;
     Ok(format!("{}", result))
 }
 
@@ -74,18 +76,25 @@ pub fn part_2(input: &str) -> Result<String, Box<dyn std::error::Error>> {
     let mut aim = 0;
     let mut horizontal = 0;
     let mut vertical = 0;
-    for command in input.lines().map(Command::parse) {
+    for command in Lines::new(input)?.map(Command::parse) {
         let command = command.ok_or("Could not parse command")?;
         match command.direction {
             Direction::Forward => {
-                horizontal += command.magnitude;
-                vertical += aim * command.magnitude
+                horizontal = horizontal.try_add(command.magnitude)?;
+                vertical = vertical.try_add(aim.try_mul(command.magnitude)?)?;
             }
-            Direction::Down => aim += command.magnitude,
-            Direction::Up => aim -= command.magnitude,
+            Direction::Down => aim = aim.try_add(command.magnitude)?,
+            Direction::Up => aim = aim.try_sub(command.magnitude)?,
         }
     }
-    Ok(format!("{}", horizontal * vertical))
+    Ok(format!("{}", horizontal.try_mul(vertical)?))
+}
+
+crate::registry::register_solvers! {
+    2021, 2, "Dive!";
+    fingerprint = Fingerprint::new().first_line(r"^(forward|down|up) \d+$");
+    One => part_1,
+    Two => part_2,
 }
 
 #[cfg(test)]
@@ -119,4 +128,13 @@ mod tests {
     fn returns_error_on_wrong_input() {
         assert_error_on_wrong_input!(&part_1, &part_2);
     }
+
+    #[test]
+    fn returns_error_on_overflow() {
+        assert!(part_1("forward 5\nup 3").is_err());
+        assert!(part_2("up 3\nforward 5").is_err());
+        let input = format!("forward {}\ndown 2\nforward 1", u32::MAX);
+        assert!(part_1(&input).is_err());
+        assert!(part_2(&input).is_err());
+    }
 }
@@ -1,11 +1,13 @@
 use std::collections::HashSet;
 
+use crate::fingerprint::Fingerprint;
 use crate::itersum::Itersum;
+use crate::parse::Lines;
 use crate::AnyError;
 
 fn _rows(text: &str) -> Result<Vec<Vec<bool>>, AnyError> {
     let mut result = Vec::new();
-    for line in text.lines() {
+    for line in Lines::new(text)? {
         let mut row = Vec::new();
         for bit in line.chars() {
             row.push(
@@ -121,6 +123,14 @@ fn _carbon_only(input: &str) -> Result<u32, Box<dyn std::error::Error>> {
     Ok(_carbon(&cols))
 }
 
+crate::registry::register_solvers! {
+    2021, 3, "Binary Diagnostic";
+    fingerprint = Fingerprint::new().alphabet("01");
+    accepts = |text| Fingerprint::new().alphabet("01").matches(text);
+    One => part_1,
+    Two => part_2,
+}
+
 #[cfg(test)]
 mod tests {
     use crate::testing::{
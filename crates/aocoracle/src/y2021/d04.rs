@@ -1,24 +1,26 @@
 use std::collections::{HashMap, HashSet};
 
+use crate::checked::{ArithmeticError, Checked, CheckedIter};
+use crate::fingerprint::Fingerprint;
+use crate::parse::{self, Lines};
 use crate::AnyError;
 
 type Board = HashMap<(bool, usize), HashSet<u32>>;
 
 fn _draws(input: &str) -> Result<Vec<u32>, AnyError> {
     let mut result = Vec::new();
-    for v in input
-        .lines()
+    for v in Lines::new(input)?
         .next()
         .ok_or("Expected at least 1 line of input, got 0")?
         .split(',')
     {
-        result.push(v.parse()?);
+        result.push(parse::number(v)?);
     }
     Ok(result)
 }
 
 fn _boards(input: &str) -> Result<Vec<Board>, AnyError> {
-    let mut lines = input.lines();
+    let mut lines = Lines::new(input)?;
     lines.next(); // Discard draws
     let mut result = Vec::new();
     for chunk in lines.collect::<Vec<&str>>().chunks(6) {
@@ -34,14 +36,15 @@ fn _board(lines: &[&str]) -> Result<Board, AnyError> {
     let mut result = HashMap::with_capacity(25);
     for (row, line) in lines.iter().enumerate() {
         for (col, cell) in line.split_whitespace().enumerate() {
+            let cell = parse::number(cell)?;
             result
                 .entry((false, row))
                 .or_insert_with(|| HashSet::with_capacity(5))
-                .insert(cell.parse()?);
+                .insert(cell);
             result
                 .entry((true, col))
                 .or_insert_with(|| HashSet::with_capacity(5))
-                .insert(cell.parse()?);
+                .insert(cell);
         }
     }
     if result.len() != 10 {
@@ -61,14 +64,12 @@ fn _bingo(board: &Board) -> bool {
     board.values().any(|nums| nums.is_empty())
 }
 
-fn _score(board: &Board) -> u32 {
+fn _score(board: &Board) -> Result<u32, ArithmeticError> {
     board
         .iter()
-        .filter_map(|((is_row, _), row)| match is_row {
-            false => Some(row.iter().sum::<u32>()),
-            true => None,
-        })
-        .sum()
+        .filter(|((is_row, _), _)| !is_row)
+        .flat_map(|(_, row)| row.iter().copied())
+        .try_sum()
 }
 
 pub fn part_1(input: &str) -> Result<String, AnyError> {
@@ -78,7 +79,7 @@ pub fn part_1(input: &str) -> Result<String, AnyError> {
         for board in boards.iter_mut() {
             _cross(board, draw);
             if _bingo(board) {
-                return Ok(format!("{}", _score(board) * draw));
+                return Ok(format!("{}", _score(board)?.try_mul(draw)?));
             }
         }
     }
@@ -100,13 +101,20 @@ pub fn part_2(input: &str) -> Result<String, AnyError> {
         for board in boards.iter_mut() {
             _cross(board, draw);
             if _bingo(board) {
-                return Ok(format!("{}", _score(board) * draw));
+                return Ok(format!("{}", _score(board)?.try_mul(draw)?));
             }
         }
     }
     panic!("Reached end of function without finding an answer");
 }
 
+crate::registry::register_solvers! {
+    2021, 4, "Giant Squid";
+    fingerprint = Fingerprint::new().alphabet("0123456789, ").first_line(r"^\d+(,\d+)+$");
+    One => part_1,
+    Two => part_2,
+}
+
 #[cfg(test)]
 mod tests {
     use crate::testing::{assert_correct_answer_on_correct_input, assert_error_on_wrong_input};
@@ -138,4 +146,18 @@ mod tests {
     fn returns_error_on_wrong_input() {
         assert_error_on_wrong_input!(&part_1, &part_2);
     }
+
+    #[test]
+    fn returns_error_on_overflow() {
+        let input = "1,2,3,4,5
+
+         1          2  3  4          5
+4000000000          6  7  8          9
+        10         11 12 13         14
+        15         16 17 18         19
+        20 4000000001 22 23         24
+";
+        assert!(part_1(input).is_err());
+        assert!(part_2(input).is_err());
+    }
 }
@@ -1,13 +1,12 @@
 use std::collections::HashMap;
 
+use crate::fingerprint::Fingerprint;
+use crate::geom::Point2;
+use crate::parse::{self, Lines};
+use crate::trace::{self, Event};
 use crate::AnyError;
-use std::hash::Hash;
 
-#[derive(Debug, Eq, Hash, PartialEq)]
-struct Point {
-    x: i32,
-    y: i32,
-}
+type Point = Point2<i32>;
 
 #[derive(Debug)]
 struct Arrow {
@@ -30,14 +29,8 @@ impl Arrow {
             .captures(line)
             .ok_or(format!("Could not capture an arrow in line {}", line))?;
         Ok(Arrow {
-            tail: Point {
-                x: cap[1].parse::<i32>()?,
-                y: cap[2].parse::<i32>()?,
-            },
-            head: Point {
-                x: cap[3].parse::<i32>()?,
-                y: cap[4].parse::<i32>()?,
-            },
+            tail: Point::new(parse::number(&cap[1])?, parse::number(&cap[2])?),
+            head: Point::new(parse::number(&cap[3])?, parse::number(&cap[4])?),
         })
     }
 
@@ -91,30 +84,26 @@ fn _counts(points: Vec<Point>) -> HashMap<Point, u32> {
 
 fn _arrows(input: &str) -> Result<Vec<Arrow>, AnyError> {
     let mut result = Vec::new();
-    for line in input.lines() {
+    for line in Lines::new(input)? {
         result.push(Arrow::parse(line)?);
     }
     Ok(result)
 }
 
-fn _print_grid(counts: &HashMap<Point, u32>) {
-    let max_row = counts.iter().map(|(p, _)| p.y).max().unwrap();
-    let max_col = counts.iter().map(|(p, _)| p.x).max().unwrap();
-    println!("r:{:?}, c:{:?}", max_row, max_col);
-    for row_num in 0..=max_row {
-        for col_num in 0..=max_col {
-            print!(
-                "{}",
-                counts
-                    .get(&Point {
-                        x: col_num,
-                        y: row_num,
-                    })
-                    .unwrap_or(&0)
-            );
-        }
-        println!();
-    }
+fn _grid_event(counts: &HashMap<Point, u32>) -> Event {
+    let max_row = counts.keys().map(|p| p.y).max().unwrap_or_default();
+    let max_col = counts.keys().map(|p| p.x).max().unwrap_or_default();
+    Event::grid(
+        "Number of lines covering each point",
+        (0..=max_row).map(|y| {
+            (0..=max_col)
+                .map(|x| match counts.get(&Point { x, y }) {
+                    Some(count) => count.to_string(),
+                    None => ".".to_string(),
+                })
+                .collect()
+        }),
+    )
 }
 
 fn _risk(arrows: Vec<Arrow>, include_diagonal: bool) -> u32 {
@@ -125,6 +114,7 @@ fn _risk(arrows: Vec<Arrow>, include_diagonal: bool) -> u32 {
             .flat_map(|a| a.points(include_diagonal))
             .collect(),
     );
+    trace::emit(|| _grid_event(&counts));
     counts.into_iter().filter(|(_, c)| 2 <= *c).count() as u32
 }
 
@@ -140,6 +130,13 @@ pub fn part_2(input: &str) -> Result<String, AnyError> {
     Ok(format!("{}", risk))
 }
 
+crate::registry::register_solvers! {
+    2021, 5, "Hydrothermal Venture";
+    fingerprint = Fingerprint::new().first_line(r"^\d+,\d+ -> \d+,\d+$");
+    One => part_1,
+    Two => part_2,
+}
+
 #[cfg(test)]
 mod tests {
     use crate::testing::{assert_correct_answer_on_correct_input, assert_error_on_wrong_input};
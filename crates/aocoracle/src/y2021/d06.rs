@@ -1,13 +1,18 @@
 use std::collections::HashMap;
 
+use crate::fingerprint::Fingerprint;
+use crate::parse::{self, Lines};
 use crate::AnyError;
 
 type Census = HashMap<u32, u64>;
 
-fn _census(line: &str) -> Result<Census, AnyError> {
+fn _census(text: &str) -> Result<Census, AnyError> {
+    let mut lines = Lines::new(text)?;
+    let line = lines.next().ok_or("Expected 1 line but got 0")?;
+    lines.finish()?;
     let mut result = HashMap::new();
-    for countdown in line.trim_end().split(',') {
-        let countdown = countdown.parse::<u32>()?;
+    for countdown in line.split(',') {
+        let countdown = parse::number::<u32>(countdown)?;
         *(result.entry(countdown).or_insert(0)) += 1;
     }
     match *result
@@ -57,6 +62,13 @@ pub fn part_2(input: &str) -> Result<String, AnyError> {
     let num_fish = _nth_census(initial, 256).values().sum::<u64>();
     Ok(format!("{}", num_fish))
 }
+crate::registry::register_solvers! {
+    2021, 6, "Lanternfish";
+    fingerprint = Fingerprint::new().lines(1, 1).alphabet("012345678,");
+    One => part_1,
+    Two => part_2,
+}
+
 #[cfg(test)]
 mod tests {
     use crate::testing::{assert_correct_answer_on_correct_input, assert_error_on_wrong_input};
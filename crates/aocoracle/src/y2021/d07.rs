@@ -1,13 +1,19 @@
+use crate::checked::{ArithmeticError, Checked};
+use crate::fingerprint::Fingerprint;
+use crate::parse::{self, Lines};
 use crate::AnyError;
 use itertools::Itertools;
 use std::collections::HashMap;
 
 type Census = HashMap<u32, u64>;
 
-fn _census(line: &str) -> Result<Census, AnyError> {
+fn _census(text: &str) -> Result<Census, AnyError> {
+    let mut lines = Lines::new(text)?;
+    let line = lines.next().ok_or("Expected 1 line but got 0")?;
+    lines.finish()?;
     let mut result = HashMap::new();
-    for countdown in line.trim_end().split(',') {
-        let countdown = countdown.parse::<u32>()?;
+    for countdown in line.split(',') {
+        let countdown = parse::number::<u32>(countdown)?;
         *(result.entry(countdown).or_insert(0)) += 1;
     }
     match *result
@@ -33,26 +39,30 @@ fn _median(census: &HashMap<u32, u64>) -> u32 {
     panic!("Huh?")
 }
 
-fn _linear_cost(census: &HashMap<u32, u64>, location: u32) -> u64 {
-    census
-        .iter()
-        .map(|(k, v)| (location as i32 - *k as i32).unsigned_abs() as u64 * v)
-        .sum()
+fn _distance(lhs: u32, rhs: u32) -> u64 {
+    (lhs as i64 - rhs as i64).unsigned_abs()
 }
 
-fn _quadratic_cost(census: &HashMap<u32, u64>, location: u32) -> u64 {
-    census
-        .iter()
-        .map(|(k, v)| {
-            let d = (location as i32 - *k as i32).unsigned_abs() as u64;
-            (d + 1) * d / 2 * v
-        })
-        .sum()
+fn _linear_cost(census: &HashMap<u32, u64>, location: u32) -> Result<u64, ArithmeticError> {
+    let mut result = 0u64;
+    for (k, v) in census {
+        result = result.try_add(_distance(location, *k).try_mul(*v)?)?;
+    }
+    Ok(result)
+}
+
+fn _quadratic_cost(census: &HashMap<u32, u64>, location: u32) -> Result<u64, ArithmeticError> {
+    let mut result = 0u64;
+    for (k, v) in census {
+        let d = _distance(location, *k);
+        result = result.try_add((d.try_add(1)?.try_mul(d)? / 2).try_mul(*v)?)?;
+    }
+    Ok(result)
 }
 
 pub fn part_1(input: &str) -> Result<String, AnyError> {
     let census = _census(input)?;
-    let cost = _linear_cost(&census, _median(&census));
+    let cost = _linear_cost(&census, _median(&census))?;
     Ok(format!("{}", cost))
 }
 
@@ -63,13 +73,20 @@ pub fn part_2(input: &str) -> Result<String, AnyError> {
         itertools::MinMaxResult::OneElement(only) => (*only, *only),
         itertools::MinMaxResult::MinMax(min, max) => (*min, *max),
     };
-    let cost = (min..=max)
-        .map(|location| _quadratic_cost(&census, location))
-        .min()
-        .unwrap();
+    let mut cost = u64::MAX;
+    for location in min..=max {
+        cost = cost.min(_quadratic_cost(&census, location)?);
+    }
     Ok(format!("{}", cost))
 }
 
+crate::registry::register_solvers! {
+    2021, 7, "The Treachery of Whales";
+    fingerprint = Fingerprint::new().lines(1, 1).alphabet("0123456789,");
+    One => part_1,
+    Two => part_2,
+}
+
 #[cfg(test)]
 mod tests {
     use crate::testing::{assert_correct_answer_on_correct_input, assert_error_on_wrong_input};
@@ -102,4 +119,10 @@ mod tests {
     fn returns_error_on_wrong_input() {
         assert_error_on_wrong_input!(&part_1, &part_2);
     }
+
+    #[test]
+    fn returns_error_on_overflow() {
+        let input = format!("0,{}", ["4000000000"; 10].join(","));
+        assert!(part_2(&input).is_err());
+    }
 }
@@ -1,6 +1,8 @@
 use hashbrown::{HashMap, HashSet};
 use itertools::Itertools;
 
+use crate::fingerprint::Fingerprint;
+use crate::parse::Lines;
 use crate::AnyError;
 
 type Key = HashMap<String, usize>;
@@ -19,7 +21,7 @@ fn _displays(text: &str) -> Result<Vec<Vec<Vec<String>>>, AnyError> {
             );
         }
     }
-    let result: Vec<Vec<Vec<String>>> = text.lines().map(_display).collect();
+    let result: Vec<Vec<Vec<String>>> = Lines::new(text)?.map(_display).collect();
     if result.iter().filter(|d| d.len() != 2).count() > 0 {
         return Err("Expected every example to have _signal patterns_ and _output value_".into());
     }
@@ -126,6 +128,13 @@ pub fn part_2(input: &str) -> Result<String, AnyError> {
     Ok(format!("{}", sum))
 }
 
+crate::registry::register_solvers! {
+    2021, 8, "Seven Segment Search";
+    fingerprint = Fingerprint::new().alphabet("abcdefg |").first_line(r"^[a-g ]+\|[a-g ]+$");
+    One => part_1,
+    Two => part_2,
+}
+
 #[cfg(test)]
 mod tests {
     use crate::testing::{assert_correct_answer_on_correct_input, assert_error_on_wrong_input};
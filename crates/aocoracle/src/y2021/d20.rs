@@ -1,8 +1,11 @@
+use crate::fingerprint::Fingerprint;
+use crate::grid::SparseGrid;
+use crate::parse::Lines;
 use crate::AnyError;
+use anyhow::anyhow;
 use hashbrown::HashMap;
-use itertools::Itertools;
 
-type Img = HashMap<(i32, i32), bool>;
+type Img = SparseGrid<bool>;
 type Key = (bool, bool, bool, bool, bool, bool, bool, bool, bool);
 type Lut = HashMap<Key, bool>;
 
@@ -30,8 +33,7 @@ fn _pixel(ch: char) -> Result<bool, AnyError> {
 }
 
 fn _img(text: &str) -> Result<Img, AnyError> {
-    let mut result = HashMap::new();
-    let mut lines = text.lines();
+    let mut lines = Lines::new(text)?;
     lines.next();
     let blank = lines
         .next()
@@ -43,18 +45,18 @@ fn _img(text: &str) -> Result<Img, AnyError> {
         )
         .into());
     }
-    for (r, line) in lines.enumerate() {
-        for (c, cell) in line.chars().enumerate() {
-            result.insert((r as i32, c as i32), _pixel(cell)?);
-        }
-    }
-
-    Ok(result)
+    // Keep the line break that strict parsing expects at the end
+    let mut image = lines.collect::<Vec<_>>().join("\n");
+    image.push('\n');
+    Ok(SparseGrid::parse(&image, |ch| {
+        _pixel(ch).map(Some).map_err(|e| anyhow!("{e}"))
+    })?)
 }
 
 fn _lut(text: &str) -> Result<HashMap<Key, bool>, AnyError> {
-    let mut lines = text.lines();
-    let line = lines.next().unwrap();
+    let line = Lines::new(text)?
+        .next()
+        .ok_or_else(|| String::from("Expected at least one line"))?;
     let mut result = HashMap::new();
     for (i, ch) in line.chars().enumerate() {
         result.insert(_key(i), _pixel(ch)?);
@@ -62,22 +64,26 @@ fn _lut(text: &str) -> Result<HashMap<Key, bool>, AnyError> {
     Ok(result)
 }
 
-fn _once_enhanced(img: &Img, lut: &Lut, padding: bool, min: i32, max: i32) -> Img {
-    let mut result = HashMap::with_capacity((max - min + 3).pow(2) as usize);
-    for r in min - 1..=max + 1 {
-        for c in min - 1..=max + 1 {
+fn _once_enhanced(img: &Img, lut: &Lut, padding: bool) -> Img {
+    let Some(((x0, y0), (x1, y1))) = img.bounds() else {
+        return Img::new();
+    };
+    let pixel = |x, y| img.get((x, y)).copied().unwrap_or(padding);
+    let mut result = Img::new();
+    for y in y0 - 1..=y1 + 1 {
+        for x in x0 - 1..=x1 + 1 {
             let key: Key = (
-                *img.get(&(r - 1, c - 1)).unwrap_or(&padding),
-                *img.get(&(r - 1, c)).unwrap_or(&padding),
-                *img.get(&(r - 1, c + 1)).unwrap_or(&padding),
-                *img.get(&(r, c - 1)).unwrap_or(&padding),
-                *img.get(&(r, c)).unwrap_or(&padding),
-                *img.get(&(r, c + 1)).unwrap_or(&padding),
-                *img.get(&(r + 1, c - 1)).unwrap_or(&padding),
-                *img.get(&(r + 1, c)).unwrap_or(&padding),
-                *img.get(&(r + 1, c + 1)).unwrap_or(&padding),
+                pixel(x - 1, y - 1),
+                pixel(x, y - 1),
+                pixel(x + 1, y - 1),
+                pixel(x - 1, y),
+                pixel(x, y),
+                pixel(x + 1, y),
+                pixel(x - 1, y + 1),
+                pixel(x, y + 1),
+                pixel(x + 1, y + 1),
             );
-            result.insert((r, c), *lut.get(&key).unwrap());
+            result.insert((x, y), *lut.get(&key).unwrap());
         }
     }
     result
@@ -96,14 +102,8 @@ fn _multi_enhanced(img: &Img, lut: &Lut, num_round: usize) -> Img {
             .unwrap(),
     };
 
-    let (min, max) = match img.keys().map(|k| k.0).minmax() {
-        itertools::MinMaxResult::NoElements => panic!("No elements"),
-        itertools::MinMaxResult::OneElement(only) => (only, only),
-        itertools::MinMaxResult::MinMax(min, max) => (min, max),
-    };
-
-    let mut result = _once_enhanced(img, lut, even, min, max);
-    for i in 1..num_round as i32 {
+    let mut result = _once_enhanced(img, lut, even);
+    for i in 1..num_round {
         result = _once_enhanced(
             &result,
             lut,
@@ -112,8 +112,6 @@ fn _multi_enhanced(img: &Img, lut: &Lut, num_round: usize) -> Img {
                 1 => odd,
                 _ => panic!("Oups"),
             },
-            min - i,
-            max + i,
         );
     }
     result
@@ -143,6 +141,13 @@ pub fn part_2(input: &str) -> Result<String, AnyError> {
         .to_string())
 }
 
+crate::registry::register_solvers! {
+    2021, 20, "Trench Map";
+    fingerprint = Fingerprint::new().alphabet("#.").first_line(r"^[#.]+$");
+    One => part_1,
+    Two => part_2,
+}
+
 #[cfg(test)]
 mod tests {
     use crate::testing::{assert_correct_answer_on_correct_input, assert_error_on_wrong_input};
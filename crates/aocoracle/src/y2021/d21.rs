@@ -1,3 +1,5 @@
+use crate::fingerprint::Fingerprint;
+use crate::parse::{self, Lines};
 use crate::AnyError;
 use hashbrown::HashMap;
 
@@ -5,9 +7,15 @@ fn _starting_positions(input: &str) -> Result<[u64; 2], AnyError> {
     let re = regex::Regex::new(r"^Player (1|2) starting position: (\d+)$").unwrap();
     let mut result = [0; 2];
     let mut positions: Vec<u64> = Vec::new();
-    for line in input.lines() {
+    for line in Lines::new(input)? {
         let cap = re.captures(line).ok_or("Regex does not match line")?;
-        positions.push(cap[2].parse::<u64>().unwrap() - 1);
+        let position = parse::number::<u64>(&cap[2])?
+            .checked_sub(1)
+            .ok_or("Expected position to be at least 1")?;
+        positions.push(position);
+    }
+    if positions.len() != 2 {
+        return Err(format!("Expected 2 players but got {}", positions.len()).into());
     }
     result[0] = positions[0];
     result[1] = positions[1];
@@ -94,6 +102,13 @@ pub fn part_2(input: &str) -> Result<String, AnyError> {
     .unwrap()
     .to_string())
 }
+crate::registry::register_solvers! {
+    2021, 21, "Dirac Dice";
+    fingerprint = Fingerprint::new().lines(2, 2).first_line(r"^Player 1 starting position: \d+$");
+    One => part_1,
+    Two => part_2,
+}
+
 #[cfg(test)]
 mod tests {
     use crate::testing::{assert_correct_answer_on_correct_input, assert_error_on_wrong_input};
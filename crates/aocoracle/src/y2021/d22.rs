@@ -1,88 +1,40 @@
-use std::cmp;
-use std::fmt::Debug;
-
 use hashbrown::HashMap;
 
+use crate::fingerprint::Fingerprint;
+use crate::geom::{self, Point3};
+use crate::parse::{self, Lines};
 use crate::AnyError;
 
-#[derive(Debug, Eq, Hash, PartialEq)]
-struct Cuboid {
-    xl: i64,
-    xr: i64,
-    yl: i64,
-    yr: i64,
-    zl: i64,
-    zr: i64,
-}
-
-impl Cuboid {
-    fn new(xl: i64, xr: i64, yl: i64, yr: i64, zl: i64, zr: i64) -> Option<Cuboid> {
-        if xr <= xl || yr <= yl || zr <= zl {
-            return None;
-        }
-        Some(Cuboid {
-            xl,
-            xr,
-            yl,
-            yr,
-            zl,
-            zr,
-        })
-    }
-}
-
-impl Cuboid {
-    fn contains_cuboid(&self, other: &Cuboid) -> bool {
-        self.xl <= other.xl
-            && self.yl <= other.yl
-            && self.zl <= other.zl
-            && other.xr <= self.xr
-            && other.yr <= self.yr
-            && other.zr <= self.zr
-    }
-
-    fn intersection(&self, other: &Cuboid) -> Option<Cuboid> {
-        Cuboid::new(
-            cmp::max(self.xl, other.xl),
-            cmp::min(self.xr, other.xr),
-            cmp::max(self.yl, other.yl),
-            cmp::min(self.yr, other.yr),
-            cmp::max(self.zl, other.zl),
-            cmp::min(self.zr, other.zr),
-        )
-    }
-
-    fn volume(&self) -> i64 {
-        (self.xr - self.xl) * (self.yr - self.yl) * (self.zr - self.zl)
-    }
-}
+type Cuboid = geom::Cuboid<i64>;
 
 fn _steps(text: &str) -> Result<Vec<(bool, Cuboid)>, AnyError> {
     let re = regex::Regex::new(
-        r"(on|off) x=(-?\d+)[.][.](-?\d+),y=(-?\d+)[.][.](-?\d+),z=(-?\d+)[.][.](-?\d+)",
+        r"^(on|off) x=(-?\d+)[.][.](-?\d+),y=(-?\d+)[.][.](-?\d+),z=(-?\d+)[.][.](-?\d+)$",
     )
     .unwrap();
     let mut result = Vec::new();
-    for cap in re.captures_iter(text) {
+    for line in Lines::new(text)? {
+        let cap = re.captures(line).ok_or("Expected one cuboid per line")?;
         let state = match &cap[1] {
             "on" => true,
             "off" => false,
             state => panic!("Unexpected state '{}'", state),
         };
-        let cuboid = Cuboid::new(
-            cap[2].parse::<i64>()?,
-            cap[3].parse::<i64>()? + 1,
-            cap[4].parse::<i64>()?,
-            cap[5].parse::<i64>()? + 1,
-            cap[6].parse::<i64>()?,
-            cap[7].parse::<i64>()? + 1,
+        let cuboid = Cuboid::from_corners(
+            Point3::new(
+                parse::number(&cap[2])?,
+                parse::number(&cap[4])?,
+                parse::number(&cap[6])?,
+            ),
+            Point3::new(
+                parse::number::<i64>(&cap[3])? + 1,
+                parse::number::<i64>(&cap[5])? + 1,
+                parse::number::<i64>(&cap[7])? + 1,
+            ),
         )
         .ok_or_else(|| format!("Not a valid cuboid: {:?}", cap))?;
         result.push((state, cuboid));
     }
-    if result.len() != text.lines().count() {
-        return Err("Expected one cuboid per line".into());
-    }
     Ok(result)
 }
 
@@ -117,10 +69,10 @@ fn _num_on(steps: Vec<(bool, Cuboid)>) -> i64 {
 }
 
 pub fn part_1(input: &str) -> Result<String, Box<dyn std::error::Error>> {
-    let bound = Cuboid::new(-50, 51, -50, 51, -50, 51).unwrap();
+    let bound = Cuboid::from_corners(Point3::new(-50, -50, -50), Point3::new(51, 51, 51)).unwrap();
     let steps = _steps(input)?
         .into_iter()
-        .filter(|(_, cuboid)| bound.contains_cuboid(cuboid))
+        .filter(|(_, cuboid)| bound.contains(cuboid))
         .collect();
     Ok(format!("{}", _num_on(steps)))
 }
@@ -130,6 +82,13 @@ pub fn part_2(input: &str) -> Result<String, Box<dyn std::error::Error>> {
     Ok(format!("{}", _num_on(steps)))
 }
 
+crate::registry::register_solvers! {
+    2021, 22, "Reactor Reboot";
+    fingerprint = Fingerprint::new().first_line(r"^(on|off) x=");
+    One => part_1,
+    Two => part_2,
+}
+
 #[cfg(test)]
 mod tests {
     use crate::testing::{assert_correct_answer_on_correct_input, assert_error_on_wrong_input};
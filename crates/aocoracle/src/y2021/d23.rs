@@ -1,3 +1,7 @@
+use crate::fingerprint::Fingerprint;
+use crate::parse::Lines;
+use crate::search;
+use crate::trace::{self, Event};
 use crate::AnyError;
 use hashbrown::HashMap;
 
@@ -61,7 +65,7 @@ fn _fmt(vs: u64, len: u64) -> String {
 }
 
 fn _rooms(input: &str) -> Result<Rooms, AnyError> {
-    let mut lines = input.lines();
+    let mut lines = Lines::new(input)?;
     if lines.next().ok_or("Expected at least 5 lines but got 0")? != "#############" {
         return Err("Expected first line to be '#############'".into());
     }
@@ -69,7 +73,8 @@ fn _rooms(input: &str) -> Result<Rooms, AnyError> {
         return Err("Expected second line to be #...........#'".into());
     }
     let mut result = [0; NUM_ROOM];
-    for line in lines.rev() {
+    let rest: Vec<_> = lines.collect();
+    for line in rest.into_iter().rev() {
         for (occupant, room) in line
             .trim()
             .chars()
@@ -217,12 +222,12 @@ fn _fmt_amphipod(amphipod: Amphipod) -> char {
     }
 }
 
-fn _print_all(title: &str, hallway: Hallway, rooms: Rooms) {
-    println!("{}", title);
-    println!("{}", _fmt(hallway, 11));
-    for room in rooms {
-        println!("{}", _fmt(room, 5));
-    }
+/// Draw the hallway on the first row and every room, from the hallway inwards, on the next
+fn _all_event(title: &str, hallway: Hallway, rooms: Rooms) -> Event {
+    Event::grid(
+        title,
+        std::iter::once(_fmt(hallway, 11)).chain(rooms.into_iter().map(|room| _fmt(room, 5))),
+    )
 }
 
 fn _min_downstream_cost(rooms: Rooms) -> u64 {
@@ -241,31 +246,18 @@ fn _min_downstream_cost(rooms: Rooms) -> u64 {
     result
 }
 
-fn _min_cost_from_hallway(
-    cache: &mut HashMap<(Hallway, Rooms), Option<u64>>,
-    paths: &Paths,
-    mut hallway: Hallway,
-    mut rooms: Rooms,
-    upstream_cost: u64,
-    mut best_total_cost: u64,
-) -> Option<u64> {
-    let key = (hallway, rooms);
-    if cache.contains_key(&key) {
-        return *cache.get(&key).unwrap();
-    }
-
-    _move_from_hallway(paths, &mut hallway, &mut rooms);
-
-    if _is_empty(hallway)
+fn _is_organized(hallway: Hallway, rooms: Rooms) -> bool {
+    _is_empty(hallway)
         && (0..NUM_ROOM as u64)
             .all(|i| !_is_empty(rooms[i as usize]) && _room_contains_only(rooms[i as usize], i))
-    {
-        cache.insert(key, Some(0));
-        return Some(0);
-    }
+}
 
-    let mut ok = false;
-    let mut best_cost = std::u64::MAX / 2;
+/// Return every state reachable by moving one amphipod from a room to the hallway, after letting
+/// every amphipod that can walk into its own room do so, and the cost of the move.
+///
+/// Only moves along the hallway are counted; moves in and out of rooms are counted by `_penalty`.
+fn _successors(paths: &Paths, hallway: Hallway, rooms: Rooms) -> Vec<((Hallway, Rooms), u64)> {
+    let mut result = Vec::new();
     for src in 0..NUM_ROOM as u64 {
         if _room_contains_only(rooms[src as usize], src) {
             continue;
@@ -276,41 +268,14 @@ fn _min_cost_from_hallway(
         new_rooms[src as usize] = new_room;
 
         for dst in _accessible_tiles(paths, hallway, src) {
-            let marginal_cost = _room_hallway_distance(src, dst, amphipod) * _multiplier(amphipod);
-
-            let min_downstream_cost = _min_downstream_cost(new_rooms);
-            let min_total_cost = upstream_cost + marginal_cost + min_downstream_cost;
-            if best_total_cost <= min_total_cost {
-                continue;
-            }
-
-            let new_hallway = _insert(hallway, dst, amphipod);
-
-            let downstream_cost = match _min_cost_from_hallway(
-                cache,
-                paths,
-                new_hallway,
-                new_rooms,
-                upstream_cost + marginal_cost,
-                best_total_cost,
-            ) {
-                Some(cost) => cost,
-                None => continue,
-            };
-            let cost = marginal_cost + downstream_cost;
-            if cost < best_cost {
-                ok = true;
-                best_cost = cost;
-                best_total_cost = upstream_cost + cost;
-            }
+            let cost = _room_hallway_distance(src, dst, amphipod) * _multiplier(amphipod);
+            let mut next_hallway = _insert(hallway, dst, amphipod);
+            let mut next_rooms = new_rooms;
+            _move_from_hallway(paths, &mut next_hallway, &mut next_rooms);
+            result.push(((next_hallway, next_rooms), cost));
         }
     }
-    if ok {
-        cache.insert(key, Some(best_cost));
-        return Some(best_cost);
-    }
-    cache.insert(key, None);
-    None
+    result
 }
 
 fn _departure_penalty(room: Room, room_num: u64) -> u64 {
@@ -357,17 +322,22 @@ fn _penalty(rooms: Rooms) -> u64 {
         .sum()
 }
 
-fn _part_x(rooms: Rooms) -> Option<u64> {
+fn _part_x(rooms: Rooms) -> anyhow::Result<Option<u64>> {
+    trace::emit(|| _all_event("Initial state", 0, rooms));
     let paths = _paths();
-    let mut cache = HashMap::new();
-    let from_hallway = _min_cost_from_hallway(&mut cache, &paths, 0, rooms, 0, std::u64::MAX)?;
-    let from_rooms = _penalty(rooms);
-    Some(from_hallway + from_rooms)
+    let outcome = search::astar(
+        [(0, rooms)],
+        |&(hallway, rooms)| _successors(&paths, hallway, rooms),
+        |&(_, rooms)| _min_downstream_cost(rooms),
+        |&(hallway, rooms)| _is_organized(hallway, rooms),
+    )?;
+    Ok(outcome.path.map(|path| path.cost + _penalty(rooms)))
 }
 
 pub fn part_1(input: &str) -> Result<String, AnyError> {
     let rooms = _rooms(input)?;
-    if let Some(answer) = _part_x(rooms) {
+    let answer = _part_x(rooms)?;
+    if let Some(answer) = answer {
         return Ok(answer.to_string());
     }
     Err("No answer".into())
@@ -395,12 +365,20 @@ pub fn part_2(input: &str) -> Result<String, AnyError> {
     rooms[3] = _push(rooms[3], 0);
     rooms[3] = _push(rooms[3], tmp.1);
 
-    if let Some(answer) = _part_x(rooms) {
+    let answer = _part_x(rooms)?;
+    if let Some(answer) = answer {
         return Ok(answer.to_string());
     }
     Err("No answer".into())
 }
 
+crate::registry::register_solvers! {
+    2021, 23, "Amphipod";
+    fingerprint = Fingerprint::new().lines(5, 5).alphabet("#.ABCD ");
+    One => part_1,
+    Two => part_2,
+}
+
 #[cfg(test)]
 mod tests {
     use crate::testing::{assert_correct_answer_on_correct_input, assert_error_on_wrong_input};
@@ -1,3 +1,5 @@
+use crate::fingerprint::Fingerprint;
+use crate::parse::{self, Lines};
 use crate::AnyError;
 
 struct Subroutine {
@@ -17,20 +19,25 @@ impl Subroutine {
             should_pop: match lines[4]
                 .split_whitespace()
                 .nth(2)
-                .map(|t| t.parse())
+                .map(parse::number)
                 .ok_or("Could not parse instruction")?
             {
                 Ok(1) => false,
                 Ok(RADIX) => true,
                 _ => return Err("Unexpected value for b".into()),
             },
-            gate_offset: lines[5].split_whitespace().nth(2).unwrap().parse().unwrap(),
-            source_offset: lines[15]
-                .split_whitespace()
-                .nth(2)
-                .unwrap()
-                .parse()
-                .unwrap(),
+            gate_offset: parse::number(
+                lines[5]
+                    .split_whitespace()
+                    .nth(2)
+                    .ok_or("Could not parse instruction")?,
+            )?,
+            source_offset: parse::number(
+                lines[15]
+                    .split_whitespace()
+                    .nth(2)
+                    .ok_or("Could not parse instruction")?,
+            )?,
         })
     }
 
@@ -48,7 +55,7 @@ impl Subroutine {
 
 fn _subroutines(text: &str) -> Result<Vec<Subroutine>, AnyError> {
     let mut result = Vec::new();
-    for chunk in text.lines().collect::<Vec<&str>>().chunks(18) {
+    for chunk in Lines::new(text)?.collect::<Vec<&str>>().chunks(18) {
         result.push(Subroutine::from_lines(chunk)?);
     }
     Ok(result)
@@ -103,6 +110,13 @@ pub fn part_2(input: &str) -> Result<String, AnyError> {
     )
 }
 
+crate::registry::register_solvers! {
+    2021, 24, "Arithmetic Logic Unit";
+    fingerprint = Fingerprint::new().first_line(r"^inp [wxyz]$");
+    One => part_1,
+    Two => part_2,
+}
+
 #[cfg(test)]
 mod tests {
     use crate::testing::{assert_correct_answer_on_correct_input, assert_error_on_wrong_input};
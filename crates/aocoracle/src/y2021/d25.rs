@@ -1,32 +1,30 @@
+use crate::fingerprint::Fingerprint;
+use crate::grid::Grid;
+use crate::trace::{self, Event};
 use crate::AnyError;
+use anyhow::bail;
 use hashbrown::HashSet;
-use itertools::Itertools;
 
 type Herd = HashSet<(usize, usize)>;
 
-fn _herds(text: &str) -> Result<(Herd, Herd), AnyError> {
-    let mut east = HashSet::new();
-    let mut south = HashSet::new();
-    for (row, line) in text.lines().enumerate() {
-        for (col, cell) in line.trim().chars().enumerate() {
-            match cell {
-                '>' => {
-                    east.insert((row, col));
-                }
-                'v' => {
-                    south.insert((row, col));
-                }
-                '.' => (),
-                _ => return Err(format!("Unexpected cell {}", cell).into()),
-            }
-        }
-    }
-    Ok((east, south))
+fn _herds(text: &str) -> Result<(Herd, Herd, usize, usize), AnyError> {
+    let grid = Grid::parse(text, |cell| match cell {
+        '>' | 'v' | '.' => Ok(cell),
+        _ => bail!("Unexpected cell {}", cell),
+    })?;
+    let herd = |kind| {
+        grid.iter()
+            .filter(|&(_, &cell)| cell == kind)
+            .map(|((col, row), _)| (row, col))
+            .collect()
+    };
+    Ok((herd('>'), herd('v'), grid.height(), grid.width()))
 }
 
-fn _fmt_herds(east: &Herd, south: &Herd, height: usize, width: usize) -> String {
-    (0..height)
-        .map(|row| {
+fn _herds_event(label: String, east: &Herd, south: &Herd, height: usize, width: usize) -> Event {
+    Event::grid(
+        label,
+        (0..height).map(|row| {
             (0..width)
                 .map(|col| {
                     if east.contains(&(row, col)) {
@@ -38,8 +36,8 @@ fn _fmt_herds(east: &Herd, south: &Herd, height: usize, width: usize) -> String
                     }
                 })
                 .collect::<String>()
-        })
-        .join("\n")
+        }),
+    )
 }
 
 fn _new_herds(old_east: &Herd, old_south: &Herd, height: usize, width: usize) -> (Herd, Herd) {
@@ -68,28 +66,17 @@ fn _new_herds(old_east: &Herd, old_south: &Herd, height: usize, width: usize) ->
     (new_east, new_south)
 }
 
-fn _num_herds(east: Herd, south: Herd) -> usize {
-    let height = *east
-        .iter()
-        .chain(south.iter())
-        .map(|(r, _)| r)
-        .max()
-        .unwrap()
-        + 1;
-    let width = *east
-        .iter()
-        .chain(south.iter())
-        .map(|(_, c)| c)
-        .max()
-        .unwrap()
-        + 1;
-
+fn _num_herds(east: Herd, south: Herd, height: usize, width: usize) -> usize {
     let mut herds = [(east, south), (HashSet::new(), HashSet::new())];
     for herd_num in 1.. {
         let old = &herds[(herd_num + 1) % 2];
         let new = _new_herds(&old.0, &old.1, height, width);
 
         if old.0 == new.0 && old.1 == new.1 {
+            trace::emit(|| {
+                let label = format!("After {herd_num} steps");
+                _herds_event(label, &new.0, &new.1, height, width)
+            });
             return herd_num;
         }
         herds[herd_num % 2] = new;
@@ -98,8 +85,14 @@ fn _num_herds(east: Herd, south: Herd) -> usize {
 }
 
 pub fn part_1(input: &str) -> Result<String, AnyError> {
-    let (east, south) = _herds(input)?;
-    Ok(_num_herds(east, south).to_string())
+    let (east, south, height, width) = _herds(input)?;
+    Ok(_num_herds(east, south, height, width).to_string())
+}
+
+crate::registry::register_solvers! {
+    2021, 25, "Sea Cucumber";
+    fingerprint = Fingerprint::new().alphabet(">v.");
+    One => part_1,
 }
 
 #[cfg(test)]
@@ -1,3 +1,6 @@
+use crate::checked::CheckedIter;
+use crate::fingerprint::{Fingerprint, DIGITS};
+use crate::parse::{self, Lines};
 use anyhow::bail;
 use std::cmp::Reverse;
 use std::collections::BinaryHeap;
@@ -27,12 +30,12 @@ impl<T: ?Sized> TopN for T where T: Iterator {}
 fn inventories(s: &str) -> anyhow::Result<Vec<Vec<u32>>> {
     let mut result = Vec::new();
     let mut inventory = Vec::new();
-    for line in s.lines() {
+    for line in Lines::new(s)? {
         if line.is_empty() {
             result.push(inventory);
             inventory = Vec::new();
         } else {
-            inventory.push(line.parse()?);
+            inventory.push(parse::number(line)?);
         }
     }
     result.push(inventory);
@@ -50,22 +53,34 @@ fn inventories(s: &str) -> anyhow::Result<Vec<Vec<u32>>> {
     Ok(result)
 }
 
+fn totals(s: &str) -> anyhow::Result<Vec<u32>> {
+    Ok(inventories(s)?
+        .into_iter()
+        .map(|inventory| inventory.into_iter().try_sum())
+        .collect::<Result<_, _>>()?)
+}
+
 pub fn part_1(input: &str) -> anyhow::Result<u32> {
-    Ok(inventories(input)?
+    Ok(totals(input)?
         .into_iter()
-        .map(|inventory| inventory.iter().sum())
         .max()
         .expect("Parsing ensures there is at least one inventory"))
 }
 
 pub fn part_2(input: &str) -> anyhow::Result<u32> {
-    Ok(inventories(input)?
+    Ok(totals(input)?
         .into_iter()
-        .map(|inventory| inventory.iter().sum::<u32>())
         .top(3)
         .expect("Parsing ensures there are at least three inventories")
         .into_iter()
-        .sum())
+        .try_sum()?)
+}
+
+crate::registry::register_solvers! {
+    2022, 1, "Calorie Counting";
+    fingerprint = Fingerprint::new().alphabet(DIGITS);
+    One => part_1,
+    Two => part_2,
 }
 
 #[cfg(test)]
@@ -99,4 +114,20 @@ mod tests {
     fn returns_error_on_wrong_input() {
         assert_error_on_wrong_input!(part_1, part_2);
     }
+
+    #[test]
+    fn strict_mode_rejects_leading_zeros() {
+        let text = "1\n\n02\n\n3\n";
+        assert_eq!(part_1(text).unwrap(), 3);
+        parse::with_strict(true, || assert!(part_1(text).is_err()));
+    }
+
+    #[test]
+    fn returns_error_on_overflow() {
+        let input = "4000000000\n300000000\n\n1\n\n2";
+        assert!(part_1(input).is_err());
+        let input = "4000000000\n\n300000000\n\n1\n\n2";
+        assert_eq!(part_1(input).unwrap(), 4000000000);
+        assert!(part_2(input).is_err());
+    }
 }
@@ -1,5 +1,7 @@
 use std::str::FromStr;
 
+use crate::fingerprint::Fingerprint;
+use crate::parse::Lines;
 use anyhow::anyhow;
 
 #[derive(Clone, Copy, Debug)]
@@ -65,7 +67,7 @@ impl FromStr for Outcome {
 fn rounds(s: &str) -> anyhow::Result<Vec<(Shape, Outcome)>> {
     let re = regex::Regex::new(r"^([ABC]) ([XYZ])$").expect("Hard coded regex is valid");
     let mut result = Vec::new();
-    for line in s.lines() {
+    for line in Lines::new(s)? {
         let cap = re
             .captures(line)
             .ok_or_else(|| anyhow!("Could not capture play on line {}", line))?;
@@ -129,6 +131,13 @@ pub fn part_2(input: &str) -> anyhow::Result<u32> {
         .sum())
 }
 
+crate::registry::register_solvers! {
+    2022, 2, "Rock Paper Scissors";
+    fingerprint = Fingerprint::new().alphabet("ABCXYZ ");
+    One => part_1,
+    Two => part_2,
+}
+
 #[cfg(test)]
 mod tests {
     use crate::testing::{assert_correct_answer_on_correct_input, assert_error_on_wrong_input};
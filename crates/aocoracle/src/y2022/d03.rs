@@ -1,5 +1,7 @@
 use std::str::FromStr;
 
+use crate::fingerprint::{Fingerprint, LETTERS};
+use crate::parse::Lines;
 use anyhow::bail;
 use hashbrown::HashSet;
 use itertools::Itertools;
@@ -39,7 +41,7 @@ impl FromStr for Rucksack {
 
 fn rucksacks(s: &str) -> anyhow::Result<Vec<Rucksack>> {
     let mut result = Vec::new();
-    for line in s.lines() {
+    for line in Lines::new(s)? {
         result.push(Rucksack::from_str(line)?);
     }
     if result.len() % 3 != 0 {
@@ -82,6 +84,13 @@ pub fn part_2(input: &str) -> anyhow::Result<u32> {
     Ok(result)
 }
 
+crate::registry::register_solvers! {
+    2022, 3, "Rucksack Reorganization";
+    fingerprint = Fingerprint::new().alphabet(LETTERS);
+    One => part_1,
+    Two => part_2,
+}
+
 #[cfg(test)]
 mod tests {
     use crate::testing::{assert_correct_answer_on_correct_input, assert_error_on_wrong_input};
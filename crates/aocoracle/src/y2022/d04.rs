@@ -1,3 +1,5 @@
+use crate::fingerprint::Fingerprint;
+use crate::parse::{self, Lines};
 use anyhow::anyhow;
 
 struct Range {
@@ -21,18 +23,18 @@ fn ranges(s: &str) -> anyhow::Result<Vec<(Range, Range)>> {
     let re =
         regex::Regex::new(r"^(\d+)+-(\d+)+,(\d+)+-(\d+)+$").expect("Hard coded regex is valid");
     let mut result = Vec::new();
-    for line in s.lines() {
+    for line in Lines::new(s)? {
         let cap = re
             .captures(line)
             .ok_or_else(|| anyhow!("Could not capture ranges on line {}", line))?;
         result.push((
             Range {
-                start: cap[1].parse::<u32>()?,
-                end: cap[2].parse::<u32>()?,
+                start: parse::number(&cap[1])?,
+                end: parse::number(&cap[2])?,
             },
             Range {
-                start: cap[3].parse::<u32>()?,
-                end: cap[4].parse::<u32>()?,
+                start: parse::number(&cap[3])?,
+                end: parse::number(&cap[4])?,
             },
         ));
     }
@@ -53,6 +55,13 @@ pub fn part_2(input: &str) -> anyhow::Result<usize> {
         .count())
 }
 
+crate::registry::register_solvers! {
+    2022, 4, "Camp Cleanup";
+    fingerprint = Fingerprint::new().first_line(r"^\d+-\d+,\d+-\d+$");
+    One => part_1,
+    Two => part_2,
+}
+
 #[cfg(test)]
 mod tests {
     use crate::testing::{assert_correct_answer_on_correct_input, assert_error_on_wrong_input};
@@ -1,12 +1,14 @@
-use std::str::Lines;
-
+use crate::fingerprint::Fingerprint;
+use crate::parse::{self, Lines};
 use anyhow::{anyhow, bail};
 use hashbrown::HashMap;
 
 fn take_stacks(lines: &mut Lines) -> anyhow::Result<HashMap<usize, Vec<char>>> {
     let mut result = HashMap::<usize, Vec<char>>::new();
     loop {
-        let line = lines.next().unwrap();
+        let line = lines
+            .next()
+            .ok_or_else(|| anyhow!("Expected empty line after stacks"))?;
         if line.is_empty() {
             break;
         }
@@ -41,13 +43,17 @@ fn take_procedure(lines: &mut Lines) -> anyhow::Result<Vec<(usize, usize, usize)
         let cap = re
             .captures(line)
             .ok_or_else(|| anyhow!("Could not capture line {}", line))?;
-        result.push((cap[1].parse()?, cap[2].parse()?, cap[3].parse()?))
+        result.push((
+            parse::number(&cap[1])?,
+            parse::number(&cap[2])?,
+            parse::number(&cap[3])?,
+        ))
     }
     Ok(result)
 }
 
 fn part_x(input: &str, preserve_order: bool) -> anyhow::Result<String> {
-    let mut lines = input.lines();
+    let mut lines = Lines::new(input)?;
     let mut stacks = take_stacks(&mut lines)?;
     let steps = take_procedure(&mut lines)?;
 
@@ -90,6 +96,13 @@ pub fn part_2(input: &str) -> anyhow::Result<String> {
     part_x(input, true)
 }
 
+crate::registry::register_solvers! {
+    2022, 5, "Supply Stacks";
+    fingerprint = Fingerprint::new().first_line(r"^[\[\] A-Z]+$");
+    One => part_1,
+    Two => part_2,
+}
+
 #[cfg(test)]
 mod tests {
     use crate::testing::{assert_correct_answer_on_correct_input, assert_error_on_wrong_input};
@@ -1,5 +1,6 @@
 use std::collections::VecDeque;
 
+use crate::fingerprint::{Fingerprint, LOWERCASE};
 use anyhow::anyhow;
 use hashbrown::HashSet;
 
@@ -34,10 +35,18 @@ pub fn part_2(input: &str) -> anyhow::Result<usize> {
     start_of_message(datastream(input)?, 14)
 }
 
+crate::registry::register_solvers! {
+    2022, 6, "Tuning Trouble";
+    fingerprint = Fingerprint::new().lines(1, 1).alphabet(LOWERCASE);
+    accepts = |text| Fingerprint::new().alphabet(LOWERCASE).matches(text);
+    One => part_1,
+    Two => part_2,
+}
+
 #[cfg(test)]
 mod tests {
     use crate::testing::{assert_correct_answer_on_correct_input, assert_error_on_wrong_input};
-    use crate::Part;
+    use crate::{Part, Solver};
 
     use super::*;
 
@@ -68,4 +77,11 @@ mod tests {
     fn returns_error_on_wrong_input() {
         assert_error_on_wrong_input!(part_1, part_2);
     }
+
+    #[test]
+    fn accepts_only_lowercase_letters() {
+        let solver = &registrations()[0];
+        assert!(solver.accepts("mjqjpqmgbljsphdztnvjfqwrcgsmlb\n"));
+        assert!(!solver.accepts("1000\n2000\n"));
+    }
 }
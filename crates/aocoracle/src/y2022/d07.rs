@@ -1,3 +1,6 @@
+use crate::checked::{Checked, CheckedIter};
+use crate::fingerprint::Fingerprint;
+use crate::parse::{self, Lines};
 use anyhow::{anyhow, bail};
 use hashbrown::HashMap;
 use itertools::Itertools;
@@ -9,10 +12,10 @@ enum Line {
 
 fn terminal_output(s: &str) -> anyhow::Result<Vec<Line>> {
     let mut result = Vec::new();
-    for line in s.lines() {
+    for line in Lines::new(s)? {
         if let Some(suffix) = line.strip_prefix("$ cd ") {
             result.push(Line::Cd(suffix.to_string()));
-        } else if line.starts_with("$ ls") || line.starts_with("dir ") {
+        } else if line == "$ ls" || line.starts_with("dir ") {
             // Never used
         } else {
             let (size, name) = line
@@ -21,14 +24,14 @@ fn terminal_output(s: &str) -> anyhow::Result<Vec<Line>> {
             if name.contains(' ') {
                 bail!("Expected filename without spaces but got {name}");
             }
-            let size = size.parse::<usize>()?;
+            let size = parse::number(size)?;
             result.push(Line::File(name.to_string(), size));
         }
     }
     Ok(result)
 }
 
-fn directory_sizes(terminal_output: Vec<Line>) -> HashMap<String, usize> {
+fn directory_sizes(terminal_output: Vec<Line>) -> anyhow::Result<HashMap<String, usize>> {
     let mut result = HashMap::new();
     let mut path = Vec::new();
     for line in terminal_output.into_iter() {
@@ -47,28 +50,32 @@ fn directory_sizes(terminal_output: Vec<Line>) -> HashMap<String, usize> {
             },
             Line::File(_, size) => {
                 for i in 1..=path.len() {
-                    *result.entry(path[..i].join("/")).or_default() += size;
+                    let total: &mut usize = result.entry(path[..i].join("/")).or_default();
+                    *total = total.try_add(size)?;
                 }
             }
         }
     }
-    result
+    Ok(result)
 }
 
 pub fn part_1(input: &str) -> anyhow::Result<usize> {
-    let directory_sizes = directory_sizes(terminal_output(input)?);
+    let directory_sizes = directory_sizes(terminal_output(input)?)?;
     Ok(directory_sizes
         .values()
-        .filter(|size| **size <= 100000)
-        .sum())
+        .copied()
+        .filter(|size| *size <= 100000)
+        .try_sum()?)
 }
 
 pub fn part_2(input: &str) -> anyhow::Result<usize> {
-    let directory_sizes = directory_sizes(terminal_output(input)?);
+    let directory_sizes = directory_sizes(terminal_output(input)?)?;
     let total = directory_sizes
         .get("/")
         .ok_or_else(|| anyhow!("Expected terminal output to include file system root"))?;
-    let needed = total - 40000000;
+    let needed = total
+        .checked_sub(40000000)
+        .ok_or_else(|| anyhow!("Expected more than 40000000 to be used but got {total}"))?;
     Ok(*directory_sizes
         .values()
         .filter(|size| **size >= needed)
@@ -77,6 +84,13 @@ pub fn part_2(input: &str) -> anyhow::Result<usize> {
         .ok_or_else(|| anyhow!("Expected at least one directory to solve the problem"))?)
 }
 
+crate::registry::register_solvers! {
+    2022, 7, "No Space Left On Device";
+    fingerprint = Fingerprint::new().first_line(r"^\$ cd /$");
+    One => part_1,
+    Two => part_2,
+}
+
 #[cfg(test)]
 mod tests {
     use crate::testing::{assert_correct_answer_on_correct_input, assert_error_on_wrong_input};
@@ -108,4 +122,12 @@ mod tests {
     fn returns_error_on_wrong_input() {
         assert_error_on_wrong_input!(part_1, part_2);
     }
+
+    #[test]
+    fn returns_error_on_overflow() {
+        let input = format!("$ cd /\n$ ls\n{} a\n1 b", usize::MAX);
+        assert!(part_1(&input).is_err());
+        assert!(part_2(&input).is_err());
+        assert!(part_2("$ cd /\n$ ls\n1 a").is_err());
+    }
 }
@@ -1,75 +1,40 @@
+use crate::checked::ArithmeticError;
+use crate::fingerprint::Fingerprint;
+use crate::geom::{Direction, Point2};
+use crate::parse::{self, Lines};
 use anyhow::{anyhow, bail};
 use hashbrown::HashSet;
 use std::iter;
-use std::ops::{Add, AddAssign, Sub};
 
-#[derive(Clone, Default, Eq, Hash, PartialEq)]
-struct Point {
-    x: i32,
-    y: i32,
-}
-
-impl Add for Point {
-    type Output = Self;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        Self {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
-        }
-    }
-}
-
-impl AddAssign for Point {
-    fn add_assign(&mut self, rhs: Self) {
-        *self = Self {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
-        }
-    }
-}
-
-impl Sub for &Point {
-    type Output = Point;
-
-    fn sub(self, rhs: Self) -> Self::Output {
-        Point {
-            x: self.x - rhs.x,
-            y: self.y - rhs.y,
-        }
-    }
-}
+type Point = Point2<i32>;
 
 fn motions(s: &str) -> anyhow::Result<Vec<(Point, i32)>> {
     let mut result = Vec::new();
-    for line in s.lines() {
+    for line in Lines::new(s)? {
         let (direction, distance) = line.split_once(' ').ok_or_else(|| {
             anyhow!("Expected direction and distance separated by space but got {line}")
         })?;
         let direction = match direction {
-            "U" => Point { x: 0, y: 1 },
-            "D" => Point { x: 0, y: -1 },
-            "L" => Point { x: -1, y: 0 },
-            "R" => Point { x: 1, y: 0 },
+            "U" => Direction::Up,
+            "D" => Direction::Down,
+            "L" => Direction::Left,
+            "R" => Direction::Right,
             _ => bail!("Expected direction to be one of U, D, L, R but got {direction}"),
         };
-        let distance = distance.parse::<i32>()?;
-        result.push((direction, distance));
+        let distance = parse::number(distance)?;
+        result.push((direction.offset(), distance));
     }
     Ok(result)
 }
 
-fn tail_motion(head: &Point, tail: &Point) -> Point {
-    let displacement = head - tail;
-    assert!((-2..=2).contains(&displacement.x));
-    assert!((-2..=2).contains(&displacement.y));
-    if displacement.x.abs() == 2 || displacement.y.abs() == 2 {
-        Point {
-            x: displacement.x.signum(),
-            y: displacement.y.signum(),
-        }
+fn tail_motion(head: Point, tail: Point) -> Result<Point, ArithmeticError> {
+    let displacement = head.try_sub(tail)?;
+    let distance = head.chebyshev(&tail)?;
+    assert!(distance <= 2);
+    if distance == 2 {
+        Ok(Point::new(displacement.x.signum(), displacement.y.signum()))
     } else {
-        Point::default()
+        Ok(Point::default())
     }
 }
 
@@ -82,12 +47,12 @@ fn part_x(input: &str, num_knot: usize) -> anyhow::Result<usize> {
     let mut visited: HashSet<_> = iter::once(Point::default()).collect();
     for (direction, distance) in motions {
         for _ in 0..distance {
-            rope[0] += direction.clone();
+            rope[0] = rope[0].try_add(direction)?;
             for i in 1..rope.len() {
-                let tail_motion = tail_motion(&rope[i - 1], &rope[i]);
+                let tail_motion = tail_motion(rope[i - 1], rope[i])?;
                 rope[i] += tail_motion;
             }
-            visited.insert(rope[rope.len() - 1].clone());
+            visited.insert(rope[rope.len() - 1]);
         }
     }
     Ok(visited.len())
@@ -101,6 +66,13 @@ pub fn part_2(input: &str) -> anyhow::Result<usize> {
     part_x(input, 10)
 }
 
+crate::registry::register_solvers! {
+    2022, 9, "Rope Bridge";
+    fingerprint = Fingerprint::new().first_line(r"^[UDLR] \d+$");
+    One => part_1,
+    Two => part_2,
+}
+
 #[cfg(test)]
 mod tests {
     use crate::testing::{assert_correct_answer_on_correct_input, assert_error_on_wrong_input};
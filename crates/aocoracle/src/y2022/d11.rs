@@ -2,7 +2,10 @@ use anyhow::{anyhow, bail};
 
 use std::collections::VecDeque;
 
-use std::str::{FromStr, Lines};
+use crate::checked::{ArithmeticError, Checked, CheckedIter};
+use crate::fingerprint::Fingerprint;
+use crate::parse::{self, Lines};
+use std::str::FromStr;
 
 #[derive(Debug)]
 enum Operand {
@@ -16,7 +19,7 @@ impl FromStr for Operand {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             "old" => Ok(Self::Old),
-            x => Ok(Self::Const(x.parse()?)),
+            x => Ok(Self::Const(parse::number(x)?)),
         }
     }
 }
@@ -47,16 +50,15 @@ struct Operation {
 }
 
 impl Operation {
-    fn evaluate(&self, old: i64) -> i64 {
-        match (&self.op, &self.lhs, &self.rhs) {
-            (Operator::Add, Operand::Const(lhs), Operand::Const(rhs)) => lhs + rhs,
-            (Operator::Add, Operand::Const(lhs), Operand::Old) => lhs + old,
-            (Operator::Add, Operand::Old, Operand::Const(rhs)) => old + rhs,
-            (Operator::Add, Operand::Old, Operand::Old) => old + old,
-            (Operator::Mul, Operand::Const(lhs), Operand::Const(rhs)) => lhs * rhs,
-            (Operator::Mul, Operand::Const(lhs), Operand::Old) => lhs * old,
-            (Operator::Mul, Operand::Old, Operand::Const(rhs)) => old * rhs,
-            (Operator::Mul, Operand::Old, Operand::Old) => old * old,
+    fn evaluate(&self, old: i64) -> Result<i64, ArithmeticError> {
+        let value = |operand: &Operand| match operand {
+            Operand::Const(value) => *value,
+            Operand::Old => old,
+        };
+        let (lhs, rhs) = (value(&self.lhs), value(&self.rhs));
+        match self.op {
+            Operator::Add => lhs.try_add(rhs),
+            Operator::Mul => lhs.try_mul(rhs),
         }
     }
 }
@@ -87,18 +89,23 @@ struct Monkey {
 }
 
 impl Monkey {
-    fn inspect_and_throw(&mut self, denominator: i64) -> Option<(usize, i64)> {
-        let old = self.items.pop_front()?;
-        let new = self.operation.evaluate(old) / denominator;
-        if new % self.test == 0 {
-            Some((self.destination_true, new))
+    fn inspect_and_throw(
+        &mut self,
+        denominator: i64,
+    ) -> Result<Option<(usize, i64)>, ArithmeticError> {
+        let Some(old) = self.items.pop_front() else {
+            return Ok(None);
+        };
+        let new = self.operation.evaluate(old)?.try_div(denominator)?;
+        if new.try_rem(self.test)? == 0 {
+            Ok(Some((self.destination_true, new)))
         } else {
-            Some((self.destination_false, new))
+            Ok(Some((self.destination_false, new)))
         }
     }
 }
 
-fn take_line<'a>(lines: &'a mut Lines, prefix: &str) -> anyhow::Result<&'a str> {
+fn take_line<'a>(lines: &mut Lines<'a>, prefix: &str) -> anyhow::Result<&'a str> {
     let line = lines
         .next()
         .ok_or_else(|| anyhow!("Expected line starting with '{prefix}' but got no line"))?;
@@ -106,28 +113,25 @@ fn take_line<'a>(lines: &'a mut Lines, prefix: &str) -> anyhow::Result<&'a str>
         .ok_or_else(|| anyhow!("Expected line starting with '{prefix}' but got '{line}'"))
 }
 
-impl FromStr for Monkey {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut lines = s.lines();
-
-        let id = take_line(&mut lines, "Monkey ")?
-            .strip_suffix(':')
-            .ok_or_else(|| anyhow!("Expected line ending with ':'"))?
-            .parse()?;
+impl Monkey {
+    fn take(lines: &mut Lines) -> anyhow::Result<Self> {
+        let id = parse::number(
+            take_line(lines, "Monkey ")?
+                .strip_suffix(':')
+                .ok_or_else(|| anyhow!("Expected line ending with ':'"))?,
+        )?;
 
         let mut items = VecDeque::new();
-        for item in take_line(&mut lines, "  Starting items: ")?.split(',') {
-            items.push_back(item.trim().parse()?);
+        for item in take_line(lines, "  Starting items: ")?.split(", ") {
+            items.push_back(parse::number(item)?);
         }
 
-        let operation = take_line(&mut lines, "  Operation: new = ")?.parse()?;
+        let operation = take_line(lines, "  Operation: new = ")?.parse()?;
 
-        let test = take_line(&mut lines, "  Test: divisible by ")?.parse()?;
+        let test = parse::number(take_line(lines, "  Test: divisible by ")?)?;
 
-        let destination_true = take_line(&mut lines, "    If true: throw to monkey ")?.parse()?;
-        let destination_false = take_line(&mut lines, "    If false: throw to monkey ")?.parse()?;
+        let destination_true = parse::number(take_line(lines, "    If true: throw to monkey ")?)?;
+        let destination_false = parse::number(take_line(lines, "    If false: throw to monkey ")?)?;
 
         Ok(Monkey {
             id,
@@ -142,12 +146,18 @@ impl FromStr for Monkey {
 
 fn monkeys(s: &str) -> anyhow::Result<Vec<Monkey>> {
     let mut result = Vec::new();
-    for (i, monkey) in s.split("\n\n").enumerate() {
-        let monkey = Monkey::from_str(monkey)?;
+    let mut lines = Lines::new(s)?;
+    for i in 0.. {
+        let monkey = Monkey::take(&mut lines)?;
         if monkey.id != i {
             bail!("Expected monkey id {i} but got {0}", monkey.id);
         }
         result.push(monkey);
+        match lines.next() {
+            None => break,
+            Some("") => {}
+            Some(line) => bail!("Expected empty line between monkeys but got {line:?}"),
+        }
     }
     Ok(result)
 }
@@ -161,19 +171,19 @@ fn monkey_business(
         bail!("Expected at least 2 monkeys but got {}", monkeys.len());
     }
     let mut counts = vec![0; monkeys.len()];
-    let modulus: i64 = monkeys.iter().map(|m| m.test).product();
+    let modulus = monkeys.iter().map(|m| m.test).try_product()?;
     for _ in 0..num_round {
         for src in 0..monkeys.len() {
-            while let Some((dst, lvl)) = monkeys[src].inspect_and_throw(denominator) {
+            while let Some((dst, lvl)) = monkeys[src].inspect_and_throw(denominator)? {
                 counts[src] += 1;
-                monkeys[dst].items.push_back(lvl % modulus);
+                monkeys[dst].items.push_back(lvl.try_rem(modulus)?);
             }
         }
     }
     counts.sort();
     let first = counts.pop().expect("Length checked above");
     let second = counts.pop().expect("Length checked above");
-    Ok(first * second)
+    Ok(first.try_mul(second)?)
 }
 
 pub fn part_1(input: &str) -> anyhow::Result<usize> {
@@ -186,6 +196,13 @@ pub fn part_2(input: &str) -> anyhow::Result<usize> {
     monkey_business(monkeys, 1, 10000)
 }
 
+crate::registry::register_solvers! {
+    2022, 11, "Monkey in the Middle";
+    fingerprint = Fingerprint::new().first_line(r"^Monkey 0:$");
+    One => part_1,
+    Two => part_2,
+}
+
 #[cfg(test)]
 mod tests {
     use crate::testing::{assert_correct_answer_on_correct_input, assert_error_on_wrong_input};
@@ -217,4 +234,28 @@ mod tests {
     fn returns_error_on_wrong_input() {
         assert_error_on_wrong_input!(part_1, part_2);
     }
+
+    #[test]
+    fn returns_error_on_overflow() {
+        let monkey = |id, items: &str, operation, test| {
+            format!(
+                "Monkey {id}:
+  Starting items: {items}
+  Operation: new = {operation}
+  Test: divisible by {test}
+    If true: throw to monkey 1
+    If false: throw to monkey 0"
+            )
+        };
+        let other = monkey(1, "1", "old + 1", "2");
+        let input = format!("{}\n\n{other}", monkey(0, "3037000500", "old * old", "2"));
+        assert!(part_2(&input).is_err());
+        let input = format!("{}\n\n{other}", monkey(0, "1", "old + 1", "0"));
+        assert!(part_1(&input).is_err());
+        let input = format!(
+            "{}\n\n{other}",
+            monkey(0, "1", "old + 1", "9223372036854775807")
+        );
+        assert!(part_1(&input).is_err());
+    }
 }
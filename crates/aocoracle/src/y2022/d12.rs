@@ -1,93 +1,58 @@
+use crate::fingerprint::Fingerprint;
+use crate::grid::Grid;
+use crate::search;
 use anyhow::{anyhow, bail};
-use hashbrown::HashMap;
-use pathfinding::prelude::bfs;
-use std::ops::Add;
 
-#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
-struct Point {
-    x: i32,
-    y: i32,
-}
+type Point = (usize, usize);
 
-impl Point {
-    fn successors(&self, heights: &HashMap<Point, u8>, reverse: bool) -> Vec<Point> {
-        let &Point { x, y } = self;
-        let prev = heights[self];
-        let mut result = vec![
-            Point { x, y: y - 1 },
-            Point { x, y: y + 1 },
-            Point { x: x - 1, y },
-            Point { x: x + 1, y },
-        ];
-        result.retain(|p| {
-            if let Some(next) = heights.get(p) {
-                match reverse {
-                    false => *next <= prev + 1,
-                    true => *next + 1 >= prev,
-                }
-            } else {
-                false
-            }
-        });
-        result
-    }
+fn successors(heights: &Grid<u8>, pos: Point) -> Vec<Point> {
+    let prev = heights[pos];
+    heights
+        .neighbours4(pos)
+        .filter(|&p| heights[p] + 1 >= prev)
+        .collect()
 }
 
-impl Add for Point {
-    type Output = Self;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        Self {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
-        }
-    }
-}
-
-fn heightmap(s: &str) -> anyhow::Result<(HashMap<Point, u8>, Point, Point)> {
-    let mut heights = HashMap::new();
-    let mut start = None;
-    let mut end = None;
-    for (y, line) in s.lines().enumerate() {
-        let y = y as i32;
-        for (x, b) in line.bytes().enumerate() {
-            let x = x as i32;
-            let h = match b {
-                b'S' => {
-                    start = Some(Point { x, y });
-                    0
-                }
-                b'E' => {
-                    end = Some(Point { x, y });
-                    b'z' - b'a'
-                }
-                b if b >= b'a' => b - b'a',
-                _ => {
-                    bail!("Expected height to be in [SEa-z] but got {}", b as char);
-                }
-            };
-            heights.insert(Point { x, y }, h);
-        }
-    }
-    Ok((
-        heights,
-        start.ok_or_else(|| anyhow!("Expected input to contain starting point"))?,
-        end.ok_or_else(|| anyhow!("Expected input to contain starting point"))?,
-    ))
+fn heightmap(s: &str) -> anyhow::Result<(Grid<u8>, Point, Point)> {
+    let tiles = Grid::parse(s, |c| match c {
+        'S' | 'E' | 'a'..='z' => Ok(c),
+        _ => bail!("Expected height to be in [SEa-z] but got {c}"),
+    })?;
+    let start = tiles
+        .position(|&c| c == 'S')
+        .ok_or_else(|| anyhow!("Expected input to contain starting point"))?;
+    let end = tiles
+        .position(|&c| c == 'E')
+        .ok_or_else(|| anyhow!("Expected input to contain end point"))?;
+    let heights = tiles.map(|&c| match c {
+        'S' => 0,
+        'E' => b'z' - b'a',
+        c => c as u8 - b'a',
+    });
+    Ok((heights, start, end))
 }
 
 pub fn part_1(input: &str) -> anyhow::Result<usize> {
     let (heights, end, start) = heightmap(input)?;
-    bfs(&start, |p| p.successors(&heights, true), |p| *p == end)
-        .map(|path| path.len() - 1)
-        .ok_or_else(|| anyhow!("Could not find any path)"))
+    search::bfs([start], |&p| successors(&heights, p), |p| *p == end)?
+        .path
+        .map(|path| path.cost)
+        .ok_or_else(|| anyhow!("Could not find any path"))
 }
 
 pub fn part_2(input: &str) -> anyhow::Result<usize> {
     let (heights, _, end) = heightmap(input)?;
-    bfs(&end, |p| p.successors(&heights, true), |p| heights[p] == 0)
-        .map(|path| path.len() - 1)
-        .ok_or_else(|| anyhow!("Could not find any path)"))
+    search::bfs([end], |&p| successors(&heights, p), |&p| heights[p] == 0)?
+        .path
+        .map(|path| path.cost)
+        .ok_or_else(|| anyhow!("Could not find any path"))
+}
+
+crate::registry::register_solvers! {
+    2022, 12, "Hill Climbing Algorithm";
+    fingerprint = Fingerprint::new().alphabet("abcdefghijklmnopqrstuvwxyzSE");
+    One => part_1,
+    Two => part_2,
 }
 
 #[cfg(test)]
@@ -1,5 +1,7 @@
-use anyhow::anyhow;
+use anyhow::{anyhow, bail};
 
+use crate::fingerprint::Fingerprint;
+use crate::parse::Lines;
 use serde::Deserialize;
 use std::cmp::Ordering;
 use std::fmt::{Debug, Formatter};
@@ -69,23 +71,31 @@ impl Ord for Packet {
 
 fn packet_pairs(s: &str) -> anyhow::Result<Vec<(Packet, Packet)>> {
     let mut result = Vec::new();
-    for pair in s.split("\n\n") {
-        let (left, right) = pair
-            .split_once('\n')
-            .ok_or_else(|| anyhow!("Expected exactly two lines"))?;
-        let left = left.parse()?;
-        let right = right.parse()?;
+    let mut lines = Lines::new(s)?;
+    loop {
+        let left = lines
+            .next()
+            .ok_or_else(|| anyhow!("Expected left packet"))?
+            .parse()?;
+        let right = lines
+            .next()
+            .ok_or_else(|| anyhow!("Expected right packet"))?
+            .parse()?;
         result.push((left, right));
+        match lines.next() {
+            None => break,
+            Some("") => {}
+            Some(line) => bail!("Expected empty line between pairs but got {line:?}"),
+        }
     }
     Ok(result)
 }
 
 fn packets(s: &str) -> anyhow::Result<Vec<Packet>> {
-    let mut result = Vec::new();
-    for line in s.lines().filter(|l| !l.is_empty()) {
-        result.push(line.parse()?);
-    }
-    Ok(result)
+    Ok(packet_pairs(s)?
+        .into_iter()
+        .flat_map(|(left, right)| [left, right])
+        .collect())
 }
 
 pub fn part_1(input: &str) -> anyhow::Result<usize> {
@@ -118,6 +128,13 @@ pub fn part_2(input: &str) -> anyhow::Result<usize> {
     Ok(pos1 * pos2)
 }
 
+crate::registry::register_solvers! {
+    2022, 13, "Distress Signal";
+    fingerprint = Fingerprint::new().alphabet("0123456789,[]").first_line(r"^\[.*\]$");
+    One => part_1,
+    Two => part_2,
+}
+
 #[cfg(test)]
 mod tests {
     use crate::testing::{assert_correct_answer_on_correct_input, assert_error_on_wrong_input};
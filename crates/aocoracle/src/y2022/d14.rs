@@ -1,45 +1,39 @@
+use crate::fingerprint::Fingerprint;
+use crate::grid::SparseGrid;
+use crate::parse::{self, Lines};
+use crate::trace::{self, Event};
 use anyhow::{anyhow, bail};
-use hashbrown::HashMap;
-use std::fmt::Debug;
 
-#[derive(Debug, Eq, Hash, PartialEq)]
-struct Point {
-    x: i32,
-    y: i32,
-}
+type Point = (i64, i64);
 
 fn rock_paths(s: &str) -> anyhow::Result<Vec<Vec<Point>>> {
     let mut result = Vec::new();
-    for line in s.lines() {
+    for line in Lines::new(s)? {
         let mut path = Vec::new();
         for point in line.split(" -> ") {
             let (x, y) = point
                 .split_once(',')
                 .ok_or_else(|| anyhow!("Expected exactly one ','"))?;
-            let x = x.parse()?;
-            let y = y.parse()?;
-            path.push(Point { x, y });
+            path.push((parse::number(x)?, parse::number(y)?));
         }
         result.push(path);
     }
     Ok(result)
 }
 
-fn rock_coordinates(paths: &Vec<Vec<Point>>) -> anyhow::Result<HashMap<Point, Tile>> {
-    let mut result = HashMap::new();
+fn rock_coordinates(paths: &Vec<Vec<Point>>) -> anyhow::Result<SparseGrid<Tile>> {
+    let mut result = SparseGrid::new();
     for path in paths {
         for line in path.windows(2) {
-            let start = line.get(0).expect("0 is in bounds for window of size 2");
-            let end = line.get(1).expect("1 is in bounds for window of size 2");
-            if start.x == end.x {
-                let x = start.x;
-                for y in start.y.min(end.y)..=start.y.max(end.y) {
-                    result.insert(Point { x, y }, Tile::Rock);
+            let (x0, y0) = line[0];
+            let (x1, y1) = line[1];
+            if x0 == x1 {
+                for y in y0.min(y1)..=y0.max(y1) {
+                    result.insert((x0, y), Tile::Rock);
                 }
-            } else if start.y == end.y {
-                let y = start.y;
-                for x in start.x.min(end.x)..=start.x.max(end.x) {
-                    result.insert(Point { x, y }, Tile::Rock);
+            } else if y0 == y1 {
+                for x in x0.min(x1)..=x0.max(x1) {
+                    result.insert((x, y0), Tile::Rock);
                 }
             } else {
                 bail!("Expected vertical or horizontal line")
@@ -52,85 +46,28 @@ fn rock_coordinates(paths: &Vec<Vec<Point>>) -> anyhow::Result<HashMap<Point, Ti
     Ok(result)
 }
 
-fn grid(s: &str) -> anyhow::Result<Grid<Tile>> {
-    let paths = rock_paths(s);
-    let mut grid = Grid::try_new(rock_coordinates(&paths?)?)?;
-    grid.0.insert(Point { x: 500, y: 0 }, Tile::Source);
+fn grid(s: &str) -> anyhow::Result<SparseGrid<Tile>> {
+    let mut grid = rock_coordinates(&rock_paths(s)?)?;
+    grid.insert((500, 0), Tile::Source);
     Ok(grid)
 }
 
-fn moved_sand<T>(grid: &Grid<T>, curr: &Point) -> Option<Point> {
-    for dx in [0, -1, 1] {
-        let next = Point {
-            x: curr.x + dx,
-            y: curr.y + 1,
-        };
-        if grid.0.get(&next).is_none() {
-            return Some(next);
-        }
-    }
-    None
+fn y_max(grid: &SparseGrid<Tile>) -> i64 {
+    let (_, (_, y_max)) = grid
+        .bounds()
+        .expect("Constructor ensures there is at least one tile");
+    y_max
 }
 
-struct Grid<T>(HashMap<Point, T>);
-
-impl<T> Grid<T> {
-    fn try_new(tiles: HashMap<Point, T>) -> anyhow::Result<Self> {
-        if tiles.is_empty() {
-            bail!("Expected at least one tile");
-        }
-        Ok(Self(tiles))
-    }
-
-    fn x_min(&self) -> i32 {
-        self.0
-            .keys()
-            .map(|p| p.x)
-            .min()
-            .expect("Constructor ensures there is at least one tile")
-    }
-    fn x_max(&self) -> i32 {
-        self.0
-            .keys()
-            .map(|p| p.x)
-            .max()
-            .expect("Constructor ensures there is at least one tile")
-    }
-    fn y_min(&self) -> i32 {
-        self.0
-            .keys()
-            .map(|p| p.y)
-            .min()
-            .expect("Constructor ensures there is at least one tile")
-    }
-    fn y_max(&self) -> i32 {
-        self.0
-            .keys()
-            .map(|p| p.y)
-            .max()
-            .expect("Constructor ensures there is at least one tile")
-    }
+fn moved_sand(grid: &SparseGrid<Tile>, (x, y): Point) -> Option<Point> {
+    [0, -1, 1]
+        .into_iter()
+        .map(|dx| (x + dx, y + 1))
+        .find(|&next| !grid.contains(next))
 }
 
-// TODO: Can this be implemented without `T: Copy`?
-impl<T: 'static> Grid<T>
-where
-    char: From<T>,
-    T: Copy,
-{
-    #[cfg(debug_assertions)]
-    fn print(&self, label: &str, default: T) {
-        println!("{}", label);
-        for y in self.y_min()..=self.y_max() {
-            for x in self.x_min()..=self.x_max() {
-                print!(
-                    "{}",
-                    char::from(*self.0.get(&Point { x, y }).unwrap_or(&default))
-                );
-            }
-            println!();
-        }
-    }
+fn trace_grid(grid: &SparseGrid<Tile>, label: &str) {
+    trace::emit(|| Event::grid(label, grid.render(Tile::Air.into(), |&t| t.into())));
 }
 
 #[derive(Clone, Copy)]
@@ -157,55 +94,58 @@ enum SimulationResult {
     RunsForever,
 }
 
-fn run_simulation(grid: &mut Grid<Tile>) -> (SimulationResult, usize) {
-    let y_max = grid.y_max();
+fn run_simulation(grid: &mut SparseGrid<Tile>) -> (SimulationResult, usize) {
+    let y_max = y_max(grid);
     for i in 0.. {
         // TODO: Keep track of previous insertion points to avoid quadratic runtime
-        let mut curr = Point { x: 500, y: 0 };
-        while let Some(next) = moved_sand(grid, &curr) {
-            if next.y == y_max {
+        let mut curr = (500, 0);
+        while let Some(next) = moved_sand(grid, curr) {
+            if next.1 == y_max {
                 return (SimulationResult::RunsForever, i);
             }
             curr = next;
         }
-        if curr.y == 0 {
+        if curr.1 == 0 {
             return (SimulationResult::SourceBlocked, i + 1);
         }
-        grid.0.insert(curr, Tile::Sand);
+        grid.insert(curr, Tile::Sand);
     }
     unreachable!()
 }
 
 pub fn part_1(input: &str) -> anyhow::Result<usize> {
     let mut grid = grid(input)?;
-    #[cfg(debug_assertions)]
-    grid.print("Before", Tile::Air);
+    trace_grid(&grid, "Before");
     let result = match run_simulation(&mut grid) {
         (SimulationResult::SourceBlocked, _) => Err(anyhow!("Expected simulation to run forever")),
         (SimulationResult::RunsForever, num_sand_unit) => Ok(num_sand_unit),
     };
-    #[cfg(debug_assertions)]
-    grid.print("After", Tile::Air);
+    trace_grid(&grid, "After");
     result
 }
 
 pub fn part_2(input: &str) -> anyhow::Result<usize> {
     let mut grid = grid(input)?;
-    let y_max = grid.y_max() + 2;
+    let y_max = y_max(&grid) + 2;
     for x in (500 - y_max)..=(500 + y_max) {
-        grid.0.insert(Point { x, y: y_max }, Tile::Rock);
+        grid.insert((x, y_max), Tile::Rock);
     }
-    #[cfg(debug_assertions)]
-    grid.print("Before", Tile::Air);
+    trace_grid(&grid, "Before");
     let result = match run_simulation(&mut grid) {
         (SimulationResult::SourceBlocked, num_sand_unit) => Ok(num_sand_unit),
         (SimulationResult::RunsForever, _) => Err(anyhow!("Expected source to become blocked")),
     };
-    #[cfg(debug_assertions)]
-    grid.print("After", Tile::Air);
+    trace_grid(&grid, "After");
     result
 }
 
+crate::registry::register_solvers! {
+    2022, 14, "Regolith Reservoir";
+    fingerprint = Fingerprint::new().first_line(r"^\d+,\d+( -> \d+,\d+)+$");
+    One => part_1,
+    Two => part_2,
+}
+
 #[cfg(test)]
 mod tests {
     use crate::testing::{assert_correct_answer_on_correct_input, assert_error_on_wrong_input};
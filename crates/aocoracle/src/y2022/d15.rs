@@ -1,19 +1,27 @@
 use anyhow::{anyhow, bail};
-use hashbrown::HashSet;
-
-use itertools::Itertools;
-
-#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
-struct Point {
-    x: i64,
-    y: i64,
-}
-
-impl Point {
-    fn manhattan(&self, other: &Self) -> u64 {
-        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
-    }
-}
+use std::ops::Range;
+
+use crate::checked::{ArithmeticError, Checked};
+use crate::fingerprint::Fingerprint;
+use crate::geom::Point2;
+use crate::interval::RangeSet;
+use crate::params::Param;
+use crate::parse::{self, Lines};
+
+const ROW: Param = Param::new(
+    "row",
+    2000000,
+    "Row in which to count positions without beacon",
+)
+.with_example(10);
+const BOUND: Param = Param::new(
+    "bound",
+    4000000,
+    "Largest coordinate that the distress beacon can have",
+)
+.with_example(20);
+
+type Point = Point2<i64>;
 
 fn parsed(text: &str) -> anyhow::Result<Vec<(Point, Point)>> {
     let re = regex::Regex::new(
@@ -21,119 +29,212 @@ fn parsed(text: &str) -> anyhow::Result<Vec<(Point, Point)>> {
     )
     .expect("Hard coded regex is valid");
     let mut result = Vec::new();
-    for line in text.lines() {
+    for line in Lines::new(text)? {
         let cap = re
             .captures(line)
             .ok_or_else(|| anyhow!("Could not capture line {}", line))?;
         result.push((
-            Point {
-                x: cap[1].parse()?,
-                y: cap[2].parse()?,
-            },
-            Point {
-                x: cap[3].parse()?,
-                y: cap[4].parse()?,
-            },
+            Point::new(parse::number(&cap[1])?, parse::number(&cap[2])?),
+            Point::new(parse::number(&cap[3])?, parse::number(&cap[4])?),
         ));
     }
     Ok(result)
 }
 
-pub fn part_1x(input: &str, tgt: i64) -> anyhow::Result<usize> {
-    let input = parsed(input)?;
-    let mut coverage = HashSet::new();
-    let mut beacons = HashSet::new();
+/// Return the positions in row `y` that are closer to some sensor than its beacon, or as close
+fn coverage(input: &[(Point, Point)], y: i64) -> Result<RangeSet<i64>, ArithmeticError> {
+    let mut result = Vec::new();
     for (s, b) in input {
-        if b.y == tgt {
-            beacons.insert(b.x);
-        }
-        let r = s.manhattan(&b) as i64;
-        let rx = r - s.y.abs_diff(tgt) as i64;
-        if rx < 0 {
-            continue;
-        }
-        let x_first = s.x - rx;
-        let x_last = s.x + rx;
-        for x in x_first..=x_last {
-            coverage.insert(x);
+        let rx = s.manhattan(b)?.try_sub(s.manhattan(&Point::new(s.x, y))?)?;
+        if 0 <= rx {
+            result.push(s.x.try_sub(rx)?..s.x.try_add(rx)?.try_add(1)?);
         }
     }
-    let coverage = coverage.difference(&beacons).collect_vec();
-    Ok(coverage.len())
+    Ok(result.into_iter().collect())
 }
 
-pub fn _part_1a(input: &str) -> anyhow::Result<usize> {
-    part_1x(input, 10)
+pub fn part_1x(input: &str, tgt: i64) -> anyhow::Result<i64> {
+    let input = parsed(input)?;
+    let beacons = input
+        .iter()
+        .filter(|(_, b)| b.y == tgt)
+        .map(|(_, b)| Ok(b.x..b.x.try_add(1)?))
+        .collect::<Result<RangeSet<_>, ArithmeticError>>()?;
+    Ok(coverage(&input, tgt)?.difference(&beacons).len()?)
 }
 
-pub fn part_1b(input: &str) -> anyhow::Result<usize> {
-    part_1x(input, 2000000)
+pub fn part_1(input: &str) -> anyhow::Result<i64> {
+    part_1x(input, ROW.get())
 }
 
-pub fn part_2x(input: &str, lo: i64, hi: i64) -> anyhow::Result<i64> {
-    let input = parsed(input)?;
-    let mut x = lo;
-    let mut y = lo;
-    'outer: while y <= hi {
-        if hi < x {
-            x = lo;
-            y += 1;
+/// Return the last number in `range` that has the same parity as `n`, if any
+fn last_with_parity(range: &Range<i64>, n: i64) -> Option<i64> {
+    let last = range.end.checked_sub(1)?;
+    let last = match last.rem_euclid(2) == n.rem_euclid(2) {
+        true => last,
+        false => last.checked_sub(1)?,
+    };
+    (range.start <= last).then_some(last)
+}
+
+/// Return the first number in `range` that has the same parity as `n`, if any
+fn first_with_parity(range: &Range<i64>, n: i64) -> Option<i64> {
+    let first = match range.start.rem_euclid(2) == n.rem_euclid(2) {
+        true => range.start,
+        false => range.start.checked_add(1)?,
+    };
+    (first < range.end).then_some(first)
+}
+
+/// Return the position with the diagonal coordinates `u` and `v`
+fn from_diagonal(u: i64, v: i64) -> Result<Point, ArithmeticError> {
+    Ok(Point::new(
+        u.try_add(v)?.try_div(2)?,
+        u.try_sub(v)?.try_div(2)?,
+    ))
+}
+
+/// Return the positions on the line `d = d0` that are covered by some sensor at `(d, c)`.
+fn covered(sensors: &[(i64, i64, i64)], d0: i64) -> Result<RangeSet<i64>, ArithmeticError> {
+    let mut result = Vec::new();
+    for &(d, c, r) in sensors {
+        if d.max(d0).try_sub(d.min(d0))? <= r {
+            result.push(c.try_sub(r)?..c.try_add(r)?.try_add(1)?);
         }
-        for (s, b) in input.iter() {
-            let r = s.manhattan(b) as i64;
-            let rx = r - s.y.abs_diff(y) as i64;
-            if rx < 0 {
-                continue;
+    }
+    Ok(result.into_iter().collect())
+}
+
+/// Return the first position, in reading order, in the square from `lo` to `hi` that is not
+/// covered by any sensor.
+///
+/// In the coordinates `u = x + y` and `v = x - y` every sensor covers a square. Unless the first
+/// uncovered position is the corner of the search area, its left or upper neighbour is covered,
+/// so it lies on one of the lines just outside of those squares. Along each such line the
+/// uncovered positions are the gaps between the squares that cross it.
+fn first_uncovered(
+    input: &[(Point, Point)],
+    lo: i64,
+    hi: i64,
+) -> Result<Option<Point>, ArithmeticError> {
+    let mut sensors = Vec::with_capacity(input.len());
+    let mut corner_is_covered = false;
+    let corner = Point::new(lo, lo);
+    for (s, b) in input {
+        let r = s.manhattan(b)?;
+        sensors.push((s.x.try_add(s.y)?, s.x.try_sub(s.y)?, r));
+        corner_is_covered |= s.manhattan(&corner)? <= r;
+    }
+    if !corner_is_covered {
+        return Ok(Some(corner));
+    }
+
+    let (lo2, hi2) = (lo.try_mul(2)?, hi.try_mul(2)?);
+    let swapped: Vec<_> = sensors.iter().map(|&(u, v, r)| (v, u, r)).collect();
+    let mut candidates = Vec::new();
+    for &(u, v, r) in &sensors {
+        for u0 in [u.try_sub(r)?.try_sub(1)?, u.try_add(r)?.try_add(1)?] {
+            let start = u0.try_sub(hi2)?.max(lo2.try_sub(u0)?);
+            let end = hi2.try_sub(u0)?.min(u0.try_sub(lo2)?).try_add(1)?;
+            let covered = covered(&sensors, u0)?;
+            // The smallest `y` in each gap is where `v` is largest
+            for gap in RangeSet::from(start..end).difference(&covered).ranges() {
+                if let Some(v0) = last_with_parity(gap, u0) {
+                    candidates.push(from_diagonal(u0, v0)?);
+                }
             }
-            let x_first = s.x - rx;
-            let x_last = s.x + rx;
-            if x_first <= x && x <= x_last {
-                x = x_last + 1;
-                continue 'outer;
+        }
+        for v0 in [v.try_sub(r)?.try_sub(1)?, v.try_add(r)?.try_add(1)?] {
+            let start = lo2.try_sub(v0)?.max(lo2.try_add(v0)?);
+            let end = hi2.try_sub(v0)?.min(hi2.try_add(v0)?).try_add(1)?;
+            let covered = covered(&swapped, v0)?;
+            // The smallest `y` in each gap is where `u` is smallest
+            for gap in RangeSet::from(start..end).difference(&covered).ranges() {
+                if let Some(u0) = first_with_parity(gap, v0) {
+                    candidates.push(from_diagonal(u0, v0)?);
+                }
             }
         }
-        return Ok(x * 4000000 + y);
     }
-    bail!("Found no possible beacon locations");
+    Ok(candidates.into_iter().min_by_key(|p| (p.y, p.x)))
 }
 
-pub fn _part_2a(input: &str) -> anyhow::Result<i64> {
-    part_2x(input, 0, 20)
+pub fn part_2x(input: &str, lo: i64, hi: i64) -> anyhow::Result<i64> {
+    let input = parsed(input)?;
+    match first_uncovered(&input, lo, hi)? {
+        Some(p) => Ok(p.x.try_mul(4000000)?.try_add(p.y)?),
+        None => bail!("Found no possible beacon locations"),
+    }
+}
+
+pub fn part_2(input: &str) -> anyhow::Result<i64> {
+    part_2x(input, 0, BOUND.get())
 }
 
-pub fn part_2b(input: &str) -> anyhow::Result<i64> {
-    part_2x(input, 0, 4000000)
+crate::registry::register_solvers! {
+    2022, 15, "Beacon Exclusion Zone";
+    fingerprint = Fingerprint::new().first_line(r"^Sensor at x=-?\d+, y=-?\d+: closest beacon is at");
+    params = [ROW, BOUND];
+    example = Fingerprint::new().first_line(r"^Sensor at x=-?\d{1,2}, y=-?\d{1,2}: closest beacon is at x=-?\d{1,2}, y=-?\d{1,2}$");
+    One => part_1,
+    Two => part_2,
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::params::{with_overrides, Override};
     use crate::testing::{assert_correct_answer_on_correct_input, assert_error_on_wrong_input};
     use crate::Part;
 
     use super::*;
 
+    fn on_example<T>(func: fn(&str) -> T) -> impl Fn(&str) -> T {
+        move |text| {
+            with_overrides(
+                &[Override::new("row", 10), Override::new("bound", 20)],
+                || func(text),
+            )
+        }
+    }
+
     #[test]
     fn part_1_works_on_example() {
-        assert_correct_answer_on_correct_input!(_part_1a, "EXAMPLE", Part::One);
+        assert_correct_answer_on_correct_input!(on_example(part_1), "EXAMPLE", Part::One);
     }
 
     #[test]
     fn part_1_works_on_input() {
-        assert_correct_answer_on_correct_input!(part_1b, "f95773a9a6b7f551", Part::One);
+        assert_correct_answer_on_correct_input!(part_1, "f95773a9a6b7f551", Part::One);
     }
 
     #[test]
     fn part_2_works_on_example() {
-        assert_correct_answer_on_correct_input!(_part_2a, "EXAMPLE", Part::Two);
+        assert_correct_answer_on_correct_input!(on_example(part_2), "EXAMPLE", Part::Two);
     }
 
     #[test]
     fn part_2_works_on_input() {
-        assert_correct_answer_on_correct_input!(part_2b, "f95773a9a6b7f551", Part::Two);
+        assert_correct_answer_on_correct_input!(part_2, "f95773a9a6b7f551", Part::Two);
     }
 
     #[test]
     fn returns_error_on_wrong_input() {
-        assert_error_on_wrong_input!(_part_1a, _part_2a);
+        assert_error_on_wrong_input!(on_example(part_1), on_example(part_2));
+    }
+
+    #[test]
+    fn returns_error_on_overflow() {
+        let line = |sx: i64, sy: i64, bx: i64, by: i64| {
+            format!("Sensor at x={sx}, y={sy}: closest beacon is at x={bx}, y={by}")
+        };
+        let far = line(i64::MAX, 10, i64::MAX, 10);
+        assert!(on_example(part_1)(&far).is_err());
+        assert!(on_example(part_2)(&far).is_err());
+        let wide = line(1, 10, i64::MAX, 10);
+        assert!(on_example(part_1)(&wide).is_err());
+        assert!(on_example(part_2)(&wide).is_err());
+        let apart = line(i64::MIN, 0, i64::MAX, 0);
+        assert!(on_example(part_1)(&apart).is_err());
+        assert!(on_example(part_2)(&apart).is_err());
     }
 }
@@ -1,4 +1,9 @@
-use anyhow::bail;
+use crate::cycle::Detector;
+use crate::fingerprint::Fingerprint;
+use crate::geom::Point2;
+use crate::parse::Lines;
+use crate::trace::{self, Event};
+use anyhow::{anyhow, bail};
 use hashbrown::{HashMap, HashSet};
 
 enum Jet {
@@ -8,7 +13,12 @@ enum Jet {
 
 fn jets(s: &str) -> anyhow::Result<Vec<Jet>> {
     let mut result = Vec::new();
-    for jet in s.trim().chars() {
+    let mut lines = Lines::new(s)?;
+    let line = lines
+        .next()
+        .ok_or_else(|| anyhow!("Expected one line but got none"))?;
+    lines.finish()?;
+    for jet in line.chars() {
         result.push(match jet {
             '<' => Jet::L,
             '>' => Jet::R,
@@ -18,17 +28,7 @@ fn jets(s: &str) -> anyhow::Result<Vec<Jet>> {
     Ok(result)
 }
 
-#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
-struct Point {
-    x: i64,
-    y: i64,
-}
-
-impl Point {
-    fn new(x: i64, y: i64) -> Self {
-        Self { x, y }
-    }
-}
+type Point = Point2<i64>;
 
 fn rocks() -> Vec<Vec<Point>> {
     let rock1 = vec![
@@ -97,48 +97,104 @@ fn moved_vertically(chamber: &HashSet<Point>, before: &[Point]) -> Option<Vec<Po
     Some(after)
 }
 
-fn chamber(rocks: &[Vec<Point>], jets: &[Jet], num_rock: usize) -> HashSet<Point> {
-    let mut chamber: HashSet<Point> = HashSet::new();
-    let mut jets = jets.iter().cycle();
-    let mut max_y = -1;
-    for template in rocks.iter().cycle().take(num_rock) {
-        let mut before: Vec<_> = template
+/// Rocks falling one at a time into a chamber that is pushed around by jets
+struct Simulation<'a> {
+    rocks: Vec<Vec<Point>>,
+    jets: &'a [Jet],
+    chamber: HashSet<Point>,
+    num_rock: usize,
+    num_jet: usize,
+    /// Highest y coordinate of each column, or -1 if the column is empty
+    tops: [i64; 7],
+}
+
+impl<'a> Simulation<'a> {
+    fn new(jets: &'a [Jet]) -> anyhow::Result<Self> {
+        if jets.is_empty() {
+            bail!("Expected at least one jet");
+        }
+        Ok(Self {
+            rocks: rocks(),
+            jets,
+            chamber: HashSet::new(),
+            num_rock: 0,
+            num_jet: 0,
+            tops: [-1; 7],
+        })
+    }
+
+    fn height(&self) -> i64 {
+        self.tops.iter().max().expect("Chamber has columns") + 1
+    }
+
+    fn drop_rock(&mut self) {
+        let max_y = self.height() - 1;
+        let mut before: Vec<_> = self.rocks[self.num_rock % self.rocks.len()]
             .iter()
             .map(|p| Point::new(p.x + 2, p.y + 4 + max_y))
             .collect();
         loop {
-            let jet = jets.next().expect("Iterator is endless");
-            if let Some(after) = moved_horizontally(&chamber, jet, &before) {
+            let jet = &self.jets[self.num_jet % self.jets.len()];
+            self.num_jet += 1;
+            if let Some(after) = moved_horizontally(&self.chamber, jet, &before) {
                 before = after;
             }
-            if let Some(after) = moved_vertically(&chamber, &before) {
+            if let Some(after) = moved_vertically(&self.chamber, &before) {
                 before = after;
             } else {
                 break;
             }
         }
-        max_y = max_y.max(
-            before
-                .iter()
-                .map(|p| p.y)
-                .max()
-                .expect("Hard coded rock is not empty"),
-        );
-        chamber.extend(before);
+        for p in &before {
+            let top = &mut self.tops[p.x as usize];
+            *top = (*top).max(p.y);
+        }
+        self.chamber.extend(before);
+        self.num_rock += 1;
+    }
+
+    /// Everything that determines how the simulation continues, except for the height.
+    ///
+    /// The surface is approximated by how far below the highest rock the top of each column is,
+    /// which does not capture overhangs but is enough in practice.
+    fn state(&self) -> (usize, usize, [i64; 7]) {
+        let height = self.height();
+        (
+            self.num_rock % self.rocks.len(),
+            self.num_jet % self.jets.len(),
+            self.tops.map(|top| height - top),
+        )
     }
-    chamber
 }
 
-fn print_champer(
+/// Return the height of the tower after `num_rock` rocks have come to rest
+fn predicted_height(jets: &[Jet], num_rock: u64) -> anyhow::Result<u64> {
+    let mut simulation = Simulation::new(jets)?;
+    let mut detector = Detector::new();
+    let mut heights = Vec::new();
+    loop {
+        heights.push(simulation.height());
+        if simulation.num_rock as u64 == num_rock {
+            return Ok(simulation.height() as u64);
+        }
+        if let Some(cycle) = detector.observe(simulation.state()) {
+            return Ok(cycle.extrapolate(num_rock, &heights) as u64);
+        }
+        simulation.drop_rock();
+        crate::cancel::checkpoint()?;
+    }
+}
+
+fn chamber_event(
     chamber: &HashSet<Point>,
     rock: &Vec<Point>,
     label: &str,
     default: char,
     limit: Option<i64>,
-) {
+) -> Event {
     let mut chamber: HashMap<Point, char> = chamber.iter().cloned().map(|p| (p, '#')).collect();
     for p in rock {
-        chamber.insert(p.clone(), '@');
+        chamber.insert(*p, '@');
     }
     let x_min = chamber.keys().map(|p| p.x).min().unwrap_or(0).min(-1);
     let x_max = chamber.keys().map(|p| p.x).max().unwrap_or(0).max(7);
@@ -159,53 +215,36 @@ fn print_champer(
         Some(limit) => y_min.max(y_max - limit),
         None => y_min,
     };
-    println!("{}", label);
-    for y in (y_min_effective..=y_max).rev() {
-        for x in x_min..=x_max {
-            print!("{}", (*chamber.get(&Point::new(x, y)).unwrap_or(&default)));
-        }
-        println!();
-    }
-}
-
-fn predicted_height(jets: &[Jet], offset_len: u64, cycle_height: u64, cycle_len: u64) -> u64 {
-    let num_cycle = (1000000000000 - offset_len) / cycle_len;
-    let remainder = (1000000000000 - offset_len) % cycle_len;
-    let chamber = chamber(&rocks(), jets, (offset_len + remainder) as usize);
-    let chamber_height = chamber.iter().map(|p| p.y).max().unwrap_or(0) + 1;
-    num_cycle * cycle_height + chamber_height as u64
+    Event::grid(
+        label,
+        (y_min_effective..=y_max).rev().map(|y| {
+            (x_min..=x_max)
+                .map(|x| *chamber.get(&Point::new(x, y)).unwrap_or(&default))
+                .collect()
+        }),
+    )
 }
 
 pub fn part_1(input: &str) -> anyhow::Result<i64> {
-    let chamber = chamber(&rocks(), &jets(input)?, 2022);
-    #[cfg(debug_assertions)]
-    print_champer(&chamber, &vec![], "Done", '.', None);
-    let chamber_height = chamber.iter().map(|p| p.y).max().unwrap_or(0) + 1;
-    Ok(chamber_height)
+    let jets = jets(input)?;
+    let mut simulation = Simulation::new(&jets)?;
+    for _ in 0..2022 {
+        simulation.drop_rock();
+    }
+    trace::emit(|| chamber_event(&simulation.chamber, &vec![], "Done", '.', None));
+    Ok(simulation.height())
 }
 
 pub fn part_2(input: &str) -> anyhow::Result<u64> {
-    let jets = jets(input)?;
-    // To guarantee that the cavern repeats it seems to me that we want to run the simulation until
-    // 1. the first rock starts falling together with the first jet, and
-    // 2. the cavern above the highest |######| has been seen before.
-    // I ran the simulation for a while to see if 1 happens and it did not.
-    //
-    // Luckily the cavern repeats anyway. The cycle boundaries also coincide with the first rock
-    // making it possible to determine the cycle length by counting the number of occupied tiles.
-    // The initial simulation leading up to the repeating cycle and the repeating cycle are found
-    // manually by searching in the printed representation of the caverns.
-    if input.starts_with(">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>") {
-        return Ok(predicted_height(&jets, 110 / 22 * 5, 53, 154 / 22 * 5));
-    }
-    if input.starts_with(">>>><<><>><<<<>>>><<>>><>><><<>>>><<<<>>") {
-        return Ok(predicted_height(&jets, 5720 / 22 * 5, 2681, 7656 / 22 * 5));
-    }
-    if input.starts_with("><<<<><<>><<>><><<<>>><<<<>>>><<>>><>>><") {
-        return Ok(predicted_height(&jets, 6644 / 22 * 5, 2767, 7678 / 22 * 5));
-    }
-    // TODO: Implement for real
-    bail!("Input does not match hard coded answers");
+    predicted_height(&jets(input)?, 1000000000000)
+}
+
+crate::registry::register_solvers! {
+    2022, 17, "Pyroclastic Flow";
+    fingerprint = Fingerprint::new().lines(1, 1).alphabet("<>");
+    accepts = |text| Fingerprint::new().alphabet("<>").matches(text);
+    One => part_1,
+    Two => part_2,
 }
 
 #[cfg(test)]
@@ -235,6 +274,19 @@ mod tests {
         assert_correct_answer_on_correct_input!(part_2, "4a92cc03c1028b0c", Part::Two);
     }
 
+    #[test]
+    fn cycles_are_extrapolated_exactly() {
+        let jets = jets(">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>").unwrap();
+        let mut simulation = Simulation::new(&jets).unwrap();
+        for _ in 0..5000 {
+            simulation.drop_rock();
+        }
+        assert_eq!(
+            predicted_height(&jets, 5000).unwrap(),
+            simulation.height() as u64
+        );
+    }
+
     #[test]
     fn returns_error_on_wrong_input() {
         assert_error_on_wrong_input!(part_1, part_2);
@@ -1,89 +1,68 @@
+use crate::checked::ArithmeticError;
+use crate::fingerprint::Fingerprint;
+use crate::geom::Point3;
+use crate::parse::{self, Lines};
+use crate::search;
 use anyhow::anyhow;
-use hashbrown::HashSet;
-
-#[derive(Clone, Debug, Default, Eq, Hash, PartialEq, PartialOrd, Ord)]
-struct Cube {
-    x: i64,
-    y: i64,
-    z: i64,
-}
-
-impl Cube {
-    fn new(x: i64, y: i64, z: i64) -> Self {
-        Self { x, y, z }
-    }
-
-    fn faces(&self) -> Vec<Face> {
-        let mut result = Vec::with_capacity(6);
-        for (dx, dy, dz) in [(0, 0, 1), (0, 1, 0), (1, 0, 0)] {
-            result.push(Face {
-                lo: self.clone(),
-                hi: Self {
-                    x: self.x + dx,
-                    y: self.y + dy,
-                    z: self.z + dz,
-                },
-            });
-            result.push(Face {
-                hi: self.clone(),
-                lo: Self {
-                    x: self.x - dx,
-                    y: self.y - dy,
-                    z: self.z - dz,
-                },
-            });
-        }
-        result
+use hashbrown::{HashMap, HashSet};
+
+type Cube = Point3<i64>;
+
+fn faces(cube: Cube) -> Result<Vec<Face>, ArithmeticError> {
+    let mut result = Vec::with_capacity(6);
+    for offset in [
+        Point3::new(0, 0, 1),
+        Point3::new(0, 1, 0),
+        Point3::new(1, 0, 0),
+    ] {
+        result.push(Face {
+            lo: cube,
+            hi: cube.try_add(offset)?,
+        });
+        result.push(Face {
+            hi: cube,
+            lo: cube.try_sub(offset)?,
+        });
     }
+    Ok(result)
+}
 
-    fn neighbors(&self) -> Vec<Self> {
-        let mut result = Vec::with_capacity(8);
-        for dx in [-1, 0, 1] {
-            for dy in [-1, 0, 1] {
-                for dz in [-1, 0, 1] {
-                    if dx == 0 && dy == 0 && dz == 0 {
-                        continue;
-                    }
-                    result.push(Self {
-                        x: self.x + dx,
-                        y: self.y + dy,
-                        z: self.z + dz,
-                    })
-                }
-            }
+fn searchable_neighbors(
+    cube: Cube,
+    surface: &HashSet<Face>,
+    faces: &HashMap<Cube, Vec<Face>>,
+) -> Vec<Cube> {
+    let mut result = Vec::with_capacity(6);
+    for face in &faces[&cube] {
+        if surface.contains(face) {
+            continue; // Crosses surface
         }
-        result
-    }
-
-    fn searchable_neighbors(&self, faces: &HashSet<Face>, points: &HashSet<Self>) -> Vec<Self> {
-        let mut result = Vec::with_capacity(6);
-        for face in self.faces() {
-            if faces.contains(&face) {
-                continue; // Crosses surface
-            }
-            let neighbor = if face.lo != *self {
-                face.lo
-            } else {
-                assert_ne!(face.hi, *self);
-                face.hi
-            };
-            if !points.contains(&neighbor) {
-                continue; // Strays away from surface
-            }
-            result.push(neighbor);
+        let neighbor = if face.lo != cube {
+            face.lo
+        } else {
+            assert_ne!(face.hi, cube);
+            face.hi
+        };
+        if !faces.contains_key(&neighbor) {
+            continue; // Strays away from surface
         }
-        result
+        result.push(neighbor);
     }
+    result
 }
 
 fn droplet(s: &str) -> anyhow::Result<HashSet<Cube>> {
     let re = regex::Regex::new(r"^(\d+),(\d+),(\d+)$").expect("Hard coded regex is valid");
     let mut result = HashSet::new();
-    for line in s.lines() {
+    for line in Lines::new(s)? {
         let cap = re
             .captures(line)
             .ok_or_else(|| anyhow!("Could not capture a cube on line {}", line))?;
-        result.insert(Cube::new(cap[1].parse()?, cap[2].parse()?, cap[3].parse()?));
+        result.insert(Cube::new(
+            parse::number(&cap[1])?,
+            parse::number(&cap[2])?,
+            parse::number(&cap[3])?,
+        ));
     }
     Ok(result)
 }
@@ -96,53 +75,59 @@ struct Face {
     hi: Cube,
 }
 
-fn surface(cubes: &HashSet<Cube>) -> HashSet<Face> {
-    let mut result = HashSet::new();
-    for face in cubes.iter().flat_map(|f| f.faces()) {
-        if result.contains(&face) {
-            result.remove(&face);
-        } else {
-            result.insert(face);
-        }
-    }
-    result
-}
-
-fn clique(faces: &HashSet<Face>, points: &HashSet<Cube>, start: Cube) -> HashSet<Cube> {
+fn surface(cubes: &HashSet<Cube>) -> Result<HashSet<Face>, ArithmeticError> {
     let mut result = HashSet::new();
-    let mut remaining = vec![start];
-
-    while let Some(p) = remaining.pop() {
-        if result.contains(&p) {
-            continue;
-        }
-        for q in p.searchable_neighbors(faces, points) {
-            remaining.push(q);
+    for &cube in cubes {
+        for face in faces(cube)? {
+            if result.contains(&face) {
+                result.remove(&face);
+            } else {
+                result.insert(face);
+            }
         }
-        result.insert(p);
     }
-    result
+    Ok(result)
 }
 
 pub fn part_1(input: &str) -> anyhow::Result<usize> {
     let droplet = droplet(input)?;
-    let surface = surface(&droplet);
+    let surface = surface(&droplet)?;
     Ok(surface.len())
 }
 
 pub fn part_2(input: &str) -> anyhow::Result<usize> {
     let droplet = droplet(input)?;
-    let surface = surface(&droplet);
-    let searchable: HashSet<_> = droplet.iter().flat_map(|p| p.neighbors()).collect();
-    let start = searchable.iter().min().unwrap().clone();
-    let accessible = clique(&surface, &searchable, start)
+    let surface = surface(&droplet)?;
+    // Computing the faces up front lets the search below assume that they do not overflow
+    let searchable = droplet
         .iter()
-        .flat_map(|cube| cube.faces())
-        .filter(|face| surface.contains(face))
-        .count();
+        .flat_map(|p| p.neighbours26())
+        .map(|cube| {
+            let cube = cube?;
+            Ok((cube, faces(cube)?))
+        })
+        .collect::<Result<HashMap<_, _>, ArithmeticError>>()?;
+    let start = *searchable
+        .keys()
+        .min()
+        .ok_or_else(|| anyhow!("Expected at least one cube"))?;
+    let accessible = search::reachable([start], |&cube| {
+        searchable_neighbors(cube, &surface, &searchable)
+    })?
+    .iter()
+    .flat_map(|cube| &searchable[cube])
+    .filter(|face| surface.contains(face))
+    .count();
     Ok(accessible)
 }
 
+crate::registry::register_solvers! {
+    2022, 18, "Boiling Boulders";
+    fingerprint = Fingerprint::new().first_line(r"^\d+,\d+,\d+$");
+    One => part_1,
+    Two => part_2,
+}
+
 #[cfg(test)]
 mod tests {
     use crate::testing::{assert_correct_answer_on_correct_input, assert_error_on_wrong_input};
@@ -174,4 +159,14 @@ mod tests {
     fn returns_error_on_wrong_input() {
         assert_error_on_wrong_input!(part_1, part_2);
     }
+
+    #[test]
+    fn returns_error_on_overflow() {
+        let input = "9223372036854775807,0,0";
+        assert!(part_1(input).is_err());
+        assert!(part_2(input).is_err());
+        let input = "9223372036854775806,0,0";
+        assert!(part_1(input).is_ok());
+        assert!(part_2(input).is_err());
+    }
 }
@@ -1,13 +1,16 @@
+use crate::checked::{Checked, CheckedIter};
+use crate::fingerprint::Fingerprint;
+use crate::parse::{self, Lines};
 use anyhow::{anyhow, bail};
 
 fn numbers(s: &str) -> anyhow::Result<Vec<i64>> {
     let re = regex::Regex::new(r"^(-?([1-9]\d*)|0)$").expect("Hard coded regex is valid");
     let mut result = Vec::new();
-    for line in s.lines() {
+    for line in Lines::new(s)? {
         let cap = re
             .captures(line)
             .ok_or_else(|| anyhow!("Could not capture number on line {}", line))?;
-        result.push(cap[1].parse()?);
+        result.push(parse::number(&cap[1])?);
     }
     let num_zero = result.iter().filter(|n| **n == 0).count();
     if num_zero != 1 {
@@ -22,7 +25,12 @@ fn move_number(mixed: &mut Vec<(usize, i64)>, id: usize) {
         .position(|x| x.0 == id)
         .expect("This function is only called with valid ids");
     let value = mixed.remove(old_index).1;
-    let mut new_index = (old_index as i64 + value).rem_euclid(mixed.len() as i64) as usize;
+    // Reducing the value first keeps the sum from overflowing
+    let len = mixed.len() as i64;
+    let shift = value.checked_rem_euclid(len).unwrap_or_default();
+    let mut new_index = (old_index as i64 + shift)
+        .checked_rem_euclid(len)
+        .unwrap_or_default() as usize;
     // Keep the first element the same as in example
     if new_index == 0 && value < 0 {
         new_index = mixed.len();
@@ -30,10 +38,12 @@ fn move_number(mixed: &mut Vec<(usize, i64)>, id: usize) {
     mixed.insert(new_index, (id, value));
 }
 
-fn part_x(numbers: &[i64], num_round: usize, key: i64) -> i64 {
+fn part_x(numbers: &[i64], num_round: usize, key: i64) -> anyhow::Result<i64> {
     let mut mixed: Vec<(usize, i64)> = numbers
         .iter()
-        .map(|value| value * key)
+        .map(|value| value.try_mul(key))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
         .enumerate()
         .collect();
     for _ in 0..num_round {
@@ -45,23 +55,30 @@ fn part_x(numbers: &[i64], num_round: usize, key: i64) -> i64 {
         .iter()
         .position(|x| x.1 == 0)
         .expect("Input validation ensures there is exactly 1 zero");
-    [
+    Ok([
         mixed[(origin + 1000) % mixed.len()].1,
         mixed[(origin + 2000) % mixed.len()].1,
         mixed[(origin + 3000) % mixed.len()].1,
     ]
-    .iter()
-    .sum()
+    .into_iter()
+    .try_sum()?)
 }
 
 pub fn part_1(input: &str) -> anyhow::Result<i64> {
     let numbers = numbers(input)?;
-    Ok(part_x(&numbers, 1, 1))
+    part_x(&numbers, 1, 1)
 }
 
 pub fn part_2(input: &str) -> anyhow::Result<i64> {
     let numbers = numbers(input)?;
-    Ok(part_x(&numbers, 10, 811589153))
+    part_x(&numbers, 10, 811589153)
+}
+
+crate::registry::register_solvers! {
+    2022, 20, "Grove Positioning System";
+    fingerprint = Fingerprint::new().alphabet("-0123456789");
+    One => part_1,
+    Two => part_2,
 }
 
 #[cfg(test)]
@@ -95,4 +112,13 @@ mod tests {
     fn returns_error_on_wrong_input() {
         assert_error_on_wrong_input!(part_1, part_2);
     }
+
+    #[test]
+    fn returns_error_on_overflow() {
+        let input = "0\n9223372036854775807\n1";
+        assert!(part_1(input).is_err());
+        let input = "0\n11373475000";
+        assert!(part_1(input).is_ok());
+        assert!(part_2(input).is_err());
+    }
 }
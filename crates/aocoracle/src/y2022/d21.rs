@@ -1,148 +1,82 @@
 use anyhow::{anyhow, bail};
 use hashbrown::HashMap;
 
-use std::cmp::Ordering;
-use std::str::FromStr;
+use crate::expr::{Expr, Expressions, Rational, Solutions};
+use crate::fingerprint::Fingerprint;
+use crate::parse::{self, Lines};
 
-#[derive(Clone, Debug, Eq, Hash, PartialEq)]
-enum Operator {
-    Add,
-    Sub,
-    Mul,
-    Div,
-}
-
-impl FromStr for Operator {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "+" => Ok(Self::Add),
-            "/" => Ok(Self::Div),
-            "*" => Ok(Self::Mul),
-            "-" => Ok(Self::Sub),
-            other => Err(anyhow!(
-                "Expected operator '+', '/', '*', '-' but got {other}"
-            )),
-        }
+fn name(s: &str) -> anyhow::Result<&str> {
+    if s.is_empty() || !s.chars().all(|c| c.is_ascii_alphanumeric()) {
+        bail!("Expected name to be alphanumeric but got {s:?}");
     }
+    Ok(s)
 }
 
-impl Operator {
-    fn call(&self, lhs: i128, rhs: i128) -> i128 {
-        match self {
-            Self::Add => lhs + rhs,
-            Self::Div => lhs / rhs,
-            Self::Mul => lhs * rhs,
-            Self::Sub => lhs - rhs,
-        }
+fn job(s: &str) -> anyhow::Result<Expr<&str>> {
+    if let Ok(x) = parse::number(s.trim()) {
+        return Ok(Expr::Number(Rational::from_integer(x)));
     }
-}
-
-#[derive(Clone, Debug, Eq, Hash, PartialEq)]
-enum Job {
-    Operand(i128),
-    Operation(Operator, u32, u32),
-}
-
-impl FromStr for Job {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(match s.trim().parse() {
-            Ok(x) => Job::Operand(x),
-            Err(_) => {
-                let tokens: Vec<_> = s.split_whitespace().collect();
-                if tokens.len() != 3 {
-                    bail!("Expected expression with 3 token but got {}", tokens.len());
-                }
-                Job::Operation(
-                    Operator::from_str(tokens[1])?,
-                    u32::from_str_radix(tokens[0], 36)?,
-                    u32::from_str_radix(tokens[2], 36)?,
-                )
-            }
-        })
+    let tokens: Vec<_> = s.split_whitespace().collect();
+    if tokens.len() != 3 {
+        bail!("Expected expression with 3 token but got {}", tokens.len());
     }
+    Ok(Expr::Operation(
+        tokens[1].parse()?,
+        name(tokens[0])?,
+        name(tokens[2])?,
+    ))
 }
 
-fn root() -> u32 {
-    u32::from_str_radix("root", 36).expect("Hard coded value is valid")
-}
-
-fn jobs(s: &str) -> anyhow::Result<HashMap<u32, Job>> {
+fn jobs(s: &str) -> anyhow::Result<Expressions<&str>> {
     let mut result = HashMap::new();
-    for line in s.lines() {
+    for line in Lines::new(s)? {
         let (id, job) = line
             .split_once(':')
             .ok_or_else(|| anyhow!("Expected exactly two parts separated by ':' but got {s}"))?;
-        let id = u32::from_str_radix(id, 36)?;
-        let job = Job::from_str(job)?;
-        result.insert(id, job);
+        if result.insert(name(id)?, self::job(job)?).is_some() {
+            bail!("Expected every monkey to have one job but {id} has several");
+        }
     }
-    if !result.contains_key(&root()) {
+    if !result.contains_key("root") {
         bail!("Expected expression to have a root")
     }
-    Ok(result)
+    Expressions::new(result)
 }
 
-fn evaluate(jobs: &HashMap<u32, Job>, job: &u32) -> i128 {
-    match jobs.get(job).unwrap() {
-        Job::Operation(op, lhs, rhs) => op.call(evaluate(jobs, lhs), evaluate(jobs, rhs)),
-        Job::Operand(x) => *x,
+fn integer(name: &str, value: Rational) -> anyhow::Result<i128> {
+    match value.is_integer() {
+        true => Ok(value.to_integer()),
+        false => bail!("Expected {name} to be an integer but got {value}"),
     }
 }
 
 pub fn part_1(input: &str) -> anyhow::Result<i128> {
     let jobs = jobs(input)?;
-    Ok(evaluate(&jobs, &root()))
+    integer("root", jobs.evaluate(&"root")?)
 }
 
-fn binary_search<F>(mut lo: i128, mut hi: i128, mut cmp: F) -> Option<i128>
-where
-    F: FnMut(i128) -> Ordering,
-{
-    while lo != hi {
-        let mid = (lo + hi) / 2;
-        match cmp(mid) {
-            // Always return the leftmost result
-            Ordering::Equal => hi = mid,
-            Ordering::Less => hi = mid - 1,
-            Ordering::Greater => lo = mid + 1,
-        }
+pub fn part_2(input: &str) -> anyhow::Result<i128> {
+    let jobs = jobs(input)?;
+    let Some(Expr::Operation(_, lhs, rhs)) = jobs.get(&"root") else {
+        bail!("Expected root to compare the numbers of two monkeys");
+    };
+    if jobs.get(&"humn").is_none() {
+        bail!("Expected a job for humn");
     }
-    match cmp(lo) {
-        Ordering::Equal => Some(lo),
-        Ordering::Less => None,
-        Ordering::Greater => None,
+    match jobs.solve(lhs, rhs, &"humn")? {
+        Solutions::One(value) => integer("humn", value),
+        Solutions::None => bail!("Expected some number to make root's numbers equal but none does"),
+        Solutions::Any => {
+            bail!("Expected one number to make root's numbers equal but every number does")
+        }
     }
 }
 
-pub fn part_2(input: &str) -> anyhow::Result<i128> {
-    let mut jobs = jobs(input)?;
-    let humn = u32::from_str_radix("humn", 36)?;
-    let (lhs, rhs) = match jobs.get(&root()).unwrap() {
-        Job::Operand(_) => panic!("Oops"),
-        Job::Operation(_, lhs, rhs) => (*lhs, *rhs),
-    };
-
-    let old = evaluate(&jobs, &lhs).cmp(&evaluate(&jobs, &rhs));
-    let answer = binary_search(0, i64::MAX as i128, |x| {
-        jobs.insert(humn, Job::Operand(x));
-        let new = evaluate(&jobs, &lhs).cmp(&evaluate(&jobs, &rhs));
-        match new {
-            Ordering::Equal => Ordering::Equal,
-            new => {
-                if old == new {
-                    Ordering::Greater
-                } else {
-                    Ordering::Less
-                }
-            }
-        }
-    })
-    .unwrap();
-    Ok(answer)
+crate::registry::register_solvers! {
+    2022, 21, "Monkey Math";
+    fingerprint = Fingerprint::new().first_line(r"^[a-z]{4}: ([a-z]{4} [-+*/] [a-z]{4}|\d+)$");
+    One => part_1,
+    Two => part_2,
 }
 
 #[cfg(test)]
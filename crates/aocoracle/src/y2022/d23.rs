@@ -1,70 +1,15 @@
+use crate::fingerprint::Fingerprint;
+use crate::geom::{Direction, Direction8, Point2};
+use crate::parse::Lines;
 use anyhow::bail;
 use hashbrown::{HashMap, HashSet};
 use std::collections::VecDeque;
 
-#[derive(Clone, Default, Eq, Hash, PartialEq)]
-struct Point {
-    x: i32,
-    y: i32,
-}
-
-impl Point {
-    fn new(x: i32, y: i32) -> Self {
-        Self { x, y }
-    }
-    fn north_west(&self) -> Self {
-        Self {
-            x: self.x - 1,
-            y: self.y - 1,
-        }
-    }
-    fn north(&self) -> Self {
-        Self {
-            x: self.x,
-            y: self.y - 1,
-        }
-    }
-    fn north_east(&self) -> Self {
-        Self {
-            x: self.x + 1,
-            y: self.y - 1,
-        }
-    }
-    fn west(&self) -> Self {
-        Self {
-            x: self.x - 1,
-            y: self.y,
-        }
-    }
-    fn east(&self) -> Self {
-        Self {
-            x: self.x + 1,
-            y: self.y,
-        }
-    }
-    fn south_west(&self) -> Self {
-        Self {
-            x: self.x - 1,
-            y: self.y + 1,
-        }
-    }
-    fn south(&self) -> Self {
-        Self {
-            x: self.x,
-            y: self.y + 1,
-        }
-    }
-    fn south_east(&self) -> Self {
-        Self {
-            x: self.x + 1,
-            y: self.y + 1,
-        }
-    }
-}
+type Point = Point2<i32>;
 
 fn map(s: &str) -> anyhow::Result<HashSet<Point>> {
     let mut result = HashSet::new();
-    for (y, line) in s.lines().enumerate() {
+    for (y, line) in Lines::new(s)?.enumerate() {
         for (x, char) in line.chars().enumerate() {
             match char {
                 '#' => {
@@ -78,53 +23,28 @@ fn map(s: &str) -> anyhow::Result<HashSet<Point>> {
     Ok(result)
 }
 
-enum Direction {
-    N,
-    S,
-    W,
-    E,
+/// Return the diagonal or straight direction that is the same as `direction`
+fn straight(direction: Direction) -> Direction8 {
+    match direction {
+        Direction::Up => Direction8::Up,
+        Direction::Right => Direction8::Right,
+        Direction::Down => Direction8::Down,
+        Direction::Left => Direction8::Left,
+    }
 }
 
 fn updated_elf(map: &HashSet<Point>, directions: &VecDeque<Direction>, elf: &Point) -> Point {
-    let nw = !map.contains(&elf.north_west());
-    let nn = !map.contains(&elf.north());
-    let ne = !map.contains(&elf.north_east());
-
-    let ww = !map.contains(&elf.west());
-    let ee = !map.contains(&elf.east());
-
-    let sw = !map.contains(&elf.south_west());
-    let ss = !map.contains(&elf.south());
-    let se = !map.contains(&elf.south_east());
-    if nw && nn && ne && ww && ee && sw && ss && se {
-        elf.clone()
-    } else {
-        for direction in directions {
-            match direction {
-                Direction::N => {
-                    if nn && ne && nw {
-                        return elf.north();
-                    }
-                }
-                Direction::S => {
-                    if ss && se && sw {
-                        return elf.south();
-                    }
-                }
-                Direction::W => {
-                    if ww && nw && sw {
-                        return elf.west();
-                    }
-                }
-                Direction::E => {
-                    if ee && ne && se {
-                        return elf.east();
-                    }
-                }
-            }
+    let is_free = |direction: Direction8| !map.contains(&(*elf + direction.offset()));
+    if Direction8::ALL.into_iter().all(is_free) {
+        return *elf;
+    }
+    for &direction in directions {
+        let ahead = straight(direction);
+        if is_free(ahead.turned_left()) && is_free(ahead) && is_free(ahead.turned_right()) {
+            return *elf + direction.offset();
         }
-        elf.clone()
     }
+    *elf
 }
 
 fn updated_map(before: &HashSet<Point>, directions: &VecDeque<Direction>) -> HashSet<Point> {
@@ -133,7 +53,7 @@ fn updated_map(before: &HashSet<Point>, directions: &VecDeque<Direction>) -> Has
         during
             .entry(updated_elf(before, directions, elf))
             .or_default()
-            .push(elf.clone());
+            .push(*elf);
     }
     let mut after = HashSet::with_capacity(before.len());
     for (proposed, elfs) in during.drain() {
@@ -149,9 +69,14 @@ fn updated_map(before: &HashSet<Point>, directions: &VecDeque<Direction>) -> Has
 }
 
 fn simulate(map: HashSet<Point>, num_step: Option<usize>) -> (usize, HashSet<Point>) {
-    let mut directions: VecDeque<_> = [Direction::N, Direction::S, Direction::W, Direction::E]
-        .into_iter()
-        .collect();
+    let mut directions: VecDeque<_> = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+    ]
+    .into_iter()
+    .collect();
 
     let mut old = map;
     let mut new = updated_map(&old, &directions);
@@ -190,6 +115,13 @@ pub fn part_2(input: &str) -> anyhow::Result<usize> {
     Ok(num_step)
 }
 
+crate::registry::register_solvers! {
+    2022, 23, "Unstable Diffusion";
+    fingerprint = Fingerprint::new().alphabet("#.");
+    One => part_1,
+    Two => part_2,
+}
+
 #[cfg(test)]
 mod tests {
     use crate::testing::{assert_correct_answer_on_correct_input, assert_error_on_wrong_input};
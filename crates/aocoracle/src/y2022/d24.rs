@@ -1,8 +1,12 @@
 use anyhow::{anyhow, bail};
 use hashbrown::HashSet;
-use pathfinding::prelude::astar;
 use std::str::FromStr;
 
+use crate::fingerprint::Fingerprint;
+use crate::grid::Grid;
+use crate::search;
+use crate::trace::{self, Event};
+
 #[derive(Debug)]
 struct Map {
     start: (usize, usize),
@@ -21,62 +25,58 @@ impl FromStr for Map {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let lines: Vec<_> = s.lines().collect();
+        let tiles = Grid::parse(s, |c| match c {
+            '#' | '.' | '^' | 'v' | '<' | '>' => Ok(c),
+            _ => bail!("Expected one of '#', '^', 'v', '<', '>', or '.' but got {c}"),
+        })?;
+        if tiles.width() < 3 || tiles.height() < 3 {
+            bail!("Expected rectangular input with sides no shorter than 3");
+        }
         let x_min = 1;
-        let x_max = lines[0]
-            .len()
-            .checked_sub(2)
-            .ok_or_else(|| anyhow!("Expected rectangular input with sides no shorter than 3"))?;
+        let x_max = tiles.width() - 2;
         let y_min = 1;
-        let y_max = lines
-            .len()
-            .checked_sub(2)
-            .ok_or_else(|| anyhow!("Expected rectangular input with sides no shorter than 3"))?;
-        let x_start = lines[0]
-            .chars()
-            .position(|char| char == '.')
+        let y_max = tiles.height() - 2;
+        let x_start = tiles
+            .row(0)
+            .iter()
+            .position(|&c| c == '.')
             .ok_or_else(|| anyhow!("Expected start tile on first line"))?;
-        let x_goal = lines[lines.len() - 1]
-            .chars()
-            .position(|char| char == '.')
+        let x_goal = tiles
+            .row(y_max + 1)
+            .iter()
+            .position(|&c| c == '.')
             .ok_or_else(|| anyhow!("Expected goal tile on last line"))?;
 
-        if x_max < 1 || y_max < 1 {
-            bail!("Expected rectangular input with sides no shorter than 3");
-        }
-
         let mut up = HashSet::new();
         let mut down = HashSet::new();
         let mut left = HashSet::new();
         let mut right = HashSet::new();
-        for (y, line) in lines.iter().enumerate() {
-            for (x, char) in line.chars().enumerate() {
-                if y == y_min - 1 || y == y_max + 1 {
-                    continue;
+        for ((x, y), &char) in tiles.iter() {
+            if y == y_min - 1 || y == y_max + 1 {
+                continue;
+            }
+            if x == x_min - 1 || x == x_max + 1 {
+                if char != '#' {
+                    bail!("Expected wall on first and last column");
                 }
-                if x == x_min - 1 || x == x_max + 1 {
-                    if char != '#' {
-                        bail!("Expected wall on first and last column");
-                    }
-                    continue;
+                continue;
+            }
+            match char {
+                '^' => {
+                    up.insert((x, y));
                 }
-                match char {
-                    '^' => {
-                        up.insert((x, y));
-                    }
-                    'v' => {
-                        down.insert((x, y));
-                    }
-                    '<' => {
-                        left.insert((x, y));
-                    }
-                    '>' => {
-                        right.insert((x, y));
-                    }
-                    '.' => {}
-                    _ => {
-                        bail!("Expected one of '^', 'v', '<', '>', or '.' but got {char}");
-                    }
+                'v' => {
+                    down.insert((x, y));
+                }
+                '<' => {
+                    left.insert((x, y));
+                }
+                '>' => {
+                    right.insert((x, y));
+                }
+                '.' => {}
+                _ => {
+                    bail!("Expected one of '^', 'v', '<', '>', or '.' but got {char}");
                 }
             }
         }
@@ -278,114 +278,79 @@ impl State {
         self.reached_goal && self.reached_start && self.elf == map.goal
     }
 
-    fn _print(&self, map: &Map, label: &str) {
-        println!("{}:", label);
-        for x in (map.x_min - 1)..=(map.x_max + 1) {
-            if self.elf == (x, map.y_min - 1) {
-                print!("E");
-            } else if map.start.0 == x {
-                print!(".");
-            } else {
-                print!("#");
-            }
-        }
-        println!();
+    fn event(&self, map: &Map, label: String) -> Event {
+        let wall = |y: usize, opening: usize| -> String {
+            ((map.x_min - 1)..=(map.x_max + 1))
+                .map(|x| match self.elf == (x, y) {
+                    true => 'E',
+                    false if opening == x => '.',
+                    false => '#',
+                })
+                .collect()
+        };
+        let mut rows = vec![wall(map.y_min - 1, map.start.0)];
         for y in map.y_min..=map.y_max {
-            print!("#");
+            let mut row = String::from("#");
             for x in map.x_min..=map.x_max {
                 if self.elf == (x, y) {
-                    print!("E");
+                    row.push('E');
                     continue;
                 }
                 match map.tile(x, y, self.t) {
-                    Some(s) => print!("{}", s),
-                    None => print!("."),
+                    Some(s) => row.push_str(s),
+                    None => row.push('.'),
                 }
             }
-            print!("#");
-            println!();
+            row.push('#');
+            rows.push(row);
         }
-        for x in (map.x_min - 1)..=(map.x_max + 1) {
-            if self.elf == (x, map.y_max + 1) {
-                print!("E");
-            } else if map.goal.0 == x {
-                print!(".");
-            } else {
-                print!("#");
-            }
-        }
-        println!();
-        println!();
+        rows.push(wall(map.y_max + 1, map.goal.0));
+        Event::grid(label, rows)
     }
 }
 
-fn _print_example(map: &Map) -> Option<()> {
-    let mut state = State::start_state(map);
-    state._print(map, "Initial state");
-    state = state.updated_blizzards().moved_down(map)?;
-    state._print(map, "Minute 1, move down");
-    state = state.updated_blizzards().moved_down(map)?;
-    state._print(map, "Minute 2, move down");
-    state = state.updated_blizzards().wait(map)?;
-    state._print(map, "Minute 3, wait");
-    state = state.updated_blizzards().moved_up(map)?;
-    state._print(map, "Minute 4, move up");
-    state = state.updated_blizzards().moved_right(map)?;
-    state._print(map, "Minute 5, move right");
-    state = state.updated_blizzards().moved_right(map)?;
-    state._print(map, "Minute 6, move right");
-    state = state.updated_blizzards().moved_down(map)?;
-    state._print(map, "Minute 7, move down");
-    state = state.updated_blizzards().moved_left(map)?;
-    state._print(map, "Minute 8, move left");
-    state = state.updated_blizzards().moved_up(map)?;
-    state._print(map, "Minute 9, move up");
-    state = state.updated_blizzards().moved_right(map)?;
-    state._print(map, "Minute 10, move right");
-    state = state.updated_blizzards().wait(map)?;
-    state._print(map, "Minute 11, wait");
-    state = state.updated_blizzards().moved_down(map)?;
-    state._print(map, "Minute 12, move down");
-    state = state.updated_blizzards().moved_down(map)?;
-    state._print(map, "Minute 13, move down");
-    state = state.updated_blizzards().moved_right(map)?;
-    state._print(map, "Minute 14, move right");
-    state = state.updated_blizzards().moved_right(map)?;
-    state._print(map, "Minute 15, move right");
-    state = state.updated_blizzards().moved_right(map)?;
-    state._print(map, "Minute 16, move right");
-    state = state.updated_blizzards().moved_down(map)?;
-    state._print(map, "Minute 17, move down");
-    state = state.updated_blizzards().moved_down(map)?;
-    state._print(map, "Minute 18, move down");
-    Some(())
+fn trace_path(map: &Map, path: &[State]) {
+    for (minute, state) in path.iter().enumerate() {
+        trace::emit(|| state.event(map, format!("Minute {minute}")));
+    }
 }
 
 pub fn part_1(input: &str) -> anyhow::Result<usize> {
     let map = Map::from_str(input)?;
     let start = State::start_state(&map);
 
-    let (_, cost) = astar(
-        &start,
+    let path = search::astar(
+        [start],
         |s| s.neighbors(&map),
         |s| s.heuristic(&map),
         |s| s.success(&map),
-    )
-    .unwrap();
-    Ok(cost)
+    )?
+    .path
+    .ok_or_else(|| anyhow!("Found no path"))?;
+    trace_path(&map, &path.nodes);
+    Ok(path.cost)
 }
 
 pub fn part_2(input: &str) -> anyhow::Result<usize> {
     let map = Map::from_str(input)?;
     let start = State::start_state(&map);
-    let (_, cost) = astar(
-        &start,
+    let path = search::astar(
+        [start],
         |s| s.neighbors(&map),
         |s| s.heuristic2(&map),
         |s| s.success2(&map),
-    )
-    .unwrap();
-    Ok(cost)
+    )?
+    .path
+    .ok_or_else(|| anyhow!("Found no path"))?;
+    trace_path(&map, &path.nodes);
+    Ok(path.cost)
+}
+
+crate::registry::register_solvers! {
+    2022, 24, "Blizzard Basin";
+    fingerprint = Fingerprint::new().alphabet("#.<>^v").first_line(r"^#\.#+$");
+    One => part_1,
+    Two => part_2,
 }
 
 #[cfg(test)]
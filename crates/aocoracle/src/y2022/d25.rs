@@ -1,16 +1,13 @@
-use anyhow::{anyhow, bail};
-
-fn checked_pow_mul_add(base: i64, exp: u32, mul_by: i64, add_to: i64) -> Option<i64> {
-    base.checked_pow(exp)?
-        .checked_mul(mul_by)?
-        .checked_add(add_to)
-}
+use crate::checked::{Checked, CheckedIter};
+use crate::fingerprint::Fingerprint;
+use crate::parse::Lines;
+use anyhow::bail;
 
 trait Snafu: Sized {
     type Err;
 
     fn from_snafu(s: &str) -> Result<Self, Self::Err>;
-    fn to_snafu(&self) -> String;
+    fn to_snafu(&self) -> Result<String, Self::Err>;
 }
 
 impl Snafu for i64 {
@@ -22,52 +19,42 @@ impl Snafu for i64 {
         }
         let mut result = 0;
         for (i, ch) in s.chars().rev().enumerate() {
-            result = checked_pow_mul_add(
-                5,
-                i as u32,
-                match ch {
-                    '2' => 2,
-                    '1' => 1,
-                    '0' => 0,
-                    '-' => -1,
-                    '=' => -2,
-                    _ => bail!("Expected one of '2', '1', '0'', '-', or '=' but got {ch}"),
-                },
-                result,
-            )
-            .ok_or_else(|| anyhow!("Overflow"))?;
+            let digit = match ch {
+                '2' => 2,
+                '1' => 1,
+                '0' => 0,
+                '-' => -1,
+                '=' => -2,
+                _ => bail!("Expected one of '2', '1', '0'', '-', or '=' but got {ch}"),
+            };
+            result = 5_i64.try_pow(i as u32)?.try_mul(digit)?.try_add(result)?;
         }
         Ok(result)
     }
 
-    fn to_snafu(&self) -> String {
+    fn to_snafu(&self) -> Result<String, Self::Err> {
         let mut rem = *self;
         let mut result = Vec::new();
         while rem != 0 {
-            result.push(match rem % 5 {
-                0 => '0',
-                1 => '1',
-                2 => '2',
-                3 => {
-                    rem += 2;
-                    '='
-                }
-                4 => {
-                    rem += 1;
-                    '-'
-                }
+            let (ch, digit) = match rem.rem_euclid(5) {
+                0 => ('0', 0),
+                1 => ('1', 1),
+                2 => ('2', 2),
+                3 => ('=', -2),
+                4 => ('-', -1),
                 _ => unreachable!(),
-            });
-            rem /= 5;
+            };
+            result.push(ch);
+            rem = rem.try_sub(digit)? / 5;
         }
         result.reverse();
-        result.iter().collect()
+        Ok(result.iter().collect())
     }
 }
 
 fn fuel_requirements(s: &str) -> anyhow::Result<Vec<i64>> {
     let mut result = Vec::new();
-    for line in s.lines() {
+    for line in Lines::new(s)? {
         result.push(i64::from_snafu(line)?);
     }
     Ok(result)
@@ -75,7 +62,13 @@ fn fuel_requirements(s: &str) -> anyhow::Result<Vec<i64>> {
 
 pub fn part_1(input: &str) -> anyhow::Result<String> {
     let fuel_requirements = fuel_requirements(input)?;
-    Ok(fuel_requirements.iter().sum::<i64>().to_snafu())
+    fuel_requirements.into_iter().try_sum()?.to_snafu()
+}
+
+crate::registry::register_solvers! {
+    2022, 25, "Full of Hot Air";
+    fingerprint = Fingerprint::new().alphabet("=-012");
+    One => part_1,
 }
 
 #[cfg(test)]
@@ -102,8 +95,19 @@ mod tests {
 
     #[test]
     fn snafu_conversion() {
-        for expected in [1, 2, 3, 4, 5, 6, 7, 8, 9, 34061028947237] {
-            assert_eq!(i64::from_snafu(&expected.to_snafu()).unwrap(), expected);
+        for expected in [1, 2, 3, 4, 5, 6, 7, 8, 9, 34061028947237, -1, -7, i64::MAX] {
+            assert_eq!(
+                i64::from_snafu(&expected.to_snafu().unwrap()).unwrap(),
+                expected
+            );
         }
     }
+
+    #[test]
+    fn returns_error_on_overflow() {
+        assert!(i64::MIN.to_snafu().is_err());
+        let max = i64::MAX.to_snafu().unwrap();
+        assert!(part_1(&format!("{max}\n1")).is_err());
+        assert!(part_1(&format!("1{max}")).is_err());
+    }
 }
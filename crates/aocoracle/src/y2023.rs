@@ -1,9 +1,11 @@
-pub mod d01;
-pub mod d02;
-pub mod d03;
-pub mod d04;
-pub mod d05;
-pub mod d06;
-pub mod d07;
-pub mod d08;
-pub mod d09;
+crate::registry::register_days! {
+    d01,
+    d02,
+    d03,
+    d04,
+    d05,
+    d06,
+    d07,
+    d08,
+    d09,
+}
@@ -1,3 +1,5 @@
+use crate::fingerprint::Fingerprint;
+use crate::parse::Lines;
 use anyhow::bail;
 
 fn line2num(line: &str) -> Option<u32> {
@@ -54,7 +56,7 @@ pub fn part_1(input: &str) -> anyhow::Result<u32> {
         bail!("contains no digits spelled out as words")
     }
     let mut sum = 0;
-    for line in input.lines() {
+    for line in Lines::new(input)? {
         let Some(addend) = line2num(line) else {
             bail!("Could not parse line {line}");
         };
@@ -69,7 +71,7 @@ pub fn part_2(input: &str) -> anyhow::Result<u32> {
         bail!("contains no digits spelled out as words")
     }
     let mut sum = 0;
-    for line in input.lines() {
+    for line in Lines::new(input)? {
         let Some(addend) = line2num2(line) else {
             bail!("Could not parse line {line}");
         };
@@ -78,6 +80,13 @@ pub fn part_2(input: &str) -> anyhow::Result<u32> {
     Ok(sum)
 }
 
+crate::registry::register_solvers! {
+    2023, 1, "Trebuchet?!";
+    fingerprint = Fingerprint::new().alphabet("abcdefghijklmnopqrstuvwxyz0123456789");
+    One => part_1,
+    Two => part_2,
+}
+
 #[cfg(test)]
 mod tests {
     use crate::testing::{assert_correct_answer_on_correct_input, assert_error_on_wrong_input};
@@ -1,3 +1,6 @@
+use crate::checked::{Checked, CheckedIter};
+use crate::fingerprint::Fingerprint;
+use crate::parse::{self, Lines};
 use anyhow::bail;
 use std::collections::HashMap;
 use std::str::FromStr;
@@ -29,7 +32,7 @@ fn parsed_draw(text: &str) -> anyhow::Result<HashMap<Color, usize>> {
             .trim()
             .split_once(' ')
             .ok_or_else(|| anyhow::anyhow!("Expected exactly one interior ' ' but got {pair:?}"))?;
-        let count = count.parse()?;
+        let count = parse::number(count)?;
         let color = color.parse()?;
         retval.insert(color, count);
     }
@@ -46,7 +49,7 @@ fn parsed_game(line: &str) -> anyhow::Result<(usize, Vec<HashMap<Color, usize>>)
     if prefix != "Game" {
         bail!("Expected line starting with 'Game' but gout {line:}");
     }
-    let num = num.parse()?;
+    let num = parse::number(num)?;
 
     let mut parsed_draws = Vec::new();
     for draw in draws.split(';') {
@@ -58,7 +61,7 @@ fn parsed_game(line: &str) -> anyhow::Result<(usize, Vec<HashMap<Color, usize>>)
 
 pub fn part_1(input: &str) -> anyhow::Result<usize> {
     let mut sum = 0;
-    'line_loop: for line in input.lines() {
+    'line_loop: for line in Lines::new(input)? {
         let (game_num, draws) = parsed_game(line)?;
         for draw in draws {
             for (color, count) in draw.into_iter() {
@@ -72,14 +75,14 @@ pub fn part_1(input: &str) -> anyhow::Result<usize> {
                 }
             }
         }
-        sum += game_num;
+        sum = game_num.try_add(sum)?;
     }
     Ok(sum)
 }
 
 pub fn part_2(input: &str) -> anyhow::Result<usize> {
     let mut sum = 0;
-    for line in input.lines() {
+    for line in Lines::new(input)? {
         let (_, draws) = parsed_game(line)?;
         let mut min_red = 0;
         let mut min_green = 0;
@@ -93,12 +96,19 @@ pub fn part_2(input: &str) -> anyhow::Result<usize> {
                 }
             }
         }
-        let power = min_red * min_green * min_blue;
-        sum += power;
+        let power = [min_red, min_green, min_blue].into_iter().try_product()?;
+        sum = sum.try_add(power)?;
     }
     Ok(sum)
 }
 
+crate::registry::register_solvers! {
+    2023, 2, "Cube Conundrum";
+    fingerprint = Fingerprint::new().first_line(r"^Game 1: ");
+    One => part_1,
+    Two => part_2,
+}
+
 #[cfg(test)]
 mod tests {
     use crate::testing::{assert_correct_answer_on_correct_input, assert_error_on_wrong_input};
@@ -130,4 +140,10 @@ mod tests {
     fn returns_error_on_wrong_input() {
         assert_error_on_wrong_input!(part_1, part_2);
     }
+
+    #[test]
+    fn returns_error_on_overflow() {
+        let input = "Game 1: 4294967296 red, 4294967296 green, 4294967296 blue";
+        assert!(part_2(input).is_err());
+    }
 }
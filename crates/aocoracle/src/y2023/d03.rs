@@ -1,5 +1,8 @@
 use std::collections::BTreeMap;
 
+use crate::checked::{Checked, CheckedIter};
+use crate::fingerprint::Fingerprint;
+use crate::grid::Grid;
 use hashbrown::HashSet;
 use itertools::Itertools;
 
@@ -10,48 +13,41 @@ struct Input {
 
 impl Input {
     fn parse(input: &str) -> anyhow::Result<Self> {
-        let re = regex::Regex::new(r"([0-9]+|[^0-9])").expect("Hard coded regex is valid");
+        let grid = Grid::parse(input, |c| match c {
+            '0'..='9' | '.' | '*' | '#' | '$' | '%' | '&' | '+' | '-' | '/' | '=' | '@' => Ok(c),
+            _ => anyhow::bail!("Invalid symbol"),
+        })?;
 
         let mut next_number_id = 0;
         let mut numbers = BTreeMap::new();
         let mut symbols = BTreeMap::new();
 
-        for (row_num, line) in input.lines().enumerate() {
+        for (row_num, row) in grid.rows().enumerate() {
             let row_num: isize = row_num.try_into()?;
-            let mut col_num: isize = 0;
-            for token in re.captures_iter(line) {
-                let token = token
-                    .get(1)
-                    .expect("Hard coded regex always matches exactly one group")
-                    .as_str();
-
-                match token.parse::<u32>() {
-                    Ok(number) => {
-                        for _ in 0..token.len() {
-                            numbers.insert((row_num, col_num), (next_number_id, number));
-                            col_num += 1;
-                        }
-                        next_number_id += 1;
+            for (is_number, group) in &row.iter().enumerate().group_by(|(_, c)| c.is_ascii_digit())
+            {
+                let group: Vec<_> = group.collect();
+                if is_number {
+                    let token: String = group.iter().map(|(_, &c)| c).collect();
+                    let number: u32 = token.parse().map_err(|_| {
+                        anyhow::anyhow!("Expected small numbers and symbols but got {}", token)
+                    })?;
+                    for (col_num, _) in group {
+                        numbers.insert((row_num, col_num.try_into()?), (next_number_id, number));
                     }
-                    Err(e) => match e.kind() {
-                        std::num::IntErrorKind::InvalidDigit => {
-                            let token = token.chars().exactly_one().expect(
-                                "Hard coded regex matches a valid number or a single symbol",
-                            );
-                            match token {
-                                '.' => {}
-                                '*' => {
-                                    symbols.insert((row_num, col_num), true);
-                                }
-                                '#' | '$' | '%' | '&' | '+' | '-' | '/' | '=' | '@' => {
-                                    symbols.insert((row_num, col_num), false);
-                                }
-                                _ => anyhow::bail!("Invalid symbol"),
-                            }
-                            col_num += 1;
+                    next_number_id += 1;
+                    continue;
+                }
+                for (col_num, &c) in group {
+                    match c {
+                        '.' => {}
+                        '*' => {
+                            symbols.insert((row_num, col_num.try_into()?), true);
                         }
-                        _ => anyhow::bail!("Expected small numbers and symbols but got {}", token),
-                    },
+                        _ => {
+                            symbols.insert((row_num, col_num.try_into()?), false);
+                        }
+                    }
                 }
             }
         }
@@ -91,7 +87,7 @@ pub fn part_1(input: &str) -> anyhow::Result<u32> {
     for ((r, c), _) in input.symbols.iter() {
         for (id, value) in input.adjacent_numbers(*r, *c) {
             if used_ids.insert(id) {
-                sum += value;
+                sum = value.try_add(sum)?;
             }
         }
     }
@@ -109,12 +105,19 @@ pub fn part_2(input: &str) -> anyhow::Result<u32> {
         let adjacent_numbers: BTreeMap<usize, u32> =
             input.adjacent_numbers(*r, *c).into_iter().collect();
         if adjacent_numbers.len() == 2 {
-            sum += adjacent_numbers.values().product::<u32>();
+            sum = adjacent_numbers.into_values().try_product()?.try_add(sum)?;
         }
     }
     Ok(sum)
 }
 
+crate::registry::register_solvers! {
+    2023, 3, "Gear Ratios";
+    fingerprint = Fingerprint::new().alphabet("0123456789.*#+$/@=%&-");
+    One => part_1,
+    Two => part_2,
+}
+
 #[cfg(test)]
 mod tests {
     use crate::testing::{assert_correct_answer_on_correct_input, assert_error_on_wrong_input};
@@ -146,4 +149,10 @@ mod tests {
     fn returns_error_on_wrong_input() {
         assert_error_on_wrong_input!(part_1, part_2);
     }
+
+    #[test]
+    fn returns_error_on_overflow() {
+        assert!(part_1("4294967295*1").is_err());
+        assert!(part_2("65536*65536").is_err());
+    }
 }
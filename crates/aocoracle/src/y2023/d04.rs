@@ -1,3 +1,6 @@
+use crate::checked::Checked;
+use crate::fingerprint::Fingerprint;
+use crate::parse::{self, Lines};
 use anyhow::{bail, Context};
 use hashbrown::{HashMap, HashSet};
 
@@ -6,7 +9,7 @@ use hashbrown::{HashMap, HashSet};
 /// Note that the cards are 0-indexed from this point.
 fn parsed_cards(input: &str) -> anyhow::Result<Vec<u8>> {
     let mut cards = Vec::with_capacity(input.lines().count());
-    for (i, line) in input.lines().enumerate() {
+    for (i, line) in Lines::new(input)?.enumerate() {
         let (answers, guesses) = line
             .split_once('|')
             .ok_or_else(|| anyhow::anyhow!("Expected exactly one '|' but got {line:?}"))?;
@@ -17,21 +20,19 @@ fn parsed_cards(input: &str) -> anyhow::Result<Vec<u8>> {
         let card_num = title.strip_prefix("Card ").ok_or_else(|| {
             anyhow::anyhow!("Expected title to start with 'Card ' but got {title:?}")
         })?;
-        let card_num: usize = card_num
-            .trim()
-            .parse()
-            .with_context(|| format!("{title:?}"))?;
+        let card_num: usize =
+            parse::number(card_num.trim()).with_context(|| format!("{title:?}"))?;
         if card_num != i + 1 {
             bail!("Expected card number to be {i} but got {card_num}");
         }
         let answers: HashSet<usize> = answers
             .split_whitespace()
-            .flat_map(|s| s.parse().ok())
-            .collect();
+            .map(parse::number)
+            .collect::<anyhow::Result<_>>()?;
         let guesses: Vec<usize> = guesses
             .split_whitespace()
-            .flat_map(|s| s.parse().ok())
-            .collect();
+            .map(parse::number)
+            .collect::<anyhow::Result<_>>()?;
         let num_correct = guesses
             .iter()
             .filter(|g| answers.contains(g))
@@ -42,21 +43,27 @@ fn parsed_cards(input: &str) -> anyhow::Result<Vec<u8>> {
     Ok(cards)
 }
 
-fn cards_count(cache: &mut HashMap<usize, usize>, cards: &[u8], curr: usize) -> usize {
-    let num_correct = *cards.get(curr).unwrap();
+fn cards_count(
+    cache: &mut HashMap<usize, usize>,
+    cards: &[u8],
+    curr: usize,
+) -> anyhow::Result<usize> {
+    let num_correct = *cards
+        .get(curr)
+        .ok_or_else(|| anyhow::anyhow!("Expected card {} to exist", curr + 1))?;
     let first = curr + 1;
     let last = curr + num_correct as usize;
 
-    let mut count = 1;
+    let mut count: usize = 1;
     for next in first..=last {
         if let Some(v) = cache.get(&next) {
-            count += v;
+            count = count.try_add(*v)?;
         } else {
-            count += cards_count(cache, cards, next);
+            count = count.try_add(cards_count(cache, cards, next)?)?;
         }
     }
     cache.insert(curr, count);
-    count
+    Ok(count)
 }
 
 pub fn part_1(input: &str) -> anyhow::Result<u32> {
@@ -65,7 +72,7 @@ pub fn part_1(input: &str) -> anyhow::Result<u32> {
     let mut sum = 0;
     for num_correct in cards {
         if let Some(p) = num_correct.checked_sub(1) {
-            sum += 2_u32.pow(p.into());
+            sum = 2_u32.try_pow(p.into())?.try_add(sum)?;
         }
     }
     Ok(sum)
@@ -77,12 +84,19 @@ pub fn part_2(input: &str) -> anyhow::Result<usize> {
 
     let mut sum = 0;
     for card_num in 0..cards.len() {
-        let value = cards_count(&mut cache, &cards, card_num);
-        sum += value;
+        let value = cards_count(&mut cache, &cards, card_num)?;
+        sum = value.try_add(sum)?;
     }
     Ok(sum)
 }
 
+crate::registry::register_solvers! {
+    2023, 4, "Scratchcards";
+    fingerprint = Fingerprint::new().first_line(r"^Card +1:[\d ]+\|[\d ]+$");
+    One => part_1,
+    Two => part_2,
+}
+
 #[cfg(test)]
 mod tests {
     use crate::testing::{assert_correct_answer_on_correct_input, assert_error_on_wrong_input};
@@ -114,4 +128,14 @@ mod tests {
     fn returns_error_on_wrong_input() {
         assert_error_on_wrong_input!(part_1, part_2);
     }
+
+    #[test]
+    fn returns_error_on_overflow() {
+        let numbers = (1..=33)
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        assert!(part_1(&format!("Card 1: {numbers} | {numbers}")).is_err());
+        assert!(part_2("Card 1: 1 | 1").is_err());
+    }
 }
@@ -1,8 +1,12 @@
-use std::str::Lines;
-
+use crate::checked::{Checked, CheckedIter};
+use crate::fingerprint::Fingerprint;
+use crate::parse::{self, Lines};
 use anyhow::bail;
 
-fn take_suffix<'a>(lines: &'a mut Lines, expected_prefix: &'static str) -> anyhow::Result<&'a str> {
+fn take_suffix<'a>(
+    lines: &mut Lines<'a>,
+    expected_prefix: &'static str,
+) -> anyhow::Result<&'a str> {
     let Some(line) = lines.next() else {
         bail!("Expected at least one more line")
     };
@@ -15,50 +19,64 @@ fn take_suffix<'a>(lines: &'a mut Lines, expected_prefix: &'static str) -> anyho
     Ok(suffix)
 }
 fn races(input: &str) -> anyhow::Result<Vec<(i64, i64)>> {
-    let mut lines = input.lines();
+    let mut lines = Lines::new(input)?;
     let mut times = Vec::new();
     for time in take_suffix(&mut lines, "Time")?.split_whitespace() {
-        times.push(time.parse()?);
+        times.push(parse::number(time)?);
     }
     let mut distances = Vec::new();
     for distance in take_suffix(&mut lines, "Distance")?.split_whitespace() {
-        distances.push(distance.parse()?)
+        distances.push(parse::number(distance)?)
     }
+    lines.finish()?;
     Ok(times.into_iter().zip(distances).collect())
 }
 fn race(input: &str) -> anyhow::Result<(i64, i64)> {
-    let mut lines = input.lines();
-    let time = take_suffix(&mut lines, "Time")?
-        .split_whitespace()
-        .collect::<String>()
-        .parse()?;
-    let distance = take_suffix(&mut lines, "Distance")?
-        .split_whitespace()
-        .collect::<String>()
-        .parse()?;
+    let mut lines = Lines::new(input)?;
+    let time = parse::number(
+        &take_suffix(&mut lines, "Time")?
+            .split_whitespace()
+            .collect::<String>(),
+    )?;
+    let distance = parse::number(
+        &take_suffix(&mut lines, "Distance")?
+            .split_whitespace()
+            .collect::<String>(),
+    )?;
+    lines.finish()?;
     Ok((time, distance))
 }
 
-fn num_victory(time: i64, distance: i64) -> i64 {
+fn num_victory(time: i64, distance: i64) -> anyhow::Result<i64> {
     let b = time as f64;
     let a = -1.0;
-    let c = -(distance + 1) as f64;
+    let c = -(distance.try_add(1)? as f64);
     let first = (-b + (b * b - 4.0 * a * c).sqrt()) / (2.0 * a);
     let last = (-b - (b * b - 4.0 * a * c).sqrt()) / (2.0 * a);
-    last.floor() as i64 - first.ceil() as i64 + 1
+    Ok((last.floor() as i64)
+        .try_sub(first.ceil() as i64)?
+        .try_add(1)?)
 }
 
 pub fn part_1(input: &str) -> anyhow::Result<i64> {
     let races = races(input)?;
-    Ok(races
-        .into_iter()
-        .map(|(t, d)| num_victory(t, d))
-        .product::<i64>())
+    let mut counts = Vec::with_capacity(races.len());
+    for (time, distance) in races {
+        counts.push(num_victory(time, distance)?);
+    }
+    Ok(counts.into_iter().try_product()?)
 }
 
 pub fn part_2(input: &str) -> anyhow::Result<i64> {
     let (time, distance) = race(input)?;
-    Ok(num_victory(time, distance))
+    num_victory(time, distance)
+}
+
+crate::registry::register_solvers! {
+    2023, 6, "Wait For It";
+    fingerprint = Fingerprint::new().lines(2, 2).first_line(r"^Time:( +\d+)+$");
+    One => part_1,
+    Two => part_2,
 }
 
 #[cfg(test)]
@@ -92,4 +110,11 @@ mod tests {
     fn returns_error_on_wrong_input() {
         assert_error_on_wrong_input!(part_1, part_2);
     }
+
+    #[test]
+    fn returns_error_on_overflow() {
+        let input = "Time: 1\nDistance: 9223372036854775807";
+        assert!(part_1(input).is_err());
+        assert!(part_2(input).is_err());
+    }
 }
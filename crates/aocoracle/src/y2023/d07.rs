@@ -1,3 +1,6 @@
+use crate::checked::{ArithmeticError, Checked};
+use crate::fingerprint::Fingerprint;
+use crate::parse::{self, Lines};
 use anyhow::bail;
 
 type Hand = [u8; 5];
@@ -62,33 +65,40 @@ fn parsed_hand(hand: &str, jack: u8) -> anyhow::Result<Hand> {
 
 fn parsed_input(input: &str, j: u8) -> anyhow::Result<Vec<(Hand, usize)>> {
     let mut result = Vec::new();
-    for line in input.lines() {
+    for line in Lines::new(input)? {
         let Some((hand, bid)) = line.split_once(' ') else {
             bail!("Expected a space in line but got {:?}", line);
         };
-        let bid = bid.parse()?;
+        let bid = parse::number(bid)?;
         result.push((parsed_hand(hand, j)?, bid));
     }
     Ok(result)
 }
 
-fn total_winnings(mut input: Vec<(Hand, usize)>) -> usize {
+fn total_winnings(mut input: Vec<(Hand, usize)>) -> Result<usize, ArithmeticError> {
     input.sort_by_key(|(h, _)| strength(h));
-    input
-        .into_iter()
-        .enumerate()
-        .map(|(i, (_, bid))| bid * (i + 1))
-        .sum()
+    let mut result: usize = 0;
+    for (i, (_, bid)) in input.into_iter().enumerate() {
+        result = bid.try_mul(i + 1)?.try_add(result)?;
+    }
+    Ok(result)
 }
 
 pub fn part_1(input: &str) -> anyhow::Result<usize> {
     let input = parsed_input(input, 11)?;
-    Ok(total_winnings(input))
+    Ok(total_winnings(input)?)
 }
 
 pub fn part_2(input: &str) -> anyhow::Result<usize> {
     let input = parsed_input(input, 0)?;
-    Ok(total_winnings(input))
+    Ok(total_winnings(input)?)
+}
+
+crate::registry::register_solvers! {
+    2023, 7, "Camel Cards";
+    fingerprint = Fingerprint::new().first_line(r"^[2-9TJQKA]{5} \d+$");
+    One => part_1,
+    Two => part_2,
 }
 
 #[cfg(test)]
@@ -123,4 +133,11 @@ mod tests {
     fn returns_error_on_wrong_input() {
         assert_error_on_wrong_input!(part_1, part_2);
     }
+
+    #[test]
+    fn returns_error_on_overflow() {
+        let input = "32T3K 18446744073709551615\nT55J5 1";
+        assert!(part_1(input).is_err());
+        assert!(part_2(input).is_err());
+    }
 }
@@ -1,3 +1,5 @@
+use crate::fingerprint::Fingerprint;
+use crate::parse::Lines;
 use anyhow::{anyhow, bail};
 use hashbrown::HashMap;
 
@@ -8,7 +10,7 @@ struct Input<'a> {
 
 impl<'a> Input<'a> {
     fn try_new(input: &'a str) -> anyhow::Result<Self> {
-        let mut lines = input.lines();
+        let mut lines = Lines::new(input)?;
         let mut directions = Vec::new();
         for d in lines
             .next()
@@ -21,16 +23,27 @@ impl<'a> Input<'a> {
                 _ => bail!("Expected L or R but got {d}"),
             });
         }
-        lines
+        let blank = lines
             .next()
             .ok_or_else(|| anyhow!("Expected a blank line"))?;
+        if !blank.is_empty() {
+            bail!("Expected a blank line but got {blank:?}");
+        }
 
+        let re = regex::Regex::new(r"^(\w{3}) = \((\w{3}), (\w{3})\)$")
+            .expect("Hard coded regex is valid");
         let mut map = HashMap::new();
         for line in lines {
-            map.insert(
-                &line.as_bytes()[..3],
-                [&line.as_bytes()[7..10], &line.as_bytes()[12..15]],
-            );
+            let cap = re
+                .captures(line)
+                .ok_or_else(|| anyhow!("Regex \"{re:?}\" could not capture line {line:?}"))?;
+            let [src, left, right] = [1, 2, 3].map(|i| {
+                cap.get(i)
+                    .expect("Regex has three groups that always match")
+                    .as_str()
+                    .as_bytes()
+            });
+            map.insert(src, [left, right]);
         }
         Ok(Self { directions, map })
     }
@@ -67,6 +80,13 @@ pub fn part_2(input: &str) -> anyhow::Result<usize> {
         .ok_or_else(|| anyhow!("Overflow"))
 }
 
+crate::registry::register_solvers! {
+    2023, 8, "Haunted Wasteland";
+    fingerprint = Fingerprint::new().first_line(r"^[LR]+$");
+    One => part_1,
+    Two => part_2,
+}
+
 #[cfg(test)]
 mod tests {
     use crate::testing::{assert_correct_answer_on_correct_input, assert_error_on_wrong_input};
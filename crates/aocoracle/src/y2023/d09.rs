@@ -1,12 +1,15 @@
+use crate::checked::{ArithmeticError, Checked};
+use crate::fingerprint::Fingerprint;
+use crate::parse::{self, Lines};
 use anyhow::bail;
 use itertools::Itertools;
 
 fn parsed_histories(input: &str) -> anyhow::Result<Vec<Vec<i64>>> {
     let mut histories = vec![];
-    for line in input.lines() {
+    for line in Lines::new(input)? {
         let mut values = vec![];
         for value in line.split(' ') {
-            values.push(value.parse()?);
+            values.push(parse::number(value)?);
         }
         if values.len() < 2 {
             bail!("Expected at least two values per line")
@@ -19,13 +22,17 @@ fn parsed_histories(input: &str) -> anyhow::Result<Vec<Vec<i64>>> {
     Ok(histories)
 }
 
-fn extrapolation(history: &[i64]) -> (i64, i64) {
+fn extrapolation(history: &[i64]) -> Result<(i64, i64), ArithmeticError> {
     let mut diffs = vec![history.to_vec()];
     loop {
         let prev = diffs
             .last()
             .expect("We start with one element and we never remove any elements");
-        let curr: Vec<_> = prev.iter().tuple_windows().map(|(&l, &r)| r - l).collect();
+        let curr: Vec<_> = prev
+            .iter()
+            .tuple_windows()
+            .map(|(&l, &r)| r.try_sub(l))
+            .collect::<Result<_, _>>()?;
         if curr.iter().all(|&v| v == 0) {
             break;
         }
@@ -34,26 +41,42 @@ fn extrapolation(history: &[i64]) -> (i64, i64) {
     let mut before = 0;
     let mut after = 0;
     while let Some(diff) = diffs.pop() {
-        before = -before
-            + diff
-                .first()
-                .expect("We only push vectors with at least one (non-zero) element");
-        after += diff
+        before = diff
+            .first()
+            .expect("We only push vectors with at least one (non-zero) element")
+            .try_sub(before)?;
+        after = diff
             .iter()
             .last()
-            .expect("We only push vectors with at least one (non-zero) element");
+            .expect("We only push vectors with at least one (non-zero) element")
+            .try_add(after)?;
     }
-    (before, after)
+    Ok((before, after))
 }
 
 pub fn part_1(input: &str) -> anyhow::Result<i64> {
     let histories = parsed_histories(input)?;
-    Ok(histories.iter().map(|h| extrapolation(h).1).sum())
+    let mut sum: i64 = 0;
+    for history in histories {
+        sum = sum.try_add(extrapolation(&history)?.1)?;
+    }
+    Ok(sum)
 }
 
 pub fn part_2(input: &str) -> anyhow::Result<i64> {
     let histories = parsed_histories(input)?;
-    Ok(histories.iter().map(|h| extrapolation(h).0).sum())
+    let mut sum: i64 = 0;
+    for history in histories {
+        sum = sum.try_add(extrapolation(&history)?.0)?;
+    }
+    Ok(sum)
+}
+
+crate::registry::register_solvers! {
+    2023, 9, "Mirage Maintenance";
+    fingerprint = Fingerprint::new().alphabet("-0123456789 ");
+    One => part_1,
+    Two => part_2,
 }
 
 #[cfg(test)]
@@ -65,12 +88,12 @@ mod tests {
 
     #[test]
     fn extrapolate_works_on_first_degree_example() {
-        assert_eq!(extrapolation(&[0, 3, 6, 9, 12, 15]), (-3, 18));
+        assert_eq!(extrapolation(&[0, 3, 6, 9, 12, 15]), Ok((-3, 18)));
     }
 
     #[test]
     fn extrapolate_works_on_third_degree_example() {
-        assert_eq!(extrapolation(&[10, 13, 16, 21, 30, 45]), (5, 68));
+        assert_eq!(extrapolation(&[10, 13, 16, 21, 30, 45]), Ok((5, 68)));
     }
 
     #[test]
@@ -98,4 +121,11 @@ mod tests {
     fn returns_error_on_wrong_input() {
         assert_error_on_wrong_input!(part_1, part_2);
     }
+
+    #[test]
+    fn returns_error_on_overflow() {
+        let input = "9223372036854775807 -9223372036854775808\n1 2";
+        assert!(part_1(input).is_err());
+        assert!(part_2(input).is_err());
+    }
 }
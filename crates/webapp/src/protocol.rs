@@ -0,0 +1,23 @@
+/// Messages between the page and the worker that runs the solvers
+use serde::{Deserialize, Serialize};
+
+/// Ask for one solver to be run on one input
+#[derive(Deserialize, Serialize)]
+pub struct Request {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    pub input: String,
+    pub trace: bool,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct Answer {
+    pub value: String,
+    pub source: String,
+    pub confidence: f64,
+    pub trace: Vec<String>,
+}
+
+/// The answer of the solver, or why it did not give one
+pub type Reply = Result<Answer, String>;
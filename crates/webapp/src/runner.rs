@@ -0,0 +1,53 @@
+use wasm_bindgen::{prelude::*, JsCast};
+use web_sys::{ErrorEvent, MessageEvent, Worker};
+use yew::Callback;
+
+use crate::protocol::{Reply, Request};
+
+/// Script that trunk generates for loading the worker binary
+const WORKER_URL: &str = "./worker_loader.js";
+
+/// A worker that runs one solver at a time off the main thread.
+///
+/// Panics abort on wasm32 so a solver that panics takes down the whole instance it runs in. In a
+/// worker that instance is not the page's, so the page can replace the worker and carry on.
+pub struct Runner {
+    worker: Worker,
+    // The worker calls these for as long as it lives
+    _onmessage: Closure<dyn FnMut(MessageEvent)>,
+    _onerror: Closure<dyn FnMut(ErrorEvent)>,
+}
+
+impl Runner {
+    pub fn new(on_reply: Callback<Reply>, on_crash: Callback<String>) -> Result<Self, JsValue> {
+        let worker = Worker::new(WORKER_URL)?;
+        let onmessage = Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+            let reply = match event.data().as_string() {
+                Some(data) => serde_json::from_str(&data).unwrap_or_else(|e| Err(e.to_string())),
+                None => Err("Expected reply to be a string".to_string()),
+            };
+            on_reply.emit(reply);
+        });
+        let onerror = Closure::<dyn FnMut(ErrorEvent)>::new(move |event: ErrorEvent| {
+            on_crash.emit(event.message());
+        });
+        worker.set_onmessage(Some(onmessage.as_ref().unchecked_ref()));
+        worker.set_onerror(Some(onerror.as_ref().unchecked_ref()));
+        Ok(Self {
+            worker,
+            _onmessage: onmessage,
+            _onerror: onerror,
+        })
+    }
+
+    pub fn send(&self, request: &Request) -> Result<(), JsValue> {
+        let request = serde_json::to_string(request).map_err(|e| e.to_string())?;
+        self.worker.post_message(&request.into())
+    }
+}
+
+impl Drop for Runner {
+    fn drop(&mut self) {
+        self.worker.terminate();
+    }
+}
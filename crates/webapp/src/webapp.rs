@@ -1,5 +1,11 @@
+mod protocol;
+mod runner;
 mod textarea;
 
+use std::collections::VecDeque;
+
+use protocol::{Answer, Reply, Request};
+use runner::Runner;
 use yew::prelude::*;
 
 trait ToEnglish {
@@ -16,18 +22,103 @@ impl ToEnglish for aoclib::Part {
     }
 }
 
+type Key = (u16, u8, aoclib::Part);
+
 enum Msg {
     Run,
-    RemoveAnswer,
+    NextAnswer,
     Update(String),
     SetPart(aoclib::Part),
+    SetTrace(bool),
+    Replied(Reply),
+    Crashed(String),
 }
 
 struct Model {
     input: String,
     part: aoclib::Part,
     options_changed: bool,
-    answers: Option<Vec<String>>,
+    answers: Option<Vec<Answer>>,
+    /// Position in the ranking of the answer being shown
+    shown: usize,
+    trace: bool,
+    /// Candidates that are yet to be tried, in stages that are tried only if no earlier stage
+    /// produced an answer
+    pending: VecDeque<VecDeque<Key>>,
+    /// Candidate being tried
+    running: Option<Key>,
+    /// Answers from the stage being tried
+    found: Vec<Answer>,
+    runner: Option<Runner>,
+}
+
+impl Model {
+    fn start(&mut self, ctx: &Context<Self>) -> Result<(), String> {
+        let args = aoclib::Cli::new(None, None, Some(self.part), true);
+        let candidates = aoclib::candidates(&args).map_err(|e| e.to_string())?;
+        self.pending = aoclib::stages(&args, &candidates, &self.input)
+            .into_iter()
+            .map(|stage| {
+                stage
+                    .into_iter()
+                    .map(|s| (s.year(), s.day(), s.part()))
+                    .collect()
+            })
+            .collect();
+        self.found.clear();
+        if self.runner.is_none() {
+            self.runner = Some(self.new_runner(ctx)?);
+        }
+        self.next()
+    }
+
+    fn new_runner(&self, ctx: &Context<Self>) -> Result<Runner, String> {
+        Runner::new(
+            ctx.link().callback(Msg::Replied),
+            ctx.link().callback(Msg::Crashed),
+        )
+        .map_err(|e| format!("{e:?}"))
+    }
+
+    fn describe(&self) -> String {
+        match self.running {
+            Some((year, day, part)) => format!("Year {year} day {day} part {part}"),
+            None => "No candidate".to_string(),
+        }
+    }
+
+    /// Send the next candidate to the worker, or publish the answers if there are none left
+    fn next(&mut self) -> Result<(), String> {
+        self.running = None;
+        while let Some(stage) = self.pending.front_mut() {
+            if let Some(key) = stage.pop_front() {
+                let (year, day, part) = key;
+                let request = Request {
+                    year,
+                    day,
+                    part: part.into(),
+                    input: self.input.clone(),
+                    trace: self.trace,
+                };
+                self.runner
+                    .as_ref()
+                    .expect("Runner is created before the first candidate is sent")
+                    .send(&request)
+                    .map_err(|e| format!("{e:?}"))?;
+                self.running = Some(key);
+                return Ok(());
+            }
+            self.pending.pop_front();
+            if !self.found.is_empty() {
+                self.pending.clear();
+            }
+        }
+        // Same order as `aoclib::helper`, most confident first and stable among equals
+        let mut answers = std::mem::take(&mut self.found);
+        answers.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+        self.answers = Some(answers);
+        Ok(())
+    }
 }
 
 impl Component for Model {
@@ -40,38 +131,72 @@ impl Component for Model {
             part: aoclib::Part::One,
             options_changed: true,
             answers: None,
+            shown: 0,
+            trace: false,
+            pending: VecDeque::new(),
+            running: None,
+            found: Vec::new(),
+            runner: None,
         }
     }
 
-    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
-            Msg::RemoveAnswer => {}
+            Msg::NextAnswer | Msg::Replied(_) | Msg::Crashed(_) => {}
             Msg::Run => self.options_changed = false,
             _ => self.options_changed = true,
         }
-        match msg {
-            Msg::RemoveAnswer => {
-                if let Some(answers) = self.answers.as_mut() {
-                    answers.remove(0);
+        let result = match msg {
+            Msg::NextAnswer => {
+                if self.answers.is_some() {
+                    self.shown += 1;
                 } else {
                     log::error!("This should not be possible in the UI");
                 }
+                Ok(())
             }
             Msg::Run => {
                 self.answers = None;
-                let args = aoclib::Cli::new(None, None, Some(self.part), true);
-                match aoclib::helper(&args, &self.input) {
-                    Ok(answers) => {
-                        self.answers = Some(answers.into_values().collect());
-                    }
-                    Err(error) => {
-                        log::error!("Oracle failed with error {}", error)
+                self.shown = 0;
+                // Replies from an earlier run would otherwise be taken for replies to this one
+                if self.running.is_some() {
+                    self.runner = None;
+                }
+                self.start(ctx)
+            }
+            Msg::Replied(reply) => {
+                match reply {
+                    Ok(answer) => self.found.push(answer),
+                    Err(error) => log::debug!("{} failed with error {}", self.describe(), error),
+                }
+                self.next()
+            }
+            Msg::Crashed(message) => {
+                log::warn!("{} panicked: {}", self.describe(), message);
+                match self.new_runner(ctx) {
+                    Ok(runner) => {
+                        self.runner = Some(runner);
+                        self.next()
                     }
-                };
+                    Err(error) => Err(error),
+                }
+            }
+            Msg::SetPart(part) => {
+                self.part = part;
+                Ok(())
+            }
+            Msg::SetTrace(trace) => {
+                self.trace = trace;
+                Ok(())
+            }
+            Msg::Update(content) => {
+                self.input = content;
+                Ok(())
             }
-            Msg::SetPart(part) => self.part = part,
-            Msg::Update(content) => self.input = content,
         };
+        if let Err(error) = result {
+            log::error!("Oracle failed with error {}", error);
+        }
         true
     }
 
@@ -79,9 +204,14 @@ impl Component for Model {
         let on_change = ctx.link().callback(Msg::Update);
         let answer: Html = {
             if let Some(answers) = &self.answers {
-                if let Some(answer) = answers.iter().next() {
+                if let Some(answer) = answers.get(self.shown) {
                     html! {
-                        <span>{format!( "The answer is {}", answer) }</span>
+                        <span>{format!(
+                            "The answer is {} ({}, {:.0}% confident)",
+                            answer.value,
+                            answer.source,
+                            100.0 * answer.confidence,
+                        ) }</span>
                     }
                 } else {
                     html! {
@@ -94,6 +224,13 @@ impl Component for Model {
                 }
             }
         };
+        let trace: Html = match self.answers.as_ref().and_then(|a| a.get(self.shown)) {
+            Some(answer) if !answer.trace.is_empty() => html! {
+                <pre>{ answer.trace.join("\n\n") }</pre>
+            },
+            _ => html! {},
+        };
+        let trace_enabled = self.trace;
         let button: Html = {
             if self.options_changed || self.answers.is_none() {
                 html!(
@@ -104,7 +241,7 @@ impl Component for Model {
             } else {
                 let answers = self.answers.as_ref().expect("None is handled in if above");
                 html!(
-                        <button onclick={ctx.link().callback(|_| Msg::RemoveAnswer)} disabled={answers.len()<2}>
+                        <button onclick={ctx.link().callback(|_| Msg::NextAnswer)} disabled={answers.len()<=self.shown+1}>
                             { "Tell us another one!" }
                         </button>
                 )
@@ -135,6 +272,15 @@ impl Component for Model {
                         />
                         <span>{ "Part Two" }</span>
                     </label>
+                    <label>
+                        <input
+                            id="checkbox_trace"
+                            type="checkbox"
+                            checked={self.trace}
+                            onclick={ctx.link().callback(move |_| Msg::SetTrace(!trace_enabled))}
+                        />
+                        <span>{ "Explain" }</span>
+                    </label>
                 </div>
                 <div class="row" id="button-row">
                     {button}
@@ -142,6 +288,9 @@ impl Component for Model {
                 <div class="row" id="answer-row">
                     {answer}
                 </div>
+                <div class="row" id="trace-row">
+                    {trace}
+                </div>
             </div>
         }
     }
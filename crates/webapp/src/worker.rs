@@ -0,0 +1,59 @@
+mod protocol;
+
+use protocol::{Answer, Reply, Request};
+use wasm_bindgen::{prelude::*, JsCast};
+use web_sys::{DedicatedWorkerGlobalScope, MessageEvent};
+
+/// Number of seconds after which a candidate is abandoned so that the page stays responsive
+const TIMEOUT: f64 = 5.0;
+
+fn solve(request: &Request) -> Reply {
+    let part = aoclib::Part::try_from(request.part)?;
+    let mut args = aoclib::Cli::new(Some(request.year), Some(request.day), Some(part), true);
+    args.timeout = Some(TIMEOUT);
+    args.trace = request.trace;
+    let solvers = aoclib::candidates(&args).map_err(|e| e.to_string())?;
+    let guess = aoclib::helper(&args, &request.input)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Expected an answer".to_string())?;
+    Ok(Answer {
+        source: format!(
+            "{} day {}: {}{}",
+            guess.year,
+            guess.day,
+            solvers[&(guess.year, guess.day, guess.part)].title(),
+            match guess.mode {
+                aoclib::params::Mode::Real => "",
+                aoclib::params::Mode::Example => " example",
+            }
+        ),
+        value: guess.answer.to_string(),
+        confidence: guess.confidence,
+        trace: guess.trace.iter().map(|e| e.to_string()).collect(),
+    })
+}
+
+fn reply(data: JsValue) -> Reply {
+    let data = data
+        .as_string()
+        .ok_or_else(|| "Expected request to be a string".to_string())?;
+    let request: Request = serde_json::from_str(&data).map_err(|e| e.to_string())?;
+    solve(&request)
+}
+
+fn main() {
+    let scope: DedicatedWorkerGlobalScope = js_sys::global().unchecked_into();
+    let onmessage = {
+        let scope = scope.clone();
+        Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+            // A panic aborts, which ends up as an error event on the page
+            let reply = serde_json::to_string(&reply(event.data())).unwrap_throw();
+            scope.post_message(&reply.into()).unwrap_throw();
+        })
+    };
+    scope.set_onmessage(Some(onmessage.as_ref().unchecked_ref()));
+    // The worker handles messages for as long as it lives
+    onmessage.forget();
+}